@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use crate::result::{
+    base::{CrawlResult, Price},
+    enums::{Category, RetailerName},
+};
+
+/// The same product sold by different retailers, keyed by whatever it was matched on,
+/// ie. "gtin:00029465088736" or "ammo:federal:9mm:115:fmj:50"
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CollectionCanonicalProduct {
+    #[serde(rename = "_id")]
+    pub key: String,
+    pub category: Category,
+    // name of the first listing matched to the product
+    pub name: String,
+    pub attributes: ProductAttributes,
+    pub listings: Vec<CanonicalListing>,
+    pub updated_at: u64,
+}
+
+impl CollectionCanonicalProduct {
+    /// The listing a customer would pay the least for
+    pub fn get_lowest_priced(&self) -> Option<&CanonicalListing> {
+        self.listings.iter().min_by_key(|listing| {
+            listing
+                .price
+                .sale_price
+                .unwrap_or(listing.price.regular_price)
+        })
+    }
+
+    pub fn get_retailer_count(&self) -> usize {
+        let mut retailers: Vec<RetailerName> = self
+            .listings
+            .iter()
+            .map(|listing| listing.retailer)
+            .collect();
+
+        retailers.sort();
+        retailers.dedup();
+
+        retailers.len()
+    }
+}
+
+/// Normalized attributes a product was matched on, ie. "Federal" and
+/// "FEDERAL AMMUNITION" both end up as "federal"
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq)]
+pub struct ProductAttributes {
+    pub gtin: Option<String>,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    pub caliber: Option<String>,
+    pub grains: Option<u64>,
+    pub bullet_type: Option<String>,
+    pub quantity: Option<u64>,
+}
+
+/// A single retailer's listing of a canonical product
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CanonicalListing {
+    pub retailer: RetailerName,
+    pub name: String,
+    pub url: String,
+    pub price: Price,
+    // how sure the matcher is that the listing is the product, from 0 to 1
+    pub confidence: f64,
+}
+
+/// A listing matched to a canonical product, before it's been written
+#[derive(Debug, Clone)]
+pub struct CanonicalLink {
+    pub key: String,
+    pub category: Category,
+    pub attributes: ProductAttributes,
+    pub listing: CanonicalListing,
+}
+
+impl CanonicalLink {
+    pub fn new(
+        key: String,
+        attributes: ProductAttributes,
+        result: &CrawlResult,
+        confidence: f64,
+    ) -> Self {
+        Self {
+            key,
+            category: result.category,
+            attributes,
+            listing: CanonicalListing {
+                retailer: result.retailer,
+                name: result.name.clone(),
+                url: result.url.clone(),
+                price: result.price.clone(),
+                confidence,
+            },
+        }
+    }
+}
@@ -3,3 +3,5 @@ pub const DISCORD_INDEXER_WEBHOOK_URL: &str = "DISCORD_INDEXER_WEBHOOK_URL";
 pub const CLOUDFLARE_TURNSTILE_SECRET_KEY: &str = "CLOUDFLARE_TURNSTILE_SECRET_KEY";
 
 pub const CRAWL_COOLDOWN_SECS: u64 = 10;
+// safety cap, so a retailer returning a garbage page count can't loop forever
+pub const MAX_PAGES_PER_TERM: u64 = 500;
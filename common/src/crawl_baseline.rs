@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::result::enums::{Category, RetailerName};
+
+/// Rolling window of how many results a retailer returned for a category,
+/// used to spot a retailer silently returning nothing after a template change
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CollectionCrawlBaseline {
+    pub retailer: RetailerName,
+    pub category: Category,
+    // oldest first
+    pub counts: Vec<u64>,
+    // consecutive crawls left out of `counts` for dropping off
+    #[serde(default)]
+    pub skipped: u64,
+}
+
+impl CollectionCrawlBaseline {
+    pub fn average(&self) -> Option<f64> {
+        if self.counts.is_empty() {
+            return None;
+        }
+
+        Some(self.counts.iter().sum::<u64>() as f64 / self.counts.len() as f64)
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{result::enums::RetailerName, utils::get_current_time};
+
+/// How far a retailer got through its crawl, saved as the crawl progresses so an
+/// interrupted indexer can pick back up with `--resume` instead of starting over
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CollectionCrawlCheckpoint {
+    pub retailer: RetailerName,
+    // results written since this time belong to the checkpointed crawl
+    pub crawl_start: u64,
+    // next search term to crawl, always 0 for GQL retailers
+    pub term_index: u64,
+    // next page of the term to crawl
+    pub page: u64,
+    pub pagination_token: Option<String>,
+    pub finished: bool,
+    pub updated_at: u64,
+}
+
+impl CollectionCrawlCheckpoint {
+    pub fn new(
+        retailer: RetailerName,
+        crawl_start: u64,
+        term_index: u64,
+        page: u64,
+        pagination_token: Option<String>,
+    ) -> Self {
+        Self {
+            retailer,
+            crawl_start,
+            term_index,
+            page,
+            pagination_token,
+            finished: false,
+            updated_at: get_current_time(),
+        }
+    }
+
+    /// Left behind once a retailer finishes, so resuming skips it entirely
+    pub fn finished(retailer: RetailerName, crawl_start: u64) -> Self {
+        Self {
+            finished: true,
+            ..Self::new(retailer, crawl_start, 0, 0, None)
+        }
+    }
+
+    /// Checkpoints of crawls that started more than `window` ago belong to a
+    /// previous run, and are never resumed from
+    pub fn is_resumable(&self, window: Duration) -> bool {
+        get_current_time().saturating_sub(self.crawl_start) < window.as_secs()
+    }
+}
@@ -0,0 +1,115 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crawl_run::CollectionCrawlRun,
+    result::{
+        base::CrawlResult,
+        enums::{Category, RetailerName},
+    },
+};
+
+/// What changed between a retailer's last live snapshot and a fresh crawl,
+/// prices are the price a customer would actually pay, in cents
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CollectionCrawlDiff {
+    pub run_id: ObjectId,
+    pub retailer: RetailerName,
+    pub crawl_start: u64,
+    pub new_products: Vec<DiffProduct>,
+    pub removed_products: Vec<DiffProduct>,
+    pub price_drops: Vec<PriceChange>,
+    pub price_increases: Vec<PriceChange>,
+    pub sales_started: Vec<PriceChange>,
+    pub sales_ended: Vec<PriceChange>,
+}
+
+impl CollectionCrawlDiff {
+    pub fn new(crawl_run: &CollectionCrawlRun, retailer: RetailerName, crawl_start: u64) -> Self {
+        Self {
+            run_id: crawl_run.id,
+            retailer,
+            crawl_start,
+            new_products: Vec::new(),
+            removed_products: Vec::new(),
+            price_drops: Vec::new(),
+            price_increases: Vec::new(),
+            sales_started: Vec::new(),
+            sales_ended: Vec::new(),
+        }
+    }
+
+    pub fn get_summary(&self) -> CrawlDiffSummary {
+        CrawlDiffSummary {
+            new_products: self.new_products.len() as u64,
+            removed_products: self.removed_products.len() as u64,
+            price_drops: self.price_drops.len() as u64,
+            price_increases: self.price_increases.len() as u64,
+            sales_started: self.sales_started.len() as u64,
+            sales_ended: self.sales_ended.len() as u64,
+        }
+    }
+}
+
+/// Just the counts of a `CollectionCrawlDiff`, small enough for reports
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy)]
+pub struct CrawlDiffSummary {
+    pub new_products: u64,
+    pub removed_products: u64,
+    pub price_drops: u64,
+    pub price_increases: u64,
+    pub sales_started: u64,
+    pub sales_ended: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DiffProduct {
+    pub name: String,
+    pub url: String,
+    pub category: Category,
+    pub price: u64,
+}
+
+impl From<&CrawlResult> for DiffProduct {
+    fn from(result: &CrawlResult) -> Self {
+        Self {
+            name: result.name.clone(),
+            url: result.url.clone(),
+            category: result.category,
+            price: result
+                .price
+                .sale_price
+                .unwrap_or(result.price.regular_price),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PriceChange {
+    pub name: String,
+    pub url: String,
+    pub category: Category,
+    pub old_price: u64,
+    pub new_price: u64,
+    // negative for a price drop
+    pub change: i64,
+    pub change_percent: f64,
+}
+
+impl PriceChange {
+    pub fn new(previous: &CrawlResult, current: &CrawlResult) -> Self {
+        let old_price = DiffProduct::from(previous).price;
+        let new_price = DiffProduct::from(current).price;
+        let change = new_price as i64 - old_price as i64;
+
+        Self {
+            name: current.name.clone(),
+            url: current.url.clone(),
+            category: current.category,
+            old_price,
+            new_price,
+            change,
+            change_percent: 100.0 * change as f64 / old_price.max(1) as f64,
+        }
+    }
+}
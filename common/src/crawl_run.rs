@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crawl_diff::CrawlDiffSummary,
+    result::{
+        counts::ResultCounts,
+        enums::{Category, RetailerName},
+    },
+    utils::get_current_time,
+};
+
+/// A single indexer run, retailers are pushed in as they finish so a
+/// run that never ends still shows how far it got
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CollectionCrawlRun {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub start_time: u64,
+    pub end_time: Option<u64>,
+    // CLI args the indexer was started with
+    pub args: Vec<String>,
+    pub retailers: Vec<CrawlRunRetailer>,
+}
+
+impl CollectionCrawlRun {
+    pub fn new(args: Vec<String>) -> Self {
+        Self {
+            id: ObjectId::new(),
+            start_time: get_current_time(),
+            end_time: None,
+            args,
+            retailers: Vec::new(),
+        }
+    }
+}
+
+/// How a single retailer's crawl went during a run
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CrawlRunRetailer {
+    pub retailer: RetailerName,
+    pub start_time: u64,
+    pub end_time: u64,
+    // start of the crawl whose results were written, before `start_time` when it was resumed
+    #[serde(default)]
+    pub crawl_start: Option<u64>,
+    pub succeeded: bool,
+    // finished earlier in an interrupted run, so nothing was crawled this time
+    #[serde(default)]
+    pub skipped: bool,
+    // cancelled for running past its time or request budget
+    #[serde(default)]
+    pub timed_out: bool,
+    // stopped part way through by the indexer shutting down
+    #[serde(default)]
+    pub interrupted: bool,
+    pub pages_fetched: u64,
+    pub counts: ResultCounts,
+    // share of ammunition with a round count, copied out of `counts` for dashboards
+    pub ammunition_metadata_coverage: Option<f64>,
+    // errors that failed the crawl
+    pub errors: Vec<String>,
+    // products that failed to parse, but didn't fail the crawl
+    pub skipped_products: u64,
+    // compared against the previous live snapshot, only set for successful crawls
+    pub changes: Option<CrawlDiffSummary>,
+    // products the rules moved or dropped, per rule
+    #[serde(default)]
+    pub rule_counts: BTreeMap<String, u64>,
+    // the first few products the rules moved or dropped, the rest only show up in `rule_counts`
+    #[serde(default)]
+    pub rule_decisions: Vec<CrawlRuleDecision>,
+}
+
+impl CrawlRunRetailer {
+    pub fn new(retailer: RetailerName, start_time: u64) -> Self {
+        Self {
+            retailer,
+            start_time,
+            end_time: start_time,
+            crawl_start: None,
+            succeeded: false,
+            skipped: false,
+            timed_out: false,
+            interrupted: false,
+            pages_fetched: 0,
+            counts: ResultCounts::new(),
+            ammunition_metadata_coverage: None,
+            errors: Vec::new(),
+            skipped_products: 0,
+            changes: None,
+            rule_counts: BTreeMap::new(),
+            rule_decisions: Vec::new(),
+        }
+    }
+
+    pub fn with_counts(self, counts: ResultCounts) -> Self {
+        Self {
+            counts,
+            ammunition_metadata_coverage: counts.get_metadata_coverage(),
+            ..self
+        }
+    }
+}
+
+/// A result the rules moved to another category or dropped
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CrawlRuleDecision {
+    pub rule: String,
+    pub name: String,
+    pub url: String,
+    pub from: Category,
+    // not set when the result was dropped
+    pub to: Option<Category>,
+}
@@ -1,8 +1,16 @@
+pub mod canonical_product;
 pub mod constants;
+pub mod crawl_baseline;
+pub mod crawl_checkpoint;
+pub mod crawl_diff;
+pub mod crawl_run;
 pub mod deserialize_disallow_empty_string;
 pub mod image_cache;
 pub mod messages;
+pub mod money;
 pub mod price_history;
 pub mod result;
+pub mod retailer_info;
+pub mod run_lock;
 pub mod search_params;
 pub mod utils;
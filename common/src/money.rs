@@ -0,0 +1,490 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display as StrumDisplay, EnumIter, EnumString};
+use thiserror::Error;
+
+// non-breaking spaces show up as thousands separators in
+// French Canadian pricing, ie. "1 234,56 $"
+const GROUPING_CHARACTERS: [char; 5] = [' ', '\u{a0}', '\u{202f}', '\'', '\u{2019}'];
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MoneyError {
+    #[error("Price is empty")]
+    Empty,
+    #[error("Price is not in a known format: {0}")]
+    InvalidFormat(String),
+    #[error("Price cannot be negative: {0}")]
+    Negative(String),
+    #[error("Price is too large to represent in cents: {0}")]
+    Overflow(String),
+    #[error("Price contains more than one currency: {0}")]
+    AmbiguousCurrency(String),
+    #[error("Cannot mix currencies: {0} and {1}")]
+    CurrencyMismatch(Currency, Currency),
+    #[error("Exchange rate is not in the format CUR=RATE: {0}")]
+    InvalidExchangeRate(String),
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Hash,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Deserialize,
+    Serialize,
+    EnumString,
+    EnumIter,
+    StrumDisplay,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Currency {
+    #[default]
+    CAD,
+    USD,
+    EUR,
+    GBP,
+}
+
+impl Currency {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Currency::CAD | Currency::USD => "$",
+            Currency::EUR => "€",
+            Currency::GBP => "£",
+        }
+    }
+
+    /// Markers that unambiguously identify a currency inside of a price string,
+    /// ordered so that longer markers are stripped before their substrings
+    fn markers(&self) -> &'static [&'static str] {
+        match self {
+            Currency::CAD => &["CAD", "CA$", "C$"],
+            Currency::USD => &["USD", "US$"],
+            Currency::EUR => &["EUR", "€"],
+            Currency::GBP => &["GBP", "£"],
+        }
+    }
+}
+
+/// An amount of money in integer cents alongside its currency.
+///
+/// Cents are unsigned since we never deal with negative prices.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Money {
+    cents: u64,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(cents: u64, currency: Currency) -> Self {
+        Self { cents, currency }
+    }
+
+    pub fn cad(cents: u64) -> Self {
+        Self::new(cents, Currency::CAD)
+    }
+
+    pub fn cents(&self) -> u64 {
+        self.cents
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Parses a price string, assuming CAD when the string does
+    /// not say otherwise. See `Money::parse_with_default` for details.
+    pub fn parse(input: &str) -> Result<Self, MoneyError> {
+        Self::parse_with_default(input, Currency::default())
+    }
+
+    /// Parses a price string into cents, capable of dealing with:
+    /// 1. "$1,234.56", "1234.56", "12.5", ".99"
+    /// 2. "1 234,56 $", "1.234,56", "12,5 $"
+    /// 3. "CAD 12.99", "12.99 CAD", "C$12.99", "US$ 12.99", "€12,99"
+    ///
+    /// A bare "$" is ambiguous, `default_currency` is used in that case.
+    /// Any sub-cent digits are rounded half up. A lone "." followed by three
+    /// digits is read as a decimal, a lone "," followed by three as grouping.
+    pub fn parse_with_default(input: &str, default_currency: Currency) -> Result<Self, MoneyError> {
+        let trimmed = input.trim();
+
+        if trimmed.is_empty() {
+            return Err(MoneyError::Empty);
+        }
+
+        let (mut number, currency) = Self::strip_currency(trimmed)?;
+
+        number = number
+            .replace('$', "")
+            .replace(GROUPING_CHARACTERS, "")
+            .trim()
+            .to_string();
+
+        if number.starts_with('-') {
+            return Err(MoneyError::Negative(input.to_string()));
+        }
+
+        if number.is_empty()
+            || !number
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+        {
+            return Err(MoneyError::InvalidFormat(input.to_string()));
+        }
+
+        let (dollars, fraction) = Self::split_decimal(&number);
+
+        if !dollars.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+            || (dollars.is_empty() && fraction.is_empty())
+        {
+            return Err(MoneyError::InvalidFormat(input.to_string()));
+        }
+
+        let parsed_dollars = match dollars.is_empty() {
+            true => 0,
+            false => dollars
+                .parse::<u64>()
+                .map_err(|_| MoneyError::Overflow(input.to_string()))?,
+        };
+
+        let cents = parsed_dollars
+            .checked_mul(100)
+            .and_then(|cents| cents.checked_add(Self::fraction_to_cents(&fraction)))
+            .ok_or_else(|| MoneyError::Overflow(input.to_string()))?;
+
+        Ok(Self::new(cents, currency.unwrap_or(default_currency)))
+    }
+
+    /// API responses like to hand out prices as floats, going through
+    /// `f32::to_string()` turns 12.5 into "12.5" which is easy to get wrong,
+    /// and float math turns 12.99 into 1298.9999, so widen and round instead
+    pub fn from_f32(value: f32, currency: Currency) -> Result<Self, MoneyError> {
+        Self::from_f64(value as f64, currency)
+    }
+
+    pub fn from_f64(value: f64, currency: Currency) -> Result<Self, MoneyError> {
+        if !value.is_finite() {
+            return Err(MoneyError::InvalidFormat(value.to_string()));
+        }
+
+        if value < 0.0 {
+            return Err(MoneyError::Negative(value.to_string()));
+        }
+
+        let cents = (value * 100.0).round();
+
+        if cents > u64::MAX as f64 {
+            return Err(MoneyError::Overflow(value.to_string()));
+        }
+
+        Ok(Self::new(cents as u64, currency))
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.ensure_same_currency(other)?;
+
+        let cents = self
+            .cents
+            .checked_add(other.cents)
+            .ok_or_else(|| MoneyError::Overflow(format!("{self} + {other}")))?;
+
+        Ok(Self::new(cents, self.currency))
+    }
+
+    /// Returns `None` if the result would be negative
+    pub fn checked_sub(self, other: Money) -> Result<Option<Money>, MoneyError> {
+        self.ensure_same_currency(other)?;
+
+        Ok(self
+            .cents
+            .checked_sub(other.cents)
+            .map(|cents| Self::new(cents, self.currency)))
+    }
+
+    pub fn checked_mul(self, quantity: u64) -> Result<Money, MoneyError> {
+        let cents = self
+            .cents
+            .checked_mul(quantity)
+            .ok_or_else(|| MoneyError::Overflow(format!("{self} * {quantity}")))?;
+
+        Ok(Self::new(cents, self.currency))
+    }
+
+    /// Integer division, rounded half up. Returns `None` when dividing by zero.
+    pub fn checked_div(self, divisor: u64) -> Option<Money> {
+        if divisor == 0 {
+            return None;
+        }
+
+        let cents = self.cents / divisor + u64::from(self.cents % divisor * 2 >= divisor);
+
+        Some(Self::new(cents, self.currency))
+    }
+
+    /// Converts into `currency` by multiplying by `rate`, rounding to the nearest cent.
+    /// `rate` is the amount of `currency` one unit of the current currency buys.
+    pub fn convert(self, currency: Currency, rate: f64) -> Result<Money, MoneyError> {
+        if self.currency == currency {
+            return Ok(self);
+        }
+
+        Self::from_f64(self.cents as f64 * rate / 100.0, currency)
+    }
+
+    fn ensure_same_currency(&self, other: Money) -> Result<(), MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch(self.currency, other.currency));
+        }
+
+        Ok(())
+    }
+
+    fn strip_currency(input: &str) -> Result<(String, Option<Currency>), MoneyError> {
+        let mut number = input.to_uppercase();
+        let mut found: Option<Currency> = None;
+
+        for currency in Currency::iter() {
+            for marker in currency.markers() {
+                if !number.contains(marker) {
+                    continue;
+                }
+
+                if found.is_some_and(|existing| existing != currency) {
+                    return Err(MoneyError::AmbiguousCurrency(input.to_string()));
+                }
+
+                found = Some(currency);
+                number = number.replace(marker, "");
+            }
+        }
+
+        Ok((number, found))
+    }
+
+    /// Figures out which of "," or "." is the decimal separator
+    /// and returns the (dollars, fraction) parts without any grouping
+    fn split_decimal(number: &str) -> (String, String) {
+        let last_comma = number.rfind(',');
+        let last_dot = number.rfind('.');
+
+        let decimal_index = match (last_comma, last_dot) {
+            // "1,234.56" or "1.234,56", whichever comes last is the decimal
+            (Some(comma), Some(dot)) => Some(comma.max(dot)),
+            (Some(index), None) | (None, Some(index)) => {
+                let character = number[index..].chars().next().unwrap_or_default();
+                let digits_after = number.len() - index - 1;
+
+                match number.matches(character).count() {
+                    // "12.5" or "12,50" can only be a decimal, "1,234" is
+                    // grouping since none of the retailers write decimals with a ","
+                    1 if digits_after != 3 || character == '.' => Some(index),
+                    // "1.234.567" or "1,234"
+                    _ => None,
+                }
+            }
+            (None, None) => None,
+        };
+
+        let Some(index) = decimal_index else {
+            return (number.replace([',', '.'], ""), String::new());
+        };
+
+        let (dollars, fraction) = number.split_at(index);
+
+        (dollars.replace([',', '.'], ""), fraction[1..].to_string())
+    }
+
+    fn fraction_to_cents(fraction: &str) -> u64 {
+        let digits: Vec<u64> = fraction
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(u64::from)
+            .collect();
+
+        let tens = digits.first().copied().unwrap_or(0);
+        let ones = digits.get(1).copied().unwrap_or(0);
+        let round_up = digits.get(2).is_some_and(|digit| *digit >= 5);
+
+        tens * 10 + ones + u64::from(round_up)
+    }
+}
+
+/// A locally supplied exchange rate into CAD, parsed from "USD=1.37"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExchangeRate {
+    pub currency: Currency,
+    pub rate: f64,
+}
+
+impl FromStr for ExchangeRate {
+    type Err = MoneyError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || MoneyError::InvalidExchangeRate(input.to_string());
+
+        let Some((currency, rate)) = input.split_once('=') else {
+            return Err(invalid());
+        };
+
+        let currency = Currency::from_str(currency.trim()).map_err(|_| invalid())?;
+        let rate = rate.trim().parse::<f64>().map_err(|_| invalid())?;
+
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(invalid());
+        }
+
+        Ok(Self { currency, rate })
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, format: &mut Formatter) -> FmtResult {
+        let dollars = (self.cents / 100).to_string();
+
+        let mut grouped = String::new();
+
+        for (index, digit) in dollars.chars().enumerate() {
+            if index > 0 && (dollars.len() - index).is_multiple_of(3) {
+                grouped.push(',');
+            }
+
+            grouped.push(digit);
+        }
+
+        write!(
+            format,
+            "{}{}.{:02} {}",
+            self.currency.symbol(),
+            grouped,
+            self.cents % 100,
+            self.currency
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> u64 {
+        Money::parse(input).unwrap().cents()
+    }
+
+    #[test]
+    fn parses_point_decimals() {
+        assert_eq!(parse("$1,234.56"), 123456);
+        assert_eq!(parse("1234.56"), 123456);
+        assert_eq!(parse("12.5"), 1250);
+        assert_eq!(parse(".99"), 99);
+        assert_eq!(parse("1,234,567.89"), 123456789);
+        assert_eq!(parse("$1,234"), 123400);
+    }
+
+    #[test]
+    fn parses_comma_decimals() {
+        assert_eq!(parse("1 234,56 $"), 123456);
+        assert_eq!(parse("1\u{a0}234,56 $"), 123456);
+        assert_eq!(parse("1.234,56"), 123456);
+        assert_eq!(parse("12,5 $"), 1250);
+        assert_eq!(parse("1.234.567"), 123456700);
+    }
+
+    #[test]
+    fn reads_lone_three_digit_group() {
+        assert_eq!(parse("1.234"), 123);
+        assert_eq!(parse("1,234"), 123400);
+    }
+
+    #[test]
+    fn parses_currencies() {
+        let cases = [
+            ("CAD 12.99", Currency::CAD),
+            ("12.99 CAD", Currency::CAD),
+            ("C$12.99", Currency::CAD),
+            ("US$ 12.99", Currency::USD),
+            ("€12,99", Currency::EUR),
+            ("£12.99", Currency::GBP),
+        ];
+
+        for (input, currency) in cases {
+            assert_eq!(
+                Money::parse(input),
+                Ok(Money::new(1299, currency)),
+                "{input}"
+            );
+        }
+
+        assert_eq!(
+            Money::parse_with_default("$12.99", Currency::USD),
+            Ok(Money::new(1299, Currency::USD))
+        );
+    }
+
+    #[test]
+    fn rounds_sub_cent_digits() {
+        assert_eq!(parse("12.345"), 1235);
+        assert_eq!(parse("12.344"), 1234);
+        assert_eq!(parse("0.995"), 100);
+    }
+
+    #[test]
+    fn rejects_invalid_prices() {
+        assert_eq!(Money::parse("  "), Err(MoneyError::Empty));
+        assert!(matches!(
+            Money::parse("-12.99"),
+            Err(MoneyError::Negative(_))
+        ));
+        assert!(matches!(
+            Money::parse("$"),
+            Err(MoneyError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            Money::parse("12.99 each"),
+            Err(MoneyError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            Money::parse("USD 12.99 CAD"),
+            Err(MoneyError::AmbiguousCurrency(_))
+        ));
+        assert!(matches!(
+            Money::parse("99999999999999999999"),
+            Err(MoneyError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn formats_with_grouping() {
+        assert_eq!(Money::cad(123456789).to_string(), "$1,234,567.89 CAD");
+        assert_eq!(Money::new(5, Currency::EUR).to_string(), "€0.05 EUR");
+    }
+
+    #[test]
+    fn parses_floats_without_drift() {
+        assert_eq!(Money::from_f32(12.99, Currency::CAD).unwrap().cents(), 1299);
+        assert_eq!(
+            Money::from_f64(0.1 + 0.2, Currency::CAD).unwrap().cents(),
+            30
+        );
+        assert!(Money::from_f64(f64::NAN, Currency::CAD).is_err());
+    }
+}
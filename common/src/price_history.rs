@@ -14,8 +14,12 @@ pub struct ApiPriceHistoryOutput {
     pub min_price: PriceHistoryEntry,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct CollectionPriceHistory {
+    // missing on history written before products had IDs, until it's migrated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_id: Option<String>,
+    // the latest name and URL seen for the product
     pub name: String,
     pub url: String,
     pub price_history: Vec<PriceHistoryEntry>,
@@ -9,9 +9,11 @@ use tracing::error;
 
 use crate::result::metadata::Ammunition;
 use crate::{
+    money::{Currency, Money, MoneyError},
     result::{
         enums::{Category, RetailerName},
         metadata::Metadata,
+        taxonomy::TaxonomyNode,
     },
     utils::get_current_time,
 };
@@ -25,15 +27,64 @@ static PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     ]
 });
 
-#[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 pub struct Price {
     pub regular_price: u64,
     pub sale_price: Option<u64>,
+    // older documents predate currencies, and were all CAD
+    #[serde(default)]
+    pub currency: Currency,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+impl Price {
+    pub fn new(regular_price: Money) -> Self {
+        Self {
+            regular_price: regular_price.cents(),
+            sale_price: None,
+            currency: regular_price.currency(),
+        }
+    }
+
+    /// The sale price is expected to be in the same currency as the regular price,
+    /// a mismatch means the retailer is showing us something we can't trust
+    pub fn with_sale_price(mut self, sale_price: Money) -> Self {
+        if sale_price.currency() != self.currency {
+            error!(
+                "Sale price {sale_price} does not match regular price currency {}, ignoring sale",
+                self.currency
+            );
+            return self;
+        }
+
+        self.sale_price = Some(sale_price.cents());
+        self
+    }
+
+    pub fn regular(&self) -> Money {
+        Money::new(self.regular_price, self.currency)
+    }
+
+    pub fn sale(&self) -> Option<Money> {
+        self.sale_price
+            .map(|sale_price| Money::new(sale_price, self.currency))
+    }
+
+    pub fn convert(&self, currency: Currency, rate: f64) -> Result<Price, MoneyError> {
+        let mut price = Price::new(self.regular().convert(currency, rate)?);
+
+        if let Some(sale_price) = self.sale() {
+            price = price.with_sale_price(sale_price.convert(currency, rate)?);
+        }
+
+        Ok(price)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CrawlResult {
     #[serde(rename(deserialize = "_id"))]
+    // results exported by the indexer don't have an ID yet
+    #[serde(default)]
     // TODO: this might break things if someone was to populate `id` accidentially
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(deserialize_with = "object_id_to_string")]
@@ -44,9 +95,23 @@ pub struct CrawlResult {
     pub query_time: u64,
     pub retailer: RetailerName,
     pub category: Category,
+    // path down the taxonomy, ie. [optics, rifleScopes], stored
+    // as a path so filtering on a parent node also finds its children
+    #[serde(default)]
+    pub taxonomy: Vec<TaxonomyNode>,
     pub description: Option<String>,
     pub image_url: Option<String>,
     pub metadata: Option<Metadata>,
+    // the retailer's own ID for the product, ie. a SKU or database ID
+    #[serde(default)]
+    pub sku: Option<String>,
+    // stays the same through renames and URL changes, set once the URL is canonical
+    #[serde(default)]
+    pub product_id: Option<String>,
+    // listed under the same URL as the product's other variants, only
+    // needed until the product ID is set, so it's never stored
+    #[serde(skip)]
+    pub is_variant: bool,
 }
 
 fn object_id_to_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
@@ -99,10 +164,7 @@ impl CrawlResult {
         // TODO: find a better way to fix the product pricing
         // in the case where both sale and price are the same
         let fixed_price = match price.regular_price == price.sale_price.unwrap_or_default() {
-            true => Price {
-                regular_price: price.regular_price,
-                sale_price: None,
-            },
+            true => Price::new(price.regular()),
             false => price,
         };
 
@@ -119,9 +181,13 @@ impl CrawlResult {
             query_time: time,
             retailer,
             category,
+            taxonomy: Vec::new(),
             description: None,
             image_url: None,
             metadata,
+            sku: None,
+            product_id: None,
+            is_variant: false,
         }
     }
 
@@ -135,11 +201,54 @@ impl CrawlResult {
         self
     }
 
+    /// Nodes that don't belong to the result's category are ignored
+    pub fn with_taxonomy(mut self, node: TaxonomyNode) -> Self {
+        if node.category() == self.category {
+            self.taxonomy = node.path();
+        }
+
+        self
+    }
+
+    pub fn get_taxonomy_node(&self) -> Option<TaxonomyNode> {
+        self.taxonomy.last().copied()
+    }
+
     pub fn with_metadata(mut self, metadata: Metadata) -> Self {
         self.metadata = Some(metadata);
         self
     }
 
+    pub fn with_sku(mut self, sku: impl Into<String>) -> Self {
+        self.sku = Some(sku.into());
+        self
+    }
+
+    /// For variants that share the product's URL without an ID of their own,
+    /// which are told apart by their name instead
+    pub fn as_variant(mut self) -> Self {
+        self.is_variant = true;
+        self
+    }
+
+    /// The retailer's own ID when it has one, otherwise the URL, which should already be
+    /// canonical. `include_name` is for variants that share a URL but have no ID of their own
+    pub fn get_product_id(&self, include_name: bool) -> String {
+        match (&self.sku, include_name) {
+            (Some(sku), _) => format!("{}:sku:{sku}", self.retailer),
+            (None, false) => Self::get_url_product_id(self.retailer, &self.url),
+            (None, true) => format!(
+                "{}#{}",
+                Self::get_url_product_id(self.retailer, &self.url),
+                self.name
+            ),
+        }
+    }
+
+    pub fn get_url_product_id(retailer: RetailerName, url: &str) -> String {
+        format!("{retailer}:url:{url}")
+    }
+
     fn get_ammo_metadata(product_name: &String) -> Option<Metadata> {
         for pattern in PATTERNS.iter() {
             if let Some(capture) = pattern.captures(product_name) {
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::result::{base::CrawlResult, enums::Category};
+
+/// Running per-category counts of a retailer's results, kept up to date as results
+/// are streamed out, so nothing has to hold onto the results themselves
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
+pub struct ResultCounts {
+    pub firearms: u64,
+    pub ammunition: u64,
+    pub ammunition_with_metadata: u64,
+    pub other: u64,
+}
+
+impl ResultCounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, category: Category, has_metadata: bool) {
+        match category {
+            Category::Firearm => self.firearms += 1,
+            Category::Ammunition => {
+                self.ammunition += 1;
+
+                if has_metadata {
+                    self.ammunition_with_metadata += 1;
+                }
+            }
+            Category::Other => self.other += 1,
+            Category::_All => {}
+        }
+    }
+
+    /// Undoes a previous `add`, used when a result is replaced by a better ranked duplicate
+    pub fn remove(&mut self, category: Category, has_metadata: bool) {
+        match category {
+            Category::Firearm => self.firearms = self.firearms.saturating_sub(1),
+            Category::Ammunition => {
+                self.ammunition = self.ammunition.saturating_sub(1);
+
+                if has_metadata {
+                    self.ammunition_with_metadata = self.ammunition_with_metadata.saturating_sub(1);
+                }
+            }
+            Category::Other => self.other = self.other.saturating_sub(1),
+            Category::_All => {}
+        }
+    }
+
+    pub fn add_result(&mut self, result: &CrawlResult) {
+        self.add(result.category, result.metadata.is_some());
+    }
+
+    pub fn get(&self, category: Category) -> u64 {
+        match category {
+            Category::Firearm => self.firearms,
+            Category::Ammunition => self.ammunition,
+            Category::Other => self.other,
+            Category::_All => self.get_total(),
+        }
+    }
+
+    pub fn get_total(&self) -> u64 {
+        self.firearms + self.ammunition + self.other
+    }
+
+    /// Share of ammunition with metadata (ie. round count), `None` without any ammunition
+    pub fn get_metadata_coverage(&self) -> Option<f64> {
+        if self.ammunition == 0 {
+            return None;
+        }
+
+        Some(self.ammunition_with_metadata as f64 / self.ammunition as f64)
+    }
+}
@@ -1,9 +1,9 @@
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result};
-use strum_macros::EnumString;
+use strum_macros::{EnumIter, EnumString};
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, ValueEnum)]
 #[serde(rename_all = "camelCase")]
 pub enum Category {
     Firearm,
@@ -11,6 +11,7 @@ pub enum Category {
     Other,
     #[default]
     #[serde(rename = "all")]
+    #[value(skip)]
     _All,
 }
 
@@ -33,6 +34,7 @@ impl Display for Category {
     Ord,
     PartialOrd,
     EnumString,
+    EnumIter,
 )]
 pub enum RetailerName {
     ReliableGun,
@@ -67,13 +69,13 @@ pub enum RetailerName {
     BartonsBigCountry,
 }
 
-impl RetailerName {
-    pub fn to_string(&self) -> String {
-        format!("{self:?}")
+impl Display for RetailerName {
+    fn fmt(&self, format: &mut Formatter) -> Result {
+        write!(format, "{self:?}")
     }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub enum ActionType {
     SemiAuto,
     LeverAction,
@@ -88,20 +90,20 @@ pub enum ActionType {
     MuzzleLoader,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub enum AmmunitionType {
     CenterFire,
     Rimfire,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub enum FirearmClass {
     NonRestricted,
     Restricted,
     Prohibited,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub enum FirearmType {
     Rifle,
     Shotgun,
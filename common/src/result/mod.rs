@@ -1,3 +1,5 @@
 pub mod base;
+pub mod counts;
 pub mod enums;
 pub mod metadata;
+pub mod taxonomy;
@@ -0,0 +1,291 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display as StrumDisplay, EnumIter};
+
+use crate::result::enums::Category;
+
+/// Subcategories that sit underneath the top level `Category`,
+/// ie. Other -> Optics -> Rifle Scopes
+#[derive(
+    Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, EnumIter, StrumDisplay,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum TaxonomyNode {
+    // firearms
+    Rifles,
+    Shotguns,
+    Handguns,
+    Airguns,
+    // ammunition
+    RifleAmmunition,
+    ShotgunAmmunition,
+    HandgunAmmunition,
+    RimfireAmmunition,
+    // other
+    Optics,
+    RifleScopes,
+    RedDots,
+    Binoculars,
+    Magazines,
+    Parts,
+    Reloading,
+    Brass,
+    Bullets,
+    Powder,
+    Primers,
+    Apparel,
+    Cleaning,
+    Storage,
+    Protection,
+    Accessories,
+}
+
+// matched in order, a phrase claims the words it matched so "scope mounts" never
+// counts as "scope", keep the more specific phrases first. A term matching
+// multiple nodes gets mapped to the closest node they have in common
+const KEYWORDS: &[(&str, TaxonomyNode)] = &[
+    ("air gun", TaxonomyNode::Airguns),
+    ("airgun", TaxonomyNode::Airguns),
+    ("rifle", TaxonomyNode::Rifles),
+    ("shotgun", TaxonomyNode::Shotguns),
+    ("pistol", TaxonomyNode::Handguns),
+    ("handgun", TaxonomyNode::Handguns),
+    ("revolver", TaxonomyNode::Handguns),
+    ("rifle", TaxonomyNode::RifleAmmunition),
+    ("centerfire", TaxonomyNode::RifleAmmunition),
+    ("centrefire", TaxonomyNode::RifleAmmunition),
+    ("shotgun", TaxonomyNode::ShotgunAmmunition),
+    ("shotshell", TaxonomyNode::ShotgunAmmunition),
+    ("pistol", TaxonomyNode::HandgunAmmunition),
+    ("handgun", TaxonomyNode::HandgunAmmunition),
+    ("rimfire", TaxonomyNode::RimfireAmmunition),
+    ("scope mount", TaxonomyNode::Accessories),
+    ("scope ring", TaxonomyNode::Accessories),
+    ("red dot", TaxonomyNode::RedDots),
+    ("eye protection", TaxonomyNode::Protection),
+    ("ear protection", TaxonomyNode::Protection),
+    ("hearing protection", TaxonomyNode::Protection),
+    ("safety glasses", TaxonomyNode::Protection),
+    ("cleaning", TaxonomyNode::Cleaning),
+    ("optic", TaxonomyNode::Optics),
+    ("scope", TaxonomyNode::RifleScopes),
+    ("binocular", TaxonomyNode::Binoculars),
+    ("magazine", TaxonomyNode::Magazines),
+    ("part", TaxonomyNode::Parts),
+    ("reloading", TaxonomyNode::Reloading),
+    ("brass", TaxonomyNode::Brass),
+    ("bullet", TaxonomyNode::Bullets),
+    ("powder", TaxonomyNode::Powder),
+    ("primer", TaxonomyNode::Primers),
+    ("apparel", TaxonomyNode::Apparel),
+    ("clothing", TaxonomyNode::Apparel),
+    ("case", TaxonomyNode::Storage),
+    ("safe", TaxonomyNode::Storage),
+    ("storage", TaxonomyNode::Storage),
+    ("hearing", TaxonomyNode::Protection),
+    ("accessory", TaxonomyNode::Accessories),
+    ("accessories", TaxonomyNode::Accessories),
+];
+
+/// Whole words only, with an optional plural, so "case" doesn't match "showcase"
+static KEYWORD_PATTERNS: LazyLock<Vec<(Regex, TaxonomyNode)>> = LazyLock::new(|| {
+    KEYWORDS
+        .iter()
+        .map(|(keyword, node)| {
+            let pattern = format!(r"\b{}(s|es)?\b", regex::escape(keyword));
+
+            (Regex::new(&pattern).unwrap(), *node)
+        })
+        .collect()
+});
+
+impl TaxonomyNode {
+    pub fn parent(&self) -> Option<TaxonomyNode> {
+        match self {
+            Self::RifleScopes | Self::RedDots | Self::Binoculars => Some(Self::Optics),
+            Self::Brass | Self::Bullets | Self::Powder | Self::Primers => Some(Self::Reloading),
+            _ => None,
+        }
+    }
+
+    pub fn category(&self) -> Category {
+        match self {
+            Self::Rifles | Self::Shotguns | Self::Handguns | Self::Airguns => Category::Firearm,
+            Self::RifleAmmunition
+            | Self::ShotgunAmmunition
+            | Self::HandgunAmmunition
+            | Self::RimfireAmmunition => Category::Ammunition,
+            _ => Category::Other,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Rifles => "Rifles",
+            Self::Shotguns => "Shotguns",
+            Self::Handguns => "Handguns",
+            Self::Airguns => "Airguns",
+            Self::RifleAmmunition => "Rifle Ammunition",
+            Self::ShotgunAmmunition => "Shotgun Ammunition",
+            Self::HandgunAmmunition => "Handgun Ammunition",
+            Self::RimfireAmmunition => "Rimfire Ammunition",
+            Self::Optics => "Optics",
+            Self::RifleScopes => "Rifle Scopes",
+            Self::RedDots => "Red Dots",
+            Self::Binoculars => "Binoculars",
+            Self::Magazines => "Magazines",
+            Self::Parts => "Parts",
+            Self::Reloading => "Reloading",
+            Self::Brass => "Brass",
+            Self::Bullets => "Bullets",
+            Self::Powder => "Powder",
+            Self::Primers => "Primers",
+            Self::Apparel => "Apparel",
+            Self::Cleaning => "Cleaning",
+            Self::Storage => "Cases & Storage",
+            Self::Protection => "Eye & Ear Protection",
+            Self::Accessories => "Accessories",
+        }
+    }
+
+    /// Every node from the top of the tree down to, and including, this node
+    pub fn path(&self) -> Vec<TaxonomyNode> {
+        let mut path = vec![*self];
+
+        while let Some(parent) = path.last().and_then(TaxonomyNode::parent) {
+            path.push(parent);
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// Maps a retailer's search term, ie. "Optics-scopes-binoculars", onto the most specific
+    /// node that covers everything the term mentions. Catch-all terms like "firearms" don't
+    /// map to anything, since they cover more than a single node
+    pub fn from_search_term(category: Category, term: &str) -> Option<TaxonomyNode> {
+        let mut normalized_term = term
+            .to_lowercase()
+            .replace(['-', '_', '/', '+'], " ")
+            .replace("%20", " ");
+
+        let mut matches: Vec<TaxonomyNode> = Vec::new();
+
+        for (pattern, node) in KEYWORD_PATTERNS.iter() {
+            if node.category() != category || !pattern.is_match(&normalized_term) {
+                continue;
+            }
+
+            matches.push(*node);
+
+            // shorter keywords can't match inside a phrase that was already matched
+            normalized_term = pattern.replace_all(&normalized_term, " ").into_owned();
+        }
+
+        let mut common_path = matches.first()?.path();
+
+        for node in &matches[1..] {
+            let path = node.path();
+
+            let shared = common_path
+                .iter()
+                .zip(&path)
+                .take_while(|(left, right)| left == right)
+                .count();
+
+            common_path.truncate(shared);
+        }
+
+        common_path.last().copied()
+    }
+
+    pub fn children(&self) -> Vec<TaxonomyNode> {
+        TaxonomyNode::iter()
+            .filter(|node| node.parent() == Some(*self))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiTaxonomyNode {
+    pub node: TaxonomyNode,
+    pub display_name: &'static str,
+    pub category: Category,
+    pub children: Vec<ApiTaxonomyNode>,
+}
+
+impl ApiTaxonomyNode {
+    fn new(node: TaxonomyNode) -> Self {
+        Self {
+            node,
+            display_name: node.display_name(),
+            category: node.category(),
+            children: node.children().into_iter().map(Self::new).collect(),
+        }
+    }
+
+    /// The full tree, starting from the nodes directly under each `Category`
+    pub fn get_tree() -> Vec<ApiTaxonomyNode> {
+        TaxonomyNode::iter()
+            .filter(|node| node.parent().is_none())
+            .map(Self::new)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_whole_words() {
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Other, "showcase"),
+            None
+        );
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Other, "gun-cases"),
+            Some(TaxonomyNode::Storage)
+        );
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Other, "firearm-accessories"),
+            Some(TaxonomyNode::Accessories)
+        );
+    }
+
+    #[test]
+    fn prefers_specific_phrases() {
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Other, "scope mounts"),
+            Some(TaxonomyNode::Accessories)
+        );
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Other, "scope-rings"),
+            Some(TaxonomyNode::Accessories)
+        );
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Other, "Optics-scopes-binoculars"),
+            Some(TaxonomyNode::Optics)
+        );
+    }
+
+    #[test]
+    fn filters_by_category() {
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Ammunition, "ammo/rifle-ammo"),
+            Some(TaxonomyNode::RifleAmmunition)
+        );
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Firearm, "rifles"),
+            Some(TaxonomyNode::Rifles)
+        );
+        assert_eq!(
+            TaxonomyNode::from_search_term(Category::Firearm, "firearms"),
+            None
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{money::Currency, result::enums::RetailerName};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Province {
+    Alberta,
+    BritishColumbia,
+    Manitoba,
+    NewBrunswick,
+    NewfoundlandAndLabrador,
+    NovaScotia,
+    Ontario,
+    PrinceEdwardIsland,
+    Quebec,
+    Saskatchewan,
+    NorthwestTerritories,
+    Nunavut,
+    Yukon,
+}
+
+/// The e-commerce platform a retailer's store runs on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Platform {
+    WooCommerce,
+    BigCommerce,
+    Shopify,
+    Magento,
+    Lightspeed,
+    PrestaShop,
+    OpenCart,
+    NopCommerce,
+    /// Built in-house rather than on an off the shelf platform
+    Custom,
+}
+
+/// Display metadata for a retailer, declared alongside the retailer itself.
+/// Everything is `'static` so it can live in the retailer registry
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RetailerInfo {
+    pub display_name: &'static str,
+    pub homepage: &'static str,
+    /// Left out until the retailer's location is confirmed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub province: Option<Province>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<&'static str>,
+    pub platforms: &'static [Platform],
+    /// What the retailer prices in, for markup that only shows a "$"
+    pub currency: Currency,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_notes: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo_url: Option<&'static str>,
+}
+
+/// Per retailer stats, aggregated from whatever is currently live
+#[derive(Debug, Deserialize)]
+pub struct CollectionRetailerStats {
+    #[serde(rename = "_id")]
+    pub retailer: RetailerName,
+    // distinct products, the live view keeps a few days of snapshots of each
+    pub live_product_count: u64,
+    // end of the retailer's last successful crawl, from crawl-runs
+    #[serde(default)]
+    pub last_crawled: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiRetailerOutput {
+    pub name: RetailerName,
+    #[serde(flatten)]
+    pub info: RetailerInfo,
+    pub last_crawled: Option<u64>,
+    pub live_product_count: u64,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// A lease on an indexer lock, only held until `expires_at` unless the holder keeps
+/// renewing it, so the lock of an indexer that crashed frees itself up
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CollectionRunLock {
+    #[serde(rename = "_id")]
+    pub name: String,
+    // unique to each indexer process
+    pub owner: String,
+    pub acquired_at: u64,
+    pub expires_at: u64,
+}
@@ -1,7 +1,9 @@
 use crate::deserialize_disallow_empty_string::disallow_empty_string;
+use crate::money::Money;
 use crate::result::base::CrawlResult;
 use crate::result::enums::Category;
 use crate::result::enums::RetailerName;
+use crate::result::taxonomy::TaxonomyNode;
 
 use mongodb::bson::doc;
 use serde::Deserialize;
@@ -45,17 +47,22 @@ pub struct ApiSearchInput {
     pub page: Option<u32>,
     #[serde(deserialize_with = "string_to_cents")]
     #[serde(default)]
-    pub min_price: Option<u32>,
+    pub min_price: Option<Money>,
     #[serde(deserialize_with = "string_to_cents")]
     #[serde(default)]
-    pub max_price: Option<u32>,
+    pub max_price: Option<Money>,
     #[serde(default)]
     pub sort: Sort,
     #[serde(default)]
     pub category: Category,
+    #[serde(default)]
+    pub subcategory: Option<TaxonomyNode>,
     #[serde(deserialize_with = "string_to_retailer_array")]
     #[serde(default)]
     pub retailers: Vec<RetailerName>,
+    // collapses listings of the same canonical product into a single item
+    #[serde(default)]
+    pub grouped: bool,
 }
 
 #[derive(Debug, Default, Deserialize, EnumString, Clone, Copy)]
@@ -93,8 +100,8 @@ where
     Ok(output)
 }
 
-// responsible for turning a String input, into an optional number
-fn string_to_cents<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+// responsible for turning a String input, into an optional price
+fn string_to_cents<'de, D>(deserializer: D) -> Result<Option<Money>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -109,37 +116,14 @@ where
         return Ok(None);
     }
 
-    let mut trimmed_price = string_price.clone();
-
-    if trimmed_price.starts_with("$") {
-        trimmed_price.remove(0);
-    }
-
-    trimmed_price = trimmed_price.replace(",", "");
-
-    // lazily deal with missing cents
-    // turns "100" -> "100.00"
-    if !trimmed_price.contains(".") {
-        trimmed_price += ".00";
-    }
-
-    let Some((dollars, cents)) = trimmed_price.split_once(".") else {
-        debug!("Invalid format: {:?}", trimmed_price);
-        return Err(Error::custom("invalid format"));
-    };
-
-    let parsed_dollars = match dollars.parse::<u32>() {
-        Ok(dollar) => dollar,
-        Err(_) => return Err(Error::custom("invalid dollar part")),
-    };
-
-    let parsed_cents = match cents.parse::<u32>() {
-        Ok(cent) => cent,
-        Err(_) => return Err(Error::custom("invalid cent part")),
+    let result = match Money::parse(&string_price) {
+        Ok(money) => money,
+        Err(err) => {
+            debug!("Invalid format: {:?} ({err})", string_price);
+            return Err(Error::custom("invalid format"));
+        }
     };
 
-    let result = parsed_dollars * 100 + parsed_cents;
-
     debug!("Converted {} into {}", string_price, result);
 
     Ok(Some(result))
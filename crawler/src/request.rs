@@ -19,8 +19,10 @@ impl Request {
     pub fn builder() -> RequestBuilder {
         RequestBuilder::new()
     }
+}
 
-    pub fn default() -> Self {
+impl Default for Request {
+    fn default() -> Self {
         Request {
             method: HttpMethod::GET,
             url: Default::default(),
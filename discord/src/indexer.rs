@@ -1,18 +1,28 @@
-use std::{cmp::max, collections::BTreeMap, env};
+use std::{cmp::max, collections::BTreeMap, env, mem};
 
 use common::{
     constants::DISCORD_INDEXER_WEBHOOK_URL,
-    result::{
-        base::CrawlResult,
-        enums::{Category, RetailerName},
-    },
+    crawl_diff::CrawlDiffSummary,
+    money::Currency,
+    result::{counts::ResultCounts, enums::RetailerName},
     utils::get_current_time,
 };
 use serenity::all::{Colour, CreateEmbed, MessageId};
 use tokio::sync::{Mutex, MutexGuard, OnceCell};
+use tracing::error;
 
 use crate::client::DiscordClient;
 
+const MAX_SKIPPED_SAMPLES: usize = 3;
+// Discord's limits, going over any of them gets the whole message rejected
+const MAX_FIELD_VALUE_LENGTH: usize = 1024;
+const MAX_FIELDS_PER_EMBED: usize = 25;
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+const MAX_MESSAGE_LENGTH: usize = 6000;
+
+// name, value, inline
+type Field = (String, String, bool);
+
 static DISCORD_INDEXER_WEBHOOK: OnceCell<Mutex<IndexerWebhook>> = OnceCell::const_new();
 
 enum IndexingState {
@@ -20,10 +30,13 @@ enum IndexingState {
     InProgressError,
     FinishedSuccess,
     FinishedError,
+    // the indexer was shut down before every retailer finished
+    Aborted,
 }
 
 #[derive(Debug)]
 struct RetailerStats {
+    #[allow(dead_code)]
     start_time: u64,
     end_time: Option<u64>,
     firearms_count: u64,
@@ -34,6 +47,24 @@ struct RetailerStats {
     ammo_count_with_metadata: u64,
     other_count: u64,
     errors: Vec<String>,
+    // cancelled for running past its budget, along with whether
+    // the partial results were kept
+    timed_out: Option<(String, bool)>,
+    // stopped part way through by a shutdown, left to be resumed
+    interrupted: bool,
+    // results priced in something other than CAD, ie. a
+    // retailer geo-switching us over to USD
+    rejected_currencies: BTreeMap<Currency, u64>,
+    converted_currencies: BTreeMap<Currency, u64>,
+    // products that failed to parse, but didn't fail the crawl
+    skipped_products: Vec<String>,
+    // categories that came back with far fewer results than usual,
+    // which is normally a retailer changing their templates on us
+    degraded_categories: Vec<String>,
+    // number of results each rule moved or dropped
+    rule_decisions: BTreeMap<String, u64>,
+    // what changed since the retailer's previous crawl
+    changes: Option<CrawlDiffSummary>,
 }
 
 impl RetailerStats {
@@ -46,6 +77,14 @@ impl RetailerStats {
             ammo_count_with_metadata: 0,
             other_count: 0,
             errors: Vec::new(),
+            timed_out: None,
+            interrupted: false,
+            rejected_currencies: BTreeMap::new(),
+            converted_currencies: BTreeMap::new(),
+            skipped_products: Vec::new(),
+            degraded_categories: Vec::new(),
+            rule_decisions: BTreeMap::new(),
+            changes: None,
         }
     }
 
@@ -58,7 +97,8 @@ pub struct IndexerWebhook {
     client: DiscordClient,
     // BTreeMap is used over HashMap since BTreeMap sort themselves
     retailers: BTreeMap<RetailerName, RetailerStats>,
-    main_message: Option<MessageId>,
+    // the report spills over into more messages once it outgrows the first
+    report_messages: Vec<MessageId>,
     state: IndexingState,
 }
 
@@ -72,11 +112,18 @@ impl IndexerWebhook {
         Self {
             client: DiscordClient::new(webhook_env_var).await,
             retailers: BTreeMap::new(),
-            main_message: None,
+            report_messages: Vec::new(),
             state: IndexingState::InProgress,
         }
     }
 
+    /// Starts a fresh report, used when a single process does more than one run
+    pub fn start_run(&mut self) {
+        self.retailers.clear();
+        self.report_messages.clear();
+        self.state = IndexingState::InProgress;
+    }
+
     pub fn register_retailer(&mut self, retailer: RetailerName) {
         self.retailers.insert(retailer, RetailerStats::new());
     }
@@ -89,27 +136,88 @@ impl IndexerWebhook {
         retailer_stats.errors.push(error.into());
     }
 
-    pub fn append_retailer_stats(&mut self, retailer: RetailerName, results: &Vec<&CrawlResult>) {
+    pub fn record_retailer_timeout(
+        &mut self,
+        retailer: RetailerName,
+        reason: impl Into<String>,
+        kept_partial: bool,
+    ) {
         let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
             return;
         };
 
-        retailer_stats.end_time = Some(get_current_time());
+        retailer_stats.timed_out = Some((reason.into(), kept_partial));
+    }
 
-        for result in results {
-            match result.category {
-                Category::Firearm => retailer_stats.firearms_count += 1,
-                Category::Ammunition => {
-                    retailer_stats.ammo_count += 1;
+    pub fn record_retailer_interrupted(&mut self, retailer: RetailerName) {
+        let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
+            return;
+        };
 
-                    if result.metadata.is_some() {
-                        retailer_stats.ammo_count_with_metadata += 1;
-                    }
-                }
-                Category::Other => retailer_stats.other_count += 1,
-                Category::_All => {}
-            }
-        }
+        retailer_stats.interrupted = true;
+    }
+
+    pub fn record_unexpected_currencies(
+        &mut self,
+        retailer: RetailerName,
+        rejected: BTreeMap<Currency, u64>,
+        converted: BTreeMap<Currency, u64>,
+    ) {
+        let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
+            return;
+        };
+
+        retailer_stats.rejected_currencies = rejected;
+        retailer_stats.converted_currencies = converted;
+    }
+
+    pub fn record_skipped_products(&mut self, retailer: RetailerName, errors: &[String]) {
+        let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
+            return;
+        };
+
+        retailer_stats.skipped_products = errors.to_vec();
+    }
+
+    pub fn record_degraded_categories(&mut self, retailer: RetailerName, degraded: Vec<String>) {
+        let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
+            return;
+        };
+
+        retailer_stats.degraded_categories = degraded;
+    }
+
+    pub fn record_rule_decisions(
+        &mut self,
+        retailer: RetailerName,
+        decisions: BTreeMap<String, u64>,
+    ) {
+        let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
+            return;
+        };
+
+        retailer_stats.rule_decisions = decisions;
+    }
+
+    pub fn record_crawl_diff(&mut self, retailer: RetailerName, changes: CrawlDiffSummary) {
+        let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
+            return;
+        };
+
+        retailer_stats.changes = Some(changes);
+    }
+
+    pub fn append_retailer_stats(&mut self, retailer: RetailerName, counts: &ResultCounts) {
+        let Some(retailer_stats) = self.retailers.get_mut(&retailer) else {
+            return;
+        };
+
+        retailer_stats.end_time = Some(get_current_time());
+
+        retailer_stats.firearms_count += counts.firearms;
+        retailer_stats.ammo_count += counts.ammunition;
+        retailer_stats.ammo_count_with_metadata += counts.ammunition_with_metadata;
+        retailer_stats.other_count += counts.other;
     }
 
     fn get_embed_colour(&self) -> Colour {
@@ -118,17 +226,14 @@ impl IndexerWebhook {
             IndexingState::InProgressError => Colour::from_rgb(235, 143, 35), // orange
             IndexingState::FinishedSuccess => Colour::from_rgb(35, 235, 143), // green
             IndexingState::FinishedError => Colour::from_rgb(235, 35, 127), // pink?
+            IndexingState::Aborted => Colour::from_rgb(128, 128, 128),   // grey
         }
     }
 
     // I don't like making this mutable, but whatever, ops tooling
-    fn create_indexer_report_embed(&mut self) -> Vec<CreateEmbed> {
-        let mut embeds: Vec<CreateEmbed> = Vec::new();
-        let mut fields: Vec<(String, String, bool)> = Vec::new();
-
-        let mut count: u64 = 0;
+    fn create_indexer_report_messages(&mut self) -> Vec<Vec<CreateEmbed>> {
+        let mut fields: Vec<Field> = Vec::new();
 
-        // TODO: deal with splitting embeds properly
         for (retailer, stats) in &self.retailers {
             let counts = format!(
                 "F: {} | O: {} | T: {}\nA: {}/{} ({:.2}%)",
@@ -142,6 +247,45 @@ impl IndexerWebhook {
 
             let mut retailer_field: String = format!("```\n{counts}\n```");
 
+            if !stats.skipped_products.is_empty() {
+                // only show a sample, the full list is in the logs
+                let sample = stats
+                    .skipped_products
+                    .iter()
+                    .take(MAX_SKIPPED_SAMPLES)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                retailer_field += &format!(
+                    "Skipped {} products:\n```\n{sample}\n```",
+                    stats.skipped_products.len()
+                );
+            }
+
+            if let Some(changes) = &stats.changes {
+                retailer_field += &format!(
+                    "Changes:\n```\nNew: {} | Gone: {}\nDrops: {} | Increases: {}\nSales: +{} | -{}\n```",
+                    changes.new_products,
+                    changes.removed_products,
+                    changes.price_drops,
+                    changes.price_increases,
+                    changes.sales_started,
+                    changes.sales_ended
+                );
+            }
+
+            if !stats.rule_decisions.is_empty() {
+                let rule_lines = stats
+                    .rule_decisions
+                    .iter()
+                    .map(|(rule, count)| format!("{count}x {rule}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                retailer_field += &format!("Rules applied:\n```\n{rule_lines}\n```");
+            }
+
             if !stats.errors.is_empty() {
                 if matches!(self.state, IndexingState::InProgress) {
                     self.state = IndexingState::InProgressError;
@@ -151,48 +295,105 @@ impl IndexerWebhook {
                 retailer_field += &format!("**```\n{error_blob}\n```**");
             }
 
-            fields.push((retailer.to_string(), retailer_field, false));
+            if let Some((reason, kept_partial)) = &stats.timed_out {
+                if matches!(self.state, IndexingState::InProgress) {
+                    self.state = IndexingState::InProgressError;
+                }
 
-            count += 1;
+                let kept = match kept_partial {
+                    true => "kept",
+                    false => "discarded",
+                };
 
-            if count % 25 == 0 {
-                embeds.push(
-                    CreateEmbed::new()
-                        .fields(fields.clone())
-                        .colour(self.get_embed_colour()),
-                );
+                retailer_field +=
+                    &format!("Timed out, partial results {kept}:\n**```\n{reason}\n```**");
+            }
 
-                fields.clear();
+            if stats.interrupted {
+                retailer_field += "**```\nInterrupted by shutdown, continues with --resume\n```**";
+            } else if matches!(self.state, IndexingState::Aborted)
+                && stats.end_time.is_none()
+                && stats.errors.is_empty()
+            {
+                retailer_field += "**```\nNot started before shutdown\n```**";
             }
-        }
 
-        if !fields.is_empty() {
-            embeds.push(
-                CreateEmbed::new()
-                    .fields(fields)
-                    .colour(self.get_embed_colour()),
-            );
+            if !stats.rejected_currencies.is_empty() || !stats.converted_currencies.is_empty() {
+                if matches!(self.state, IndexingState::InProgress) {
+                    self.state = IndexingState::InProgressError;
+                }
+
+                let mut currency_lines: Vec<String> = Vec::new();
+
+                for (currency, count) in &stats.rejected_currencies {
+                    currency_lines.push(format!("Rejected {count} {currency} priced results"));
+                }
+
+                for (currency, count) in &stats.converted_currencies {
+                    currency_lines.push(format!("Converted {count} {currency} priced results"));
+                }
+
+                retailer_field += &format!("**```\n{}\n```**", currency_lines.join("\n"));
+            }
+
+            if !stats.degraded_categories.is_empty() {
+                if matches!(self.state, IndexingState::InProgress) {
+                    self.state = IndexingState::InProgressError;
+                }
+
+                let degraded_blob = stats.degraded_categories.join("\n");
+                retailer_field += &format!("Degraded:\n**```\n{degraded_blob}\n```**");
+            }
+
+            fields.push((
+                retailer.to_string(),
+                truncate_field_value(retailer_field),
+                false,
+            ));
         }
 
-        embeds
+        let colour = self.get_embed_colour();
+
+        split_fields(fields)
+            .into_iter()
+            .map(|embeds| {
+                embeds
+                    .into_iter()
+                    .map(|fields| CreateEmbed::new().fields(fields).colour(colour))
+                    .collect()
+            })
+            .collect()
     }
 
     pub async fn update_main_message(&mut self) {
-        let embeds = self.create_indexer_report_embed();
-
-        if let Some(main_message) = self.main_message {
-            let _ = self.client.update_message(main_message, embeds).await;
-        } else {
-            let returned_message_id = self
-                .client
-                .send_message(embeds)
-                .await
-                .expect("Expected Discord API call to succeed");
-
-            self.main_message = Some(returned_message_id.expect("Expected message returned").id);
+        let messages = self.create_indexer_report_messages();
+
+        for (index, embeds) in messages.into_iter().enumerate() {
+            if let Some(message_id) = self.report_messages.get(index) {
+                if let Err(err) = self.client.update_message(*message_id, embeds).await {
+                    error!("Failed to update indexer report: {err}");
+                }
+
+                continue;
+            }
+
+            match self.client.send_message(embeds).await {
+                Ok(Some(message)) => self.report_messages.push(message.id),
+                Ok(None) => error!("Discord didn't return the indexer report message"),
+                // the messages after it would end up out of order
+                Err(err) => {
+                    error!("Failed to send indexer report: {err}");
+                    return;
+                }
+            }
         }
     }
 
+    /// Marks the report as stopped early, so it doesn't sit "in progress" forever
+    pub fn abort(&mut self) {
+        self.state = IndexingState::Aborted;
+    }
+
     pub fn finish(&mut self) {
         self.state = match self.state {
             IndexingState::InProgressError => IndexingState::FinishedError,
@@ -201,6 +402,69 @@ impl IndexerWebhook {
     }
 }
 
+/// Cuts a field down to what Discord accepts, closing a code block the cut left open
+fn truncate_field_value(value: String) -> String {
+    if value.chars().count() <= MAX_FIELD_VALUE_LENGTH {
+        return value;
+    }
+
+    let suffix = "...\n```";
+    let mut truncated: String = value
+        .chars()
+        .take(MAX_FIELD_VALUE_LENGTH - suffix.len())
+        .collect();
+
+    truncated += "...";
+
+    if truncated.matches("```").count() % 2 == 1 {
+        truncated += "\n```";
+    }
+
+    truncated
+}
+
+/// Splits the fields into embeds, and the embeds into messages, so that
+/// none of them go over Discord's limits
+fn split_fields(fields: Vec<Field>) -> Vec<Vec<Vec<Field>>> {
+    let mut messages: Vec<Vec<Vec<Field>>> = Vec::new();
+    let mut embeds: Vec<Vec<Field>> = Vec::new();
+    let mut embed_fields: Vec<Field> = Vec::new();
+    let mut message_length = 0;
+
+    for field in fields {
+        let field_length = field.0.chars().count() + field.1.chars().count();
+
+        if embed_fields.len() == MAX_FIELDS_PER_EMBED {
+            embeds.push(mem::take(&mut embed_fields));
+        }
+
+        let message_full = message_length + field_length > MAX_MESSAGE_LENGTH
+            || embeds.len() == MAX_EMBEDS_PER_MESSAGE;
+
+        if message_full && message_length > 0 {
+            if !embed_fields.is_empty() {
+                embeds.push(mem::take(&mut embed_fields));
+            }
+
+            messages.push(mem::take(&mut embeds));
+            message_length = 0;
+        }
+
+        message_length += field_length;
+        embed_fields.push(field);
+    }
+
+    if !embed_fields.is_empty() {
+        embeds.push(embed_fields);
+    }
+
+    if !embeds.is_empty() {
+        messages.push(embeds);
+    }
+
+    messages
+}
+
 pub async fn get_indexer_webhook() -> MutexGuard<'static, IndexerWebhook> {
     if !DISCORD_INDEXER_WEBHOOK.initialized() {
         let _ = DISCORD_INDEXER_WEBHOOK.set(Mutex::new(IndexerWebhook::new().await));
@@ -208,3 +472,45 @@ pub async fn get_indexer_webhook() -> MutexGuard<'static, IndexerWebhook> {
 
     DISCORD_INDEXER_WEBHOOK.get().unwrap().lock().await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(value_length: usize) -> Field {
+        (String::from("Retailer"), "a".repeat(value_length), false)
+    }
+
+    #[test]
+    fn truncates_long_fields() {
+        let value = format!("```\n{}\n```", "a".repeat(2000));
+        let truncated = truncate_field_value(value);
+
+        assert_eq!(truncated.chars().count(), MAX_FIELD_VALUE_LENGTH);
+        assert!(truncated.ends_with("...\n```"));
+    }
+
+    #[test]
+    fn splits_fields_within_limits() {
+        let messages = split_fields((0..60).map(|_| field(1000)).collect());
+
+        for message in &messages {
+            let length: usize = message
+                .iter()
+                .flatten()
+                .map(|(name, value, _)| name.len() + value.len())
+                .sum();
+
+            assert!(length <= MAX_MESSAGE_LENGTH);
+            assert!(message.len() <= MAX_EMBEDS_PER_MESSAGE);
+        }
+
+        assert_eq!(messages.iter().flatten().flatten().count(), 60);
+
+        let small = split_fields((0..30).map(|_| field(10)).collect());
+
+        assert_eq!(small.len(), 1);
+        assert_eq!(small[0].len(), 2);
+        assert_eq!(small[0][0].len(), MAX_FIELDS_PER_EMBED);
+    }
+}
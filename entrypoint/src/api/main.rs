@@ -2,18 +2,18 @@ use axum::{
     Router,
     routing::{get, post},
 };
+use clap::Parser;
 use common::utils::is_beta_environment;
-use mongodb_connector::connector::MongoDBConnector;
 use service_layers::build_service_layers;
 use std::{env, net::SocketAddr, sync::Arc};
 use tokio::net::TcpListener;
 use tracing::info;
-use utils::logger::configure_logger;
+use utils::{logger::configure_logger, storage::StorageBackend};
 
 use crate::{
     routes::{
         contact::contact_handler, history::history_handler, image::image_handler,
-        search_query::search_handler,
+        retailers::retailers_handler, search_query::search_handler, taxonomy::taxonomy_handler,
     },
     structs::ServerState,
 };
@@ -27,26 +27,39 @@ pub(crate) mod structs;
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
+#[derive(Parser)]
+#[command(version)]
+struct Arguments {
+    /// Where results are read from, memory starts out empty
+    #[arg(long, value_enum, default_value_t = StorageBackend::Mongodb)]
+    storage: StorageBackend,
+}
+
 #[tokio::main]
 async fn main() {
+    let args = Arguments::parse();
+
     configure_logger();
 
     let port = env::var("API_PORT").unwrap_or("3001".to_string());
 
-    info!("Starting MongoDB client");
+    info!("Starting {:?} storage", args.storage);
 
-    let mongodb = MongoDBConnector::new().await;
-    let state = Arc::new(ServerState { db: mongodb });
+    let state = Arc::new(ServerState {
+        db: args.storage.connect().await,
+    });
 
     let addr = format!("0.0.0.0:{port}");
 
-    info!("MongoDB client ready");
+    info!("Storage ready");
     info!("Starting web server on: {addr}");
 
     let mut router = Router::new()
         .route("/api/search", get(search_handler))
         .route("/api/contact", post(contact_handler))
-        .route("/api/history", get(history_handler));
+        .route("/api/history", get(history_handler))
+        .route("/api/retailers", get(retailers_handler))
+        .route("/api/taxonomy", get(taxonomy_handler));
 
     if is_beta_environment() {
         router = router.route("/api/image", get(image_handler));
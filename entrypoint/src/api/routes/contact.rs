@@ -50,7 +50,7 @@ pub(crate) async fn contact_handler(
     headers: HeaderMap,
     State(state): State<Arc<ServerState>>,
     WithRejection(Json(json), _): WithRejection<Json<Payload>, ApiError>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<impl IntoResponse, ApiError> {
     let Some(ip_addr_header) = headers.get(IP_HEADER) else {
         error!("Request is missing {IP_HEADER} header");
 
@@ -104,7 +104,7 @@ pub(crate) async fn contact_handler(
         return Ok(StatusCode::BAD_REQUEST);
     }
 
-    state.db.insert_message(message.clone()).await;
+    state.db.insert_message(message.clone()).await?;
 
     get_contact_webhook().await.relay_message(message).await;
 
@@ -3,8 +3,9 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use mongodb_connector::errors::StorageError;
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, error};
 
 #[derive(Debug, Error)]
 pub(crate) enum ApiError {
@@ -12,6 +13,8 @@ pub(crate) enum ApiError {
     QueryExtractorRejection(#[from] QueryRejection),
     #[error(transparent)]
     JsonExtractorRejection(#[from] JsonRejection),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
 }
 
 impl IntoResponse for ApiError {
@@ -19,6 +22,11 @@ impl IntoResponse for ApiError {
         let (status, message) = match self {
             Self::QueryExtractorRejection(rejection) => (rejection.status(), rejection.body_text()),
             Self::JsonExtractorRejection(rejection) => (rejection.status(), rejection.body_text()),
+            Self::Storage(err) => {
+                error!("Failed to reach storage: {err}");
+
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
         };
 
         debug!("Failed to parse incoming request: {}, {}", status, message);
@@ -36,10 +36,10 @@ fn get_lowest_price(price: &PriceHistoryEntry) -> u64 {
 pub(crate) async fn history_handler(
     State(state): State<Arc<ServerState>>,
     WithRejection(Query(query), _): WithRejection<Query<ApiPriceHistoryInput>, ApiError>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<impl IntoResponse, ApiError> {
     let start_time: Instant = Instant::now();
 
-    let Some(result) = state.db.get_pricing_history(query).await else {
+    let Some(result) = state.db.get_pricing_history(query).await? else {
         return Ok(StatusCode::BAD_REQUEST.into_response());
     };
 
@@ -105,3 +105,78 @@ pub(crate) async fn history_handler(
 
     Ok(response.into_response())
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::{Body, to_bytes},
+        http::Request,
+        routing::get,
+    };
+    use common::{
+        money::Money,
+        result::{
+            base::{CrawlResult, Price},
+            enums::{Category, RetailerName},
+        },
+    };
+    use mongodb_connector::{memory::InMemoryStorage, storage::Storage};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn get_history(db: InMemoryStorage, id: &str) -> (StatusCode, Value) {
+        let router = Router::new()
+            .route("/api/history", get(history_handler))
+            .with_state(Arc::new(ServerState { db: Box::new(db) }));
+
+        let request = Request::builder()
+            .uri(format!("/api/history?id={id}"))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn returns_price_history_of_live_result() {
+        let db = InMemoryStorage::new();
+        let result = CrawlResult::new(
+            "Ruger 10/22 Carbine".into(),
+            "https://example.com/ruger-10-22".into(),
+            Price::new(Money::cad(45_000)).with_sale_price(Money::cad(40_000)),
+            RetailerName::AlFlahertys,
+            Category::Firearm,
+        );
+
+        db.insert_results(vec![&result]).await.unwrap();
+        db.finalize_results(RetailerName::AlFlahertys, 0)
+            .await
+            .unwrap();
+
+        let live_results = db
+            .get_live_results(RetailerName::AlFlahertys, 0)
+            .await
+            .unwrap();
+        let id = live_results[0].id.clone().unwrap();
+
+        let (status, body) = get_history(db, &id).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["history"].as_array().map(Vec::len), Some(1));
+        assert_eq!(body["min_price"]["sale_price"], 40_000);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_result() {
+        let (status, _) = get_history(InMemoryStorage::new(), "507f1f77bcf86cd799439011").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -21,8 +21,8 @@ pub(crate) struct Payload {
 pub(crate) async fn image_handler(
     State(state): State<Arc<ServerState>>,
     WithRejection(Query(query), _): WithRejection<Query<Payload>, ApiError>,
-) -> Result<impl IntoResponse, StatusCode> {
-    let Some(result) = state.db.find_result(query.id).await else {
+) -> Result<impl IntoResponse, ApiError> {
+    let Some(result) = state.db.find_result(query.id).await? else {
         return Ok(StatusCode::NOT_FOUND.into_response());
     };
 
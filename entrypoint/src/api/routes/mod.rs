@@ -2,4 +2,6 @@ pub(crate) mod contact;
 pub(crate) mod error_message_erasure;
 pub(crate) mod history;
 pub(crate) mod image;
+pub(crate) mod retailers;
 pub(crate) mod search_query;
+pub(crate) mod taxonomy;
@@ -0,0 +1,45 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{Json, extract::State, response::IntoResponse};
+use common::retailer_info::ApiRetailerOutput;
+use retailers::registry::get_registered_retailers;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::{ServerState, routes::error_message_erasure::ApiError};
+
+pub(crate) async fn retailers_handler(
+    State(state): State<Arc<ServerState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let start_time = Instant::now();
+
+    let stats: HashMap<_, _> = state
+        .db
+        .get_retailer_stats()
+        .await?
+        .into_iter()
+        .map(|retailer_stats| (retailer_stats.retailer, retailer_stats))
+        .collect();
+
+    let mut retailers: Vec<ApiRetailerOutput> = get_registered_retailers()
+        .map(|registration| {
+            let retailer_stats = stats.get(&registration.name);
+
+            ApiRetailerOutput {
+                name: registration.name,
+                info: registration.info,
+                last_crawled: retailer_stats.and_then(|retailer_stats| retailer_stats.last_crawled),
+                live_product_count: retailer_stats
+                    .map(|retailer_stats| retailer_stats.live_product_count)
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    // registration order depends on the linker, keep the output stable
+    retailers.sort_by_key(|retailer| retailer.name);
+
+    debug!("Request time: {}ms", start_time.elapsed().as_millis());
+
+    Ok(Json::from(retailers).into_response())
+}
@@ -1,13 +1,20 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use axum::{
     Json,
     extract::{Query, State},
-    http::StatusCode,
     response::IntoResponse,
 };
 use axum_extra::extract::WithRejection;
-use common::{result::base::CrawlResult, search_params::ApiSearchInput};
+use common::{
+    canonical_product::{CanonicalListing, CollectionCanonicalProduct},
+    result::{
+        base::{CrawlResult, Price},
+        enums::RetailerName,
+    },
+    search_params::ApiSearchInput,
+};
+use mongodb_connector::{errors::StorageError, storage::Storage};
 use serde::Serialize;
 use tokio::time::Instant;
 use tracing::debug;
@@ -18,22 +25,44 @@ use crate::{ServerState, routes::error_message_erasure::ApiError};
 struct ApiResult {
     items: Vec<CrawlResult>,
     total_count: u64,
+    // only set for grouped searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    groups: Option<Vec<ApiProductGroup>>,
+}
+
+/// Every retailer's listing of the same product, attached to the item that stands in for it
+#[derive(Serialize, Debug)]
+struct ApiProductGroup {
+    // ID of the item in `items` the group belongs to
+    id: Option<String>,
+    canonical_id: String,
+    retailer_count: usize,
+    lowest_price: Price,
+    lowest_price_retailer: RetailerName,
+    lowest_price_url: String,
+    listings: Vec<CanonicalListing>,
 }
 
 pub(crate) async fn search_handler(
     State(state): State<Arc<ServerState>>,
     WithRejection(Query(params), _): WithRejection<Query<ApiSearchInput>, ApiError>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<impl IntoResponse, ApiError> {
     let start_time = Instant::now();
 
     debug!("{params:?}");
 
-    let db_results = state.db.search_items(&params).await;
+    let db_results = state.db.search_items(&params).await?;
 
     // TODO: can probably delete this and just return db_results
+    let groups = match params.grouped {
+        true => Some(get_groups(state.db.as_ref(), &db_results.items).await?),
+        false => None,
+    };
+
     let result = ApiResult {
         items: db_results.items,
         total_count: db_results.total_count,
+        groups,
     };
 
     debug!("{:?}", result);
@@ -44,3 +73,128 @@ pub(crate) async fn search_handler(
 
     Ok(response.into_response())
 }
+
+/// Attaches the listings of every canonical product on the page to the item that stands
+/// in for it, the search already collapsed each product's listings into a single item
+async fn get_groups(
+    db: &dyn Storage,
+    items: &[CrawlResult],
+) -> Result<Vec<ApiProductGroup>, StorageError> {
+    let urls: Vec<String> = items.iter().map(|item| item.url.clone()).collect();
+    let products = db.get_canonical_products(urls).await?;
+
+    let mut products_by_url: HashMap<&str, &CollectionCanonicalProduct> = HashMap::new();
+
+    for product in &products {
+        for listing in &product.listings {
+            products_by_url.insert(listing.url.as_str(), product);
+        }
+    }
+
+    let groups = items
+        .iter()
+        .filter_map(|item| {
+            let product = products_by_url.get(item.url.as_str())?;
+            let lowest_priced = product.get_lowest_priced()?;
+
+            // a product only one retailer carries has nothing to compare against
+            if product.get_retailer_count() < 2 {
+                return None;
+            }
+
+            Some(ApiProductGroup {
+                id: item.id.clone(),
+                canonical_id: product.key.clone(),
+                retailer_count: product.get_retailer_count(),
+                lowest_price: lowest_priced.price.clone(),
+                lowest_price_retailer: lowest_priced.retailer,
+                lowest_price_url: lowest_priced.url.clone(),
+                listings: product.listings.clone(),
+            })
+        })
+        .collect();
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Router,
+        body::{Body, to_bytes},
+        http::{Request, StatusCode},
+        routing::get,
+    };
+    use common::{
+        money::Money,
+        result::{
+            base::{CrawlResult, Price},
+            enums::{Category, RetailerName},
+        },
+    };
+    use mongodb_connector::memory::InMemoryStorage;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn search(state: ServerState, query: &str) -> (StatusCode, Value) {
+        let router = Router::new()
+            .route("/api/search", get(search_handler))
+            .with_state(Arc::new(state));
+
+        let request = Request::builder()
+            .uri(format!("/api/search?{query}"))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn searches_live_results() {
+        let db = InMemoryStorage::new();
+        let results = [
+            CrawlResult::new(
+                "Ruger 10/22 Carbine".into(),
+                "https://example.com/ruger-10-22".into(),
+                Price::new(Money::cad(45_000)),
+                RetailerName::AlFlahertys,
+                Category::Firearm,
+            ),
+            CrawlResult::new(
+                "Tikka T3x Lite".into(),
+                "https://example.com/tikka-t3x".into(),
+                Price::new(Money::cad(110_000)),
+                RetailerName::AlFlahertys,
+                Category::Firearm,
+            ),
+        ];
+
+        db.insert_results(results.iter().collect()).await.unwrap();
+        db.finalize_results(RetailerName::AlFlahertys, 0)
+            .await
+            .unwrap();
+
+        let (status, body) = search(ServerState { db: Box::new(db) }, "query=ruger").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_count"], 1);
+        assert_eq!(body["items"][0]["name"], "Ruger 10/22 Carbine");
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_query() {
+        let state = ServerState {
+            db: Box::new(InMemoryStorage::new()),
+        };
+
+        let (status, _) = search(state, "query=").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
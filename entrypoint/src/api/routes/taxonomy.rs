@@ -0,0 +1,6 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use common::result::taxonomy::ApiTaxonomyNode;
+
+pub(crate) async fn taxonomy_handler() -> Result<impl IntoResponse, StatusCode> {
+    Ok(Json::from(ApiTaxonomyNode::get_tree()).into_response())
+}
@@ -1,5 +1,5 @@
-use mongodb_connector::connector::MongoDBConnector;
+use mongodb_connector::storage::Storage;
 
 pub(crate) struct ServerState {
-    pub(crate) db: MongoDBConnector,
+    pub(crate) db: Box<dyn Storage>,
 }
@@ -0,0 +1,81 @@
+use common::{
+    crawl_baseline::CollectionCrawlBaseline,
+    result::{counts::ResultCounts, enums::Category},
+};
+
+use crate::clients::base::PageAnomaly;
+
+/// Number of previous crawls kept around to compare against
+pub(crate) const BASELINE_WINDOW: u64 = 7;
+/// Categories that normally return a handful of products are too noisy to judge
+const MIN_BASELINE_AVERAGE: f64 = 5.0;
+/// A category losing this much of its usual count is considered degraded
+const MAX_DROP_RATIO: f64 = 0.8;
+/// A drop lasting this many crawls in a row is taken as the retailer's new normal
+pub(crate) const MAX_BASELINE_SKIPS: u64 = 3;
+
+const TRACKED_CATEGORIES: [Category; 3] =
+    [Category::Firearm, Category::Ammunition, Category::Other];
+
+#[derive(Debug)]
+pub(crate) struct DegradedCategory {
+    pub(crate) category: Category,
+    // page anomalies say nothing about whether the count itself can be trusted
+    pub(crate) count_dropped: bool,
+    pub(crate) reason: String,
+}
+
+pub(crate) fn get_category_counts(counts: &ResultCounts) -> Vec<(Category, u64)> {
+    TRACKED_CATEGORIES
+        .into_iter()
+        .map(|category| (category, counts.get(category)))
+        .collect()
+}
+
+/// Compares the per-category counts against the rolling baseline, and the page
+/// counts against what was actually parsed, returning every category that
+/// looks like it's broken alongside why
+pub(crate) fn find_degraded_categories(
+    counts: &[(Category, u64)],
+    baselines: &[CollectionCrawlBaseline],
+    page_anomalies: &[PageAnomaly],
+) -> Vec<DegradedCategory> {
+    let mut degraded: Vec<DegradedCategory> = Vec::new();
+
+    for (category, count) in counts {
+        let Some(average) = baselines
+            .iter()
+            .find(|baseline| baseline.category == *category)
+            .and_then(CollectionCrawlBaseline::average)
+        else {
+            continue;
+        };
+
+        if average < MIN_BASELINE_AVERAGE {
+            continue;
+        }
+
+        let drop = 1.0 - (*count as f64 / average);
+
+        if *count == 0 || drop >= MAX_DROP_RATIO {
+            degraded.push(DegradedCategory {
+                category: *category,
+                count_dropped: true,
+                reason: format!(
+                    "{category}: {count} results, expected ~{average:.0} ({:.0}% drop)",
+                    100.0 * drop
+                ),
+            });
+        }
+    }
+
+    for page_anomaly in page_anomalies {
+        degraded.push(DegradedCategory {
+            category: page_anomaly.category,
+            count_dropped: false,
+            reason: page_anomaly.to_string(),
+        });
+    }
+
+    degraded
+}
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+/// What happens to the results a retailer found before running out of budget
+#[derive(Debug, Default, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum BudgetPolicy {
+    /// Treat the crawl like any other failure, partial results never go live
+    #[default]
+    Discard,
+    /// Publish whatever was found before the budget ran out
+    Keep,
+}
+
+/// Limits on a single retailer's crawl, so one stuck retailer
+/// can't hold up the rest of the run indefinitely
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct CrawlBudget {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) max_requests: Option<u64>,
+    pub(crate) policy: BudgetPolicy,
+}
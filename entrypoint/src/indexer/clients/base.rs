@@ -1,65 +1,165 @@
-use std::collections::HashMap;
+use std::{
+    fmt::{self, Display, Formatter},
+    hash::{DefaultHasher, Hash, Hasher},
+};
 
 use async_trait::async_trait;
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
-    metadata::Metadata,
+use common::{
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    result::{
+        base::CrawlResult,
+        counts::ResultCounts,
+        enums::{Category, RetailerName},
+    },
 };
 use metrics::{Metrics, put_metric};
 use retailers::errors::RetailerError;
 
-use crate::clients::utils::{get_category_tier, get_key};
+use crate::clients::{sink::ResultSink, utils::get_key};
+
+#[derive(Debug)]
+pub(crate) enum PageAnomalyKind {
+    /// `get_num_pages` claimed the page exists, but it parsed into nothing,
+    /// usually the first sign of a retailer changing their templates
+    Empty { num_pages: u64 },
+    /// The page had the exact same products as an earlier page
+    Repeated,
+    /// Pagination was cut off by `MAX_PAGES_PER_TERM`
+    PageCapReached,
+}
+
+#[derive(Debug)]
+pub(crate) struct PageAnomaly {
+    pub(crate) category: Category,
+    pub(crate) term: String,
+    // 1 indexed
+    pub(crate) page: u64,
+    pub(crate) kind: PageAnomalyKind,
+}
 
-pub(super) fn insert_result(results: &mut HashMap<String, CrawlResult>, new_result: CrawlResult) {
-    let key = get_key(&new_result);
+impl Display for PageAnomaly {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self {
+            category,
+            term,
+            page,
+            ..
+        } = self;
 
-    // deal with retailers that have the same product in multiple places
-    if let Some(existing_result) = results.get_mut(&key)
-        && get_category_tier(existing_result.category) < get_category_tier(new_result.category)
-    {
-        *existing_result = new_result;
-    } else {
-        results.insert(key, new_result);
+        match self.kind {
+            PageAnomalyKind::Empty { num_pages } => write!(
+                f,
+                "{category}: page {page}/{num_pages} of '{term}' had no products"
+            ),
+            PageAnomalyKind::Repeated => write!(
+                f,
+                "{category}: page {page} of '{term}' repeated an earlier page"
+            ),
+            PageAnomalyKind::PageCapReached => {
+                write!(f, "{category}: '{term}' stopped at the {page} page limit")
+            }
+        }
+    }
+}
+
+/// Fingerprints the products on a page to catch retailers that keep serving
+/// the same page regardless of the page number they're given
+pub(crate) fn get_page_fingerprint(results: &[CrawlResult]) -> u64 {
+    let mut keys: Vec<String> = results.iter().map(get_key).collect();
+    keys.sort();
+
+    let mut hasher = DefaultHasher::new();
+    keys.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/// Tracks products that failed to parse, so a single bad product card doesn't
+/// throw away the entire crawl, while a broken parser still fails the retailer
+pub(crate) struct ErrorBudget {
+    max_errors: u64,
+    errors: Vec<String>,
+}
+
+impl ErrorBudget {
+    pub(crate) fn new(max_errors: u64) -> Self {
+        Self {
+            max_errors,
+            errors: Vec::new(),
+        }
+    }
+
+    pub(crate) fn spend(&mut self, errors: Vec<RetailerError>) -> Result<(), RetailerError> {
+        self.errors
+            .extend(errors.into_iter().map(|error| error.to_string()));
+
+        let error_count = self.errors.len() as u64;
+
+        if error_count > self.max_errors {
+            return Err(RetailerError::ErrorBudgetExceeded(
+                error_count,
+                self.max_errors,
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn get_errors(&self) -> &[String] {
+        &self.errors
     }
 }
 
 #[async_trait]
 pub(crate) trait Client {
-    async fn crawl(&mut self) -> Result<(), RetailerError>;
-
-    fn get_results(&self) -> Vec<&CrawlResult>;
+    /// Streams every result through `sink` as it's found, picking
+    /// up from `checkpoint` when resuming an interrupted crawl
+    async fn crawl(
+        &mut self,
+        sink: &mut ResultSink,
+        checkpoint: Option<&CollectionCrawlCheckpoint>,
+    ) -> Result<(), RetailerError>;
 
     fn get_retailer_name(&self) -> RetailerName;
 
-    fn emit_metrics(&self) {
-        for result in self.get_results() {
-            let metric = match result.category {
-                Category::Firearm => Some(Metrics::CrawledFirearm),
-                Category::Ammunition => Some(Metrics::CrawledAmmunition),
-                Category::Other => Some(Metrics::CrawledOther),
-                _ => None,
-            };
-
-            if let Some(metric) = metric {
-                put_metric!(metric, 1, "retailer" => self.get_retailer_name().to_string());
-            }
+    /// Errors for products that were skipped during the crawl
+    fn get_skipped(&self) -> &[String];
 
-            if result.category == Category::Ammunition {
-                let mut has_metadata = false;
+    /// Pages that looked wrong, ie. missing products or repeating
+    fn get_page_anomalies(&self) -> &[PageAnomaly];
 
-                if let Some(Metadata::Ammunition { .. }) = &result.metadata {
-                    has_metadata = true;
-                }
+    /// Number of pages requested from the retailer during the crawl
+    fn get_pages_fetched(&self) -> u64;
 
-                if !has_metadata {
-                    put_metric!(
-                        Metrics::CrawledAmmunitionNoRoundCount,
-                        1,
-                        "retailer" => self.get_retailer_name().to_string()
-                    );
-                }
-            }
+    fn emit_metrics(&self, counts: &ResultCounts) {
+        let retailer = self.get_retailer_name().to_string();
+
+        let category_metrics = [
+            (Metrics::CrawledFirearm, counts.firearms),
+            (Metrics::CrawledAmmunition, counts.ammunition),
+            (Metrics::CrawledOther, counts.other),
+        ];
+
+        for (metric, count) in category_metrics {
+            put_metric!(metric, count, "retailer" => retailer.clone());
+        }
+
+        let no_round_count = counts.ammunition - counts.ammunition_with_metadata;
+
+        if no_round_count > 0 {
+            put_metric!(
+                Metrics::CrawledAmmunitionNoRoundCount,
+                no_round_count,
+                "retailer" => retailer
+            );
+        }
+
+        if !self.get_skipped().is_empty() {
+            put_metric!(
+                Metrics::SkippedProduct,
+                self.get_skipped().len() as u64,
+                "retailer" => self.get_retailer_name().to_string()
+            );
         }
     }
 }
@@ -1,66 +1,158 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashSet, time::Duration};
 
 use async_trait::async_trait;
 use common::{
-    constants::CRAWL_COOLDOWN_SECS,
-    result::{base::CrawlResult, enums::RetailerName},
+    constants::{CRAWL_COOLDOWN_SECS, MAX_PAGES_PER_TERM},
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    result::enums::{Category, RetailerName},
 };
 use crawler::unprotected::UnprotectedCrawler;
-use retailers::{errors::RetailerError, structures::GqlRetailerSuper};
+use retailers::{
+    errors::RetailerError,
+    structures::{GqlRetailerSuper, PaginationStrategy},
+};
 use tokio::time::sleep;
-use tracing::debug;
+use tracing::{debug, warn};
 
-use crate::clients::base::{Client, insert_result};
+use crate::{
+    clients::{
+        base::{Client, ErrorBudget, PageAnomaly, PageAnomalyKind},
+        sink::ResultSink,
+    },
+    shutdown::is_shutting_down,
+};
 
 pub(crate) struct GqlClient {
     retailer: Box<dyn GqlRetailerSuper>,
-    results: HashMap<String, CrawlResult>,
+    // product URLs are resolved against this
+    base_url: &'static str,
+    error_budget: ErrorBudget,
+    page_anomalies: Vec<PageAnomaly>,
+    pages_fetched: u64,
+    max_requests: Option<u64>,
 }
 
 impl GqlClient {
-    pub(crate) fn new(retailer: Box<dyn GqlRetailerSuper>) -> Self {
+    pub(crate) fn new(
+        retailer: Box<dyn GqlRetailerSuper>,
+        base_url: &'static str,
+        max_product_errors: u64,
+        max_requests: Option<u64>,
+    ) -> Self {
         Self {
             retailer,
-            results: HashMap::new(),
+            base_url,
+            error_budget: ErrorBudget::new(max_product_errors),
+            page_anomalies: Vec::new(),
+            pages_fetched: 0,
+            max_requests,
         }
     }
+
+    fn record_page_anomaly(&mut self, page: u64, kind: PageAnomalyKind) {
+        // GQL retailers don't have search terms, they page through everything at once
+        let page_anomaly = PageAnomaly {
+            category: Category::_All,
+            term: "graphql".into(),
+            page,
+            kind,
+        };
+
+        warn!("{:?}: {page_anomaly}", self.retailer.get_retailer_name());
+
+        self.page_anomalies.push(page_anomaly);
+    }
 }
 
 #[async_trait]
 impl Client for GqlClient {
-    async fn crawl(&mut self) -> Result<(), RetailerError> {
-        let mut pagination_token: Option<String> = None;
+    async fn crawl(
+        &mut self,
+        sink: &mut ResultSink,
+        checkpoint: Option<&CollectionCrawlCheckpoint>,
+    ) -> Result<(), RetailerError> {
+        let strategy = self.retailer.get_pagination_strategy();
+
+        if !matches!(strategy, PaginationStrategy::Cursor) {
+            return Err(RetailerError::GeneralError(format!(
+                "GQL retailers can only follow cursors, not {strategy:?}"
+            )));
+        }
+
+        let mut pagination_token: Option<String> =
+            checkpoint.and_then(|checkpoint| checkpoint.pagination_token.clone());
+        let mut seen_tokens: HashSet<String> = HashSet::new();
+        let mut current_page: u64 = checkpoint.map_or(0, |checkpoint| checkpoint.page);
 
         loop {
+            if current_page >= MAX_PAGES_PER_TERM {
+                self.record_page_anomaly(current_page, PageAnomalyKind::PageCapReached);
+                break;
+            }
+
+            // the checkpoint already points at this page, so --resume picks up from here
+            if is_shutting_down() {
+                return Err(RetailerError::Interrupted);
+            }
+
+            if let Some(max_requests) = self.max_requests
+                && self.pages_fetched >= max_requests
+            {
+                return Err(RetailerError::RequestBudgetExceeded(max_requests));
+            }
+
             debug!("Using token: {pagination_token:?}");
             let request = self.retailer.build_page_request(pagination_token).await?;
 
             let response = UnprotectedCrawler::make_web_request(request).await?;
+            self.pages_fetched += 1;
             let response_body = response.body;
 
             pagination_token = self.retailer.get_pagination_token(&response_body)?;
 
-            let results = self.retailer.parse_response(&response_body).await?;
+            let mut parsed_page = self.retailer.parse_response(&response_body).await?;
+            parsed_page.canonicalize(self.base_url, &self.retailer.get_url_rules());
 
-            for crawled_result in results {
-                insert_result(&mut self.results, crawled_result);
+            for crawled_result in parsed_page.results {
+                sink.insert(crawled_result).await;
             }
 
-            if pagination_token.is_none() {
+            self.error_budget.spend(parsed_page.errors)?;
+
+            let Some(token) = &pagination_token else {
+                break;
+            };
+
+            // a cursor we've already used would send us around in circles
+            if !seen_tokens.insert(token.clone()) {
+                self.record_page_anomaly(current_page + 1, PageAnomalyKind::Repeated);
                 break;
             }
 
+            current_page += 1;
+
+            sink.checkpoint(0, current_page, pagination_token.clone())
+                .await;
+
             sleep(Duration::from_secs(CRAWL_COOLDOWN_SECS)).await;
         }
 
         Ok(())
     }
 
-    fn get_results(&self) -> Vec<&CrawlResult> {
-        self.results.values().collect()
-    }
-
     fn get_retailer_name(&self) -> RetailerName {
         self.retailer.get_retailer_name()
     }
+
+    fn get_skipped(&self) -> &[String] {
+        self.error_budget.get_errors()
+    }
+
+    fn get_page_anomalies(&self) -> &[PageAnomaly] {
+        &self.page_anomalies
+    }
+
+    fn get_pages_fetched(&self) -> u64 {
+        self.pages_fetched
+    }
 }
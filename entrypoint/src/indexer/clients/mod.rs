@@ -1,4 +1,5 @@
 pub(crate) mod base;
 pub(crate) mod graphql_client;
 pub(crate) mod pagination_client;
+pub(crate) mod sink;
 pub(super) mod utils;
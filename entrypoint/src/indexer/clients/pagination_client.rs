@@ -1,90 +1,449 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    cmp::min,
+    collections::{HashSet, VecDeque},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use common::{
-    constants::CRAWL_COOLDOWN_SECS,
-    result::{base::CrawlResult, enums::RetailerName},
+    constants::{CRAWL_COOLDOWN_SECS, MAX_PAGES_PER_TERM},
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    result::enums::RetailerName,
 };
 use crawler::{request::Request, unprotected::UnprotectedCrawler};
+use futures::{StreamExt, stream};
 use retailers::{
     errors::RetailerError,
-    structures::{HtmlRetailerSuper, HtmlSearchQuery},
+    structures::{HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage},
+};
+use tokio::{
+    sync::{
+        Semaphore,
+        mpsc::{UnboundedSender, unbounded_channel},
+    },
+    task::JoinSet,
+    time::sleep,
 };
-use tokio::time::sleep;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
-use crate::clients::base::{Client, insert_result};
+use crate::{
+    clients::{
+        base::{Client, ErrorBudget, PageAnomaly, PageAnomalyKind, get_page_fingerprint},
+        sink::ResultSink,
+    },
+    shutdown::is_shutting_down,
+};
 
-pub(crate) struct PaginationClient {
+/// What a term's crawl sends back to be merged, pages in page order
+enum TermEvent {
+    Page {
+        // 0 indexed
+        page: u64,
+        // 0 when the strategy doesn't know the page count up front
+        num_pages: u64,
+        parsed_page: ParsedPage,
+        has_next_page: bool,
+    },
+    // 1 indexed page that repeated an earlier page, everything after it is dropped
+    Repeated(u64),
+    PageCapReached,
+    Finished(Result<(), RetailerError>),
+}
+
+/// Hands the pages of a single search term on to be merged as they come in
+struct TermPages {
+    events: UnboundedSender<TermEvent>,
+    seen_pages: HashSet<u64>,
+}
+
+impl TermPages {
+    /// Returns `false` if the page repeats an earlier one, and was not sent
+    fn push_page(
+        &mut self,
+        page: u64,
+        num_pages: u64,
+        parsed_page: ParsedPage,
+        has_next_page: bool,
+    ) -> bool {
+        if !parsed_page.results.is_empty()
+            && !self
+                .seen_pages
+                .insert(get_page_fingerprint(&parsed_page.results))
+        {
+            self.send(TermEvent::Repeated(page + 1));
+            return false;
+        }
+
+        self.send(TermEvent::Page {
+            page,
+            num_pages,
+            parsed_page,
+            has_next_page,
+        });
+        true
+    }
+
+    fn send(&self, event: TermEvent) {
+        // the receiver is only gone once the crawl has already given up on this term
+        let _ = self.events.send(event);
+    }
+}
+
+/// The parts of the client needed to fetch pages, split out so the terms
+/// being fetched don't hold onto the client while earlier terms are merged
+struct PageFetcher {
     retailer: Box<dyn HtmlRetailerSuper>,
-    max_pages: u64,
-    results: HashMap<String, CrawlResult>,
+    // product URLs are resolved against this
+    base_url: &'static str,
+    page_concurrency: usize,
+    // shared between every search term, so the retailer never sees
+    // more than `page_concurrency` requests from us at once
+    request_slots: Semaphore,
+    pages_fetched: AtomicU64,
+    max_requests: Option<u64>,
+}
+
+pub(crate) struct PaginationClient {
+    fetcher: Arc<PageFetcher>,
+    error_budget: ErrorBudget,
+    page_anomalies: Vec<PageAnomaly>,
 }
 
 #[async_trait]
 impl Client for PaginationClient {
-    async fn crawl(&mut self) -> Result<(), RetailerError> {
-        for term in self.retailer.get_search_terms() {
-            self.paginate_calls(term).await?;
+    async fn crawl(
+        &mut self,
+        sink: &mut ResultSink,
+        checkpoint: Option<&CollectionCrawlCheckpoint>,
+    ) -> Result<(), RetailerError> {
+        // the checkpointed page is only where the checkpointed term picks back up
+        let (start_term, start_page) = checkpoint.map_or((0, 0), |checkpoint| {
+            (checkpoint.term_index as usize, checkpoint.page)
+        });
+
+        let mut terms = self
+            .fetcher
+            .retailer
+            .get_search_terms()
+            .into_iter()
+            .enumerate()
+            .skip(start_term);
+
+        // dropping the set aborts any term still being fetched
+        let mut term_tasks = JoinSet::new();
+        let mut fetching_terms = VecDeque::new();
+
+        loop {
+            // later terms keep fetching while earlier ones are merged, but terms are
+            // always merged in order, so the merge is the same no matter which finishes first
+            while fetching_terms.len() < self.fetcher.page_concurrency
+                && let Some((term_index, term)) = terms.next()
+            {
+                let (events, receiver) = unbounded_channel();
+                let fetcher = self.fetcher.clone();
+                let fetched_term = term.clone();
+                let term_start_page = match term_index == start_term {
+                    true => start_page,
+                    false => 0,
+                };
+
+                term_tasks.spawn(async move {
+                    let mut term_pages = TermPages {
+                        events,
+                        seen_pages: HashSet::new(),
+                    };
+
+                    let result = fetcher
+                        .crawl_term(&fetched_term, term_start_page, &mut term_pages)
+                        .await;
+                    term_pages.send(TermEvent::Finished(result));
+                });
+
+                fetching_terms.push_back((term_index, term, receiver));
+            }
+
+            let Some((term_index, term, mut receiver)) = fetching_terms.pop_front() else {
+                break;
+            };
+
+            loop {
+                let Some(event) = receiver.recv().await else {
+                    return Err(RetailerError::GeneralError(format!(
+                        "Crawl of {} stopped before finishing",
+                        term.term
+                    )));
+                };
+
+                match event {
+                    TermEvent::Page {
+                        page,
+                        num_pages,
+                        parsed_page,
+                        has_next_page,
+                    } => {
+                        self.merge_page(sink, &term, page, num_pages, parsed_page)
+                            .await?;
+
+                        match has_next_page {
+                            true => sink.checkpoint(term_index as u64, page + 1, None).await,
+                            false => sink.checkpoint(term_index as u64 + 1, 0, None).await,
+                        }
+                    }
+                    TermEvent::Repeated(page) => {
+                        self.record_page_anomaly(&term, page, PageAnomalyKind::Repeated);
+                    }
+                    TermEvent::PageCapReached => {
+                        self.record_page_anomaly(
+                            &term,
+                            MAX_PAGES_PER_TERM,
+                            PageAnomalyKind::PageCapReached,
+                        );
+                    }
+                    TermEvent::Finished(Ok(())) => break,
+                    TermEvent::Finished(Err(RetailerError::Interrupted)) => {
+                        // later terms can still have pages in flight, let those finish,
+                        // every page they'd start from here on fails straight away
+                        while term_tasks.join_next().await.is_some() {}
+
+                        return Err(RetailerError::Interrupted);
+                    }
+                    TermEvent::Finished(Err(err)) => return Err(err),
+                }
+            }
+
+            sink.checkpoint(term_index as u64 + 1, 0, None).await;
         }
 
         Ok(())
     }
 
-    fn get_results(&self) -> Vec<&CrawlResult> {
-        self.results.values().collect()
+    fn get_retailer_name(&self) -> RetailerName {
+        self.fetcher.retailer.get_retailer_name()
     }
 
-    fn get_retailer_name(&self) -> RetailerName {
-        self.retailer.get_retailer_name()
+    fn get_skipped(&self) -> &[String] {
+        self.error_budget.get_errors()
+    }
+
+    fn get_page_anomalies(&self) -> &[PageAnomaly] {
+        &self.page_anomalies
+    }
+
+    fn get_pages_fetched(&self) -> u64 {
+        self.fetcher.pages_fetched.load(Ordering::Relaxed)
     }
 }
 
 impl PaginationClient {
-    pub(crate) fn new(retailer: Box<dyn HtmlRetailerSuper>) -> Self {
+    pub(crate) fn new(
+        retailer: Box<dyn HtmlRetailerSuper>,
+        base_url: &'static str,
+        max_product_errors: u64,
+        page_concurrency: usize,
+        max_requests: Option<u64>,
+    ) -> Self {
+        let page_concurrency = page_concurrency.max(1);
+
         Self {
-            retailer,
-            max_pages: 1,
-            results: HashMap::new(),
+            fetcher: Arc::new(PageFetcher {
+                retailer,
+                base_url,
+                page_concurrency,
+                request_slots: Semaphore::new(page_concurrency),
+                pages_fetched: AtomicU64::new(0),
+                max_requests,
+            }),
+            error_budget: ErrorBudget::new(max_product_errors),
+            page_anomalies: Vec::new(),
         }
     }
 
-    pub(crate) fn update_max_pages(&mut self, max_page: u64) {
-        self.max_pages = max_page;
+    fn record_page_anomaly(&mut self, term: &HtmlSearchQuery, page: u64, kind: PageAnomalyKind) {
+        let page_anomaly = PageAnomaly {
+            category: term.category,
+            term: term.term.clone(),
+            page,
+            kind,
+        };
+
+        warn!("{:?}: {page_anomaly}", self.get_retailer_name());
+
+        self.page_anomalies.push(page_anomaly);
+    }
+
+    async fn merge_page(
+        &mut self,
+        sink: &mut ResultSink,
+        term: &HtmlSearchQuery,
+        page: u64,
+        num_pages: u64,
+        parsed_page: ParsedPage,
+    ) -> Result<(), RetailerError> {
+        // a single empty page can just be an empty category, but when the retailer
+        // says there's multiple pages, every one of them should have something on it
+        if num_pages > 1 && parsed_page.get_listing_count() == 0 {
+            self.record_page_anomaly(term, page + 1, PageAnomalyKind::Empty { num_pages });
+        }
+
+        let taxonomy_node = term.get_taxonomy_node();
+
+        for mut crawled_result in parsed_page.results {
+            // retailers that know better have already set the taxonomy themselves
+            if let Some(node) = taxonomy_node
+                && crawled_result.taxonomy.is_empty()
+            {
+                crawled_result = crawled_result.with_taxonomy(node);
+            }
+
+            sink.insert(crawled_result).await;
+        }
+
+        self.error_budget.spend(parsed_page.errors)
     }
+}
+
+impl PageFetcher {
+    /// Fetches the pages of a single search term from `start_page` on, sending
+    /// each one on to be merged as soon as it's in
+    async fn crawl_term(
+        &self,
+        term: &HtmlSearchQuery,
+        start_page: u64,
+        term_pages: &mut TermPages,
+    ) -> Result<(), RetailerError> {
+        let (response, first_page) = self.fetch_page(start_page, term).await?;
 
-    async fn paginate_calls(&mut self, term: HtmlSearchQuery) -> Result<(), RetailerError> {
-        self.update_max_pages(1);
-        let mut current_page: u64 = 0;
+        match self.retailer.get_pagination_strategy() {
+            PaginationStrategy::PageCount => {
+                let num_pages = self.retailer.get_num_pages(&response)?;
+                let last_page = min(num_pages, MAX_PAGES_PER_TERM);
+                debug!("Found {num_pages} pages for {}", term.term);
 
-        while current_page < self.max_pages {
-            let request = self
-                .retailer
-                .build_page_request(current_page, &term)
-                .await?;
+                if num_pages > MAX_PAGES_PER_TERM {
+                    term_pages.send(TermEvent::PageCapReached);
+                }
 
-            let response = self.send_request(request).await?;
-            trace!("{response:?}");
+                if !term_pages.push_page(
+                    start_page,
+                    num_pages,
+                    first_page,
+                    start_page + 1 < last_page,
+                ) {
+                    return Ok(());
+                }
 
-            // commit a sin and attempt to change the loop conditions mid loop iteration
-            self.update_max_pages(self.retailer.get_num_pages(&response)?);
-            debug!("Changing max pages to {}", self.max_pages);
+                // the page count is known at this point, so the rest can be fetched at once
+                let mut remaining_pages = stream::iter(start_page + 1..last_page)
+                    .map(|page| async move { (page, self.fetch_page(page, term).await) })
+                    .buffered(self.page_concurrency);
 
-            let results = self.retailer.parse_response(&response, &term).await?;
+                while let Some((page, fetched_page)) = remaining_pages.next().await {
+                    let (_, parsed_page) = fetched_page?;
 
-            for crawled_result in results {
-                insert_result(&mut self.results, crawled_result);
+                    if !term_pages.push_page(page, num_pages, parsed_page, page + 1 < last_page) {
+                        break;
+                    }
+                }
+            }
+            PaginationStrategy::SinglePage => {
+                term_pages.push_page(start_page, 0, first_page, false);
+            }
+            PaginationStrategy::Cursor => {
+                return Err(RetailerError::GeneralError(
+                    "Cursor pagination is only supported for GQL retailers".into(),
+                ));
             }
+            strategy => {
+                // whether there's a next page is only known once the current one is in,
+                // so these have to go one by one
+                let mut current_page = start_page;
+                let mut num_pages = 0;
+                let (mut response, mut parsed_page) = (response, first_page);
 
-            current_page += 1;
+                loop {
+                    let page_size = parsed_page.get_listing_count();
 
-            sleep(Duration::from_secs(CRAWL_COOLDOWN_SECS)).await;
+                    let has_next_page = match strategy {
+                        PaginationStrategy::PageNumber => {
+                            // the pager can move along with the current page, so keep re-reading it
+                            num_pages = self.retailer.get_num_pages(&response)?;
+                            debug!("Found {num_pages} pages for {}", term.term);
+
+                            current_page + 1 < num_pages
+                        }
+                        PaginationStrategy::OffsetLimit { limit } => page_size >= limit,
+                        _ => page_size > 0 && !self.retailer.is_last_page(&response)?,
+                    };
+                    let hit_page_cap = has_next_page && current_page + 1 >= MAX_PAGES_PER_TERM;
+
+                    if !term_pages.push_page(
+                        current_page,
+                        num_pages,
+                        parsed_page,
+                        has_next_page && !hit_page_cap,
+                    ) || !has_next_page
+                    {
+                        break;
+                    }
+
+                    if hit_page_cap {
+                        term_pages.send(TermEvent::PageCapReached);
+                        break;
+                    }
+
+                    current_page += 1;
+
+                    (response, parsed_page) = self.fetch_page(current_page, term).await?;
+                }
+            }
         }
 
         Ok(())
     }
 
-    async fn send_request(&mut self, request: Request) -> Result<String, RetailerError> {
+    async fn fetch_page(
+        &self,
+        page: u64,
+        term: &HtmlSearchQuery,
+    ) -> Result<(String, ParsedPage), RetailerError> {
+        let _permit = self
+            .request_slots
+            .acquire()
+            .await
+            .expect("Request semaphore to never be closed");
+
+        if is_shutting_down() {
+            return Err(RetailerError::Interrupted);
+        }
+
+        let pages_fetched = self.pages_fetched.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(max_requests) = self.max_requests
+            && pages_fetched >= max_requests
+        {
+            self.pages_fetched.fetch_sub(1, Ordering::Relaxed);
+            return Err(RetailerError::RequestBudgetExceeded(max_requests));
+        }
+
+        let request = self.retailer.build_page_request(page, term).await?;
+
+        let response = self.send_request(request).await?;
+        trace!("{response:?}");
+
+        let mut parsed_page = self.retailer.parse_response(&response, term).await?;
+        parsed_page.canonicalize(self.base_url, &self.retailer.get_url_rules());
+
+        // hold onto the slot during the cooldown, keeping the per retailer request rate polite
+        sleep(Duration::from_secs(CRAWL_COOLDOWN_SECS)).await;
+
+        Ok((response, parsed_page))
+    }
+
+    async fn send_request(&self, request: Request) -> Result<String, RetailerError> {
         Ok(UnprotectedCrawler::make_web_request(request).await?.body)
     }
 }
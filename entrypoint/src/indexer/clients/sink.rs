@@ -0,0 +1,183 @@
+use std::{collections::HashMap, sync::Arc};
+
+use common::{
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    result::{
+        base::CrawlResult,
+        counts::ResultCounts,
+        enums::{Category, RetailerName},
+    },
+};
+use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tracing::warn;
+
+use crate::{
+    clients::utils::{get_key, get_result_rank},
+    currency_policy::{CurrencyPolicy, CurrencyReport},
+    rules::{RuleAction, RuleDecisions, RulesEngine},
+};
+
+/// Results buffered between a client and the writer, once it's full a slow
+/// database holds the crawl up instead of results piling up in memory
+const STREAM_CAPACITY: usize = 1000;
+
+#[derive(Debug)]
+pub(crate) enum StreamedResult {
+    New(CrawlResult),
+    /// A better ranked duplicate of a result that was already streamed
+    Replacement(CrawlResult),
+    /// Everything streamed before this point is part of the checkpoint
+    Checkpoint(CollectionCrawlCheckpoint),
+}
+
+/// Just enough of a streamed result to dedup against, and to undo its counts
+struct SeenResult {
+    rank: (i64, usize),
+    category: Category,
+    has_metadata: bool,
+}
+
+impl SeenResult {
+    fn new(result: &CrawlResult) -> Self {
+        Self {
+            rank: get_result_rank(result),
+            category: result.category,
+            has_metadata: result.metadata.is_some(),
+        }
+    }
+}
+
+/// What's left over once a retailer is done streaming
+pub(crate) struct SinkReport {
+    pub(crate) counts: ResultCounts,
+    pub(crate) rule_decisions: RuleDecisions,
+    pub(crate) currency_report: CurrencyReport,
+}
+
+/// Receives every result a client crawls, applies the rules and currency policy,
+/// dedups it and streams it out to the writer, instead of holding onto the
+/// entire catalogue until the retailer finishes
+pub(crate) struct ResultSink {
+    retailer: RetailerName,
+    crawl_start: u64,
+    sender: Sender<StreamedResult>,
+    seen: HashMap<String, SeenResult>,
+    counts: ResultCounts,
+    rules: Arc<RulesEngine>,
+    rule_decisions: RuleDecisions,
+    currency_policy: Arc<CurrencyPolicy>,
+    currency_report: CurrencyReport,
+}
+
+impl ResultSink {
+    pub(crate) fn new(
+        retailer: RetailerName,
+        crawl_start: u64,
+        rules: Arc<RulesEngine>,
+        currency_policy: Arc<CurrencyPolicy>,
+    ) -> (Self, Receiver<StreamedResult>) {
+        let (sender, receiver) = channel(STREAM_CAPACITY);
+
+        let sink = Self {
+            retailer,
+            crawl_start,
+            sender,
+            seen: HashMap::new(),
+            counts: ResultCounts::new(),
+            rules,
+            rule_decisions: RuleDecisions::default(),
+            currency_policy,
+            currency_report: CurrencyReport::default(),
+        };
+
+        (sink, receiver)
+    }
+
+    pub(crate) async fn insert(&mut self, mut new_result: CrawlResult) {
+        // price bounds in the rules are in CAD, convert before they're checked
+        if !self
+            .currency_policy
+            .apply(&mut new_result, &mut self.currency_report)
+        {
+            return;
+        }
+
+        // rules run before dedup, so the corrected category is the one being ranked
+        let decision = self.rules.apply(&mut new_result);
+        let key = get_key(&new_result);
+
+        if let Some(decision) = decision {
+            let is_dropped = matches!(decision.action, RuleAction::Drop);
+
+            self.rule_decisions.record(key.clone(), decision);
+
+            if is_dropped {
+                return;
+            }
+        }
+        let new_seen = SeenResult::new(&new_result);
+
+        // deal with retailers that have the same product in multiple places,
+        // ties keep the first one since it has already been written out
+        let streamed = match self.seen.get(&key) {
+            Some(existing) if existing.rank >= new_seen.rank => return,
+            Some(existing) => {
+                self.counts.remove(existing.category, existing.has_metadata);
+                StreamedResult::Replacement(new_result)
+            }
+            None => StreamedResult::New(new_result),
+        };
+
+        self.counts.add(new_seen.category, new_seen.has_metadata);
+        self.seen.insert(key, new_seen);
+
+        if self.sender.send(streamed).await.is_err() {
+            warn!("Result writer has stopped, dropping result");
+        }
+    }
+
+    /// Picks the dedup state and counts back up from results an
+    /// interrupted crawl already wrote, without streaming them again
+    pub(crate) fn restore(&mut self, results: Vec<CrawlResult>) {
+        for result in results {
+            let seen = SeenResult::new(&result);
+
+            self.counts.add(seen.category, seen.has_metadata);
+            self.seen.insert(get_key(&result), seen);
+        }
+    }
+
+    /// Marks where the crawl should pick back up from if it gets interrupted
+    pub(crate) async fn checkpoint(
+        &mut self,
+        term_index: u64,
+        page: u64,
+        pagination_token: Option<String>,
+    ) {
+        let checkpoint = CollectionCrawlCheckpoint::new(
+            self.retailer,
+            self.crawl_start,
+            term_index,
+            page,
+            pagination_token,
+        );
+
+        if self
+            .sender
+            .send(StreamedResult::Checkpoint(checkpoint))
+            .await
+            .is_err()
+        {
+            warn!("Result writer has stopped, dropping checkpoint");
+        }
+    }
+
+    /// Closes the stream, letting the writer flush whatever it has left
+    pub(crate) fn finish(self) -> SinkReport {
+        SinkReport {
+            counts: self.counts,
+            rule_decisions: self.rule_decisions,
+            currency_report: self.currency_report,
+        }
+    }
+}
@@ -12,6 +12,15 @@ pub(crate) fn get_category_tier(category: Category) -> i64 {
     }
 }
 
+/// Ranks duplicated entries, the category tier comes first, and
+/// within the same category the more specific taxonomy node wins
+pub(crate) fn get_result_rank(crawl_result: &CrawlResult) -> (i64, usize) {
+    (
+        get_category_tier(crawl_result.category),
+        crawl_result.taxonomy.len(),
+    )
+}
+
 /// Creates a "unique" key for the results hashing to dedupe products
 pub(crate) fn get_key(crawl_result: &CrawlResult) -> String {
     format!("{}{}", crawl_result.name, crawl_result.url)
@@ -0,0 +1,79 @@
+use std::collections::{BTreeMap, HashMap};
+
+use clap::ValueEnum;
+use common::{
+    money::{Currency, ExchangeRate},
+    result::base::CrawlResult,
+};
+use tracing::warn;
+
+/// What to do with a result that isn't priced in CAD
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub(crate) enum CurrencyMode {
+    /// Drop any non-CAD result
+    #[default]
+    Reject,
+    /// Convert non-CAD results using the supplied exchange rates,
+    /// results without a matching rate are dropped
+    Convert,
+}
+
+/// Counts of results found in an unexpected currency, per currency
+#[derive(Debug, Default)]
+pub(crate) struct CurrencyReport {
+    pub(crate) rejected: BTreeMap<Currency, u64>,
+    pub(crate) converted: BTreeMap<Currency, u64>,
+}
+
+impl CurrencyReport {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rejected.is_empty() && self.converted.is_empty()
+    }
+}
+
+pub(crate) struct CurrencyPolicy {
+    mode: CurrencyMode,
+    rates: HashMap<Currency, f64>,
+}
+
+impl CurrencyPolicy {
+    pub(crate) fn new(mode: CurrencyMode, rates: Vec<ExchangeRate>) -> Self {
+        Self {
+            mode,
+            rates: rates
+                .into_iter()
+                .map(|exchange_rate| (exchange_rate.currency, exchange_rate.rate))
+                .collect(),
+        }
+    }
+
+    /// Converts the result in place, returning `false` when it should be dropped
+    pub(crate) fn apply(&self, result: &mut CrawlResult, report: &mut CurrencyReport) -> bool {
+        let currency = result.price.currency;
+
+        if currency == Currency::CAD {
+            return true;
+        }
+
+        let converted = match (self.mode, self.rates.get(&currency)) {
+            (CurrencyMode::Convert, Some(rate)) => result.price.convert(Currency::CAD, *rate),
+            _ => {
+                *report.rejected.entry(currency).or_default() += 1;
+                return false;
+            }
+        };
+
+        match converted {
+            Ok(price) => {
+                result.price = price;
+                *report.converted.entry(currency).or_default() += 1;
+                true
+            }
+            Err(err) => {
+                warn!("Failed to convert {} from {currency}: {err}", result.url);
+                *report.rejected.entry(currency).or_default() += 1;
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::{fs, path::PathBuf};
+
+use clap::{Args, ValueEnum};
+use common::result::{
+    base::CrawlResult,
+    enums::{Category, RetailerName},
+};
+use crawler::{request::Request, unprotected::UnprotectedCrawler};
+use retailers::{
+    errors::RetailerError,
+    registry::{RetailerFactory, get_registered_retailers},
+    structures::{GqlRetailerSuper, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum DebugError {
+    #[error("{0:?} is not a registered retailer")]
+    UnknownRetailer(RetailerName),
+    #[error("Retailer has no search terms, some retailers only load them during init, pass --term")]
+    NoSearchTerms,
+    #[error("Page numbers start at 1")]
+    InvalidPage,
+    #[error("Failed to read HTML file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize report: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Retailer(#[from] RetailerError),
+}
+
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub(crate) enum DebugFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+#[derive(Args)]
+pub(crate) struct DebugArguments {
+    /// Retailer to run the parser of
+    #[arg(short, long)]
+    retailer: RetailerName,
+    /// Search term to build the request for, uses the retailer's first term by default
+    #[arg(short, long)]
+    term: Option<String>,
+    /// Category of --term, decides the category of the parsed results
+    #[arg(short, long, value_enum, default_value_t = Category::Other, requires = "term")]
+    category: Category,
+    /// Page of the search term to fetch, starting at 1
+    #[arg(short, long, default_value_t = 1)]
+    page: u64,
+    /// Pagination token to fetch with, only used by GQL retailers
+    #[arg(long)]
+    pagination_token: Option<String>,
+    /// Parses a saved response instead of fetching the page
+    #[arg(long)]
+    html_file: Option<PathBuf>,
+    /// How the report is printed
+    #[arg(short, long, value_enum, default_value_t = DebugFormat::Table)]
+    format: DebugFormat,
+}
+
+/// Everything the retailer's parser did with a single page
+#[derive(Serialize, Default)]
+struct DebugReport {
+    retailer: String,
+    term: Option<String>,
+    request: String,
+    pagination_strategy: Option<String>,
+    num_pages: Option<u64>,
+    last_page: Option<bool>,
+    next_pagination_token: Option<String>,
+    results: Vec<CrawlResult>,
+    errors: Vec<String>,
+    skipped: u64,
+}
+
+impl DebugReport {
+    fn print_table(&self) {
+        println!("Retailer:   {}", self.retailer);
+
+        if let Some(term) = &self.term {
+            println!("Term:       {term}");
+        }
+
+        println!("Request:    {}", self.request);
+
+        if let Some(pagination_strategy) = &self.pagination_strategy {
+            println!("Pagination: {pagination_strategy}");
+        }
+
+        if let Some(num_pages) = self.num_pages {
+            println!("Pages:      {num_pages}");
+        }
+
+        if let Some(last_page) = self.last_page {
+            println!("Last page:  {last_page}");
+        }
+
+        if let Some(token) = &self.next_pagination_token {
+            println!("Next token: {token}");
+        }
+
+        println!(
+            "\n{} results, {} errors, {} skipped\n",
+            self.results.len(),
+            self.errors.len(),
+            self.skipped
+        );
+
+        println!("{:<12} {:>16} {:>16}  NAME", "CATEGORY", "REGULAR", "SALE");
+
+        for result in &self.results {
+            let sale_price = result
+                .price
+                .sale()
+                .map_or(String::from("-"), |sale_price| sale_price.to_string());
+
+            println!(
+                "{:<12} {:>16} {:>16}  {}",
+                result.category.to_string(),
+                result.price.regular().to_string(),
+                sale_price,
+                result.name
+            );
+            println!("{:<12} {:>16} {:>16}  {}", "", "", "", result.url);
+        }
+
+        if !self.errors.is_empty() {
+            println!("\nErrors:");
+
+            for error in &self.errors {
+                println!("  {error}");
+            }
+        }
+    }
+}
+
+/// Runs a single page through a retailer's parser, without writing anything
+/// to the DB or Discord, so a broken parser can be reproduced without a crawl
+pub(crate) async fn run_debug(args: DebugArguments) -> Result<(), DebugError> {
+    if args.page == 0 {
+        return Err(DebugError::InvalidPage);
+    }
+
+    let registration = get_registered_retailers()
+        .find(|registration| registration.name == args.retailer)
+        .ok_or(DebugError::UnknownRetailer(args.retailer))?;
+
+    let report = match registration.factory {
+        RetailerFactory::Html(factory) => {
+            debug_html_retailer(factory(), registration.info.homepage, &args).await?
+        }
+        RetailerFactory::Gql(factory) => {
+            debug_gql_retailer(factory(), registration.info.homepage, &args).await?
+        }
+    };
+
+    match args.format {
+        DebugFormat::Table => report.print_table(),
+        DebugFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    Ok(())
+}
+
+async fn get_response(args: &DebugArguments, request: Request) -> Result<String, DebugError> {
+    match &args.html_file {
+        Some(html_file) => Ok(fs::read_to_string(html_file)?),
+        None => Ok(UnprotectedCrawler::make_web_request(request)
+            .await
+            .map_err(RetailerError::from)?
+            .body),
+    }
+}
+
+async fn debug_html_retailer(
+    mut retailer: Box<dyn HtmlRetailerSuper>,
+    base_url: &str,
+    args: &DebugArguments,
+) -> Result<DebugReport, DebugError> {
+    // init is usually just fetching tokens, which a saved response doesn't need
+    if args.html_file.is_none() {
+        retailer.init().await?;
+    }
+
+    // taken as is, retailers that load their terms during init don't have any without it
+    let search_term: HtmlSearchQuery = match &args.term {
+        Some(term) => HtmlSearchQuery {
+            term: term.clone(),
+            category: args.category,
+        },
+        None => retailer
+            .get_search_terms()
+            .first()
+            .cloned()
+            .ok_or(DebugError::NoSearchTerms)?,
+    };
+
+    let request = retailer
+        .build_page_request(args.page - 1, &search_term)
+        .await?;
+    let request_debug = format!("{request:?}");
+
+    let response = get_response(args, request).await?;
+
+    let pagination_strategy = retailer.get_pagination_strategy();
+    let num_pages = match pagination_strategy {
+        PaginationStrategy::PageNumber | PaginationStrategy::PageCount => {
+            Some(retailer.get_num_pages(&response)?)
+        }
+        _ => None,
+    };
+    let last_page = match pagination_strategy {
+        PaginationStrategy::UntilEmpty => Some(retailer.is_last_page(&response)?),
+        _ => None,
+    };
+
+    let mut parsed_page = retailer.parse_response(&response, &search_term).await?;
+    parsed_page.canonicalize(base_url, &retailer.get_url_rules());
+
+    Ok(DebugReport {
+        retailer: args.retailer.to_string(),
+        term: Some(search_term.term),
+        request: request_debug,
+        pagination_strategy: Some(format!("{pagination_strategy:?}")),
+        num_pages,
+        last_page,
+        results: parsed_page.results,
+        errors: parsed_page
+            .errors
+            .iter()
+            .map(|error| error.to_string())
+            .collect(),
+        skipped: parsed_page.skipped,
+        ..DebugReport::default()
+    })
+}
+
+async fn debug_gql_retailer(
+    mut retailer: Box<dyn GqlRetailerSuper>,
+    base_url: &str,
+    args: &DebugArguments,
+) -> Result<DebugReport, DebugError> {
+    if args.html_file.is_none() {
+        retailer.init().await?;
+    }
+
+    let request = retailer
+        .build_page_request(args.pagination_token.clone())
+        .await?;
+    let request_debug = format!("{request:?}");
+
+    let response = get_response(args, request).await?;
+
+    let next_pagination_token = retailer.get_pagination_token(&response)?;
+    let mut parsed_page = retailer.parse_response(&response).await?;
+    parsed_page.canonicalize(base_url, &retailer.get_url_rules());
+
+    Ok(DebugReport {
+        retailer: args.retailer.to_string(),
+        request: request_debug,
+        pagination_strategy: Some(format!("{:?}", retailer.get_pagination_strategy())),
+        next_pagination_token,
+        results: parsed_page.results,
+        errors: parsed_page
+            .errors
+            .iter()
+            .map(|error| error.to_string())
+            .collect(),
+        skipped: parsed_page.skipped,
+        ..DebugReport::default()
+    })
+}
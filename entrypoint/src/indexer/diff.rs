@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use common::{
+    crawl_diff::{CollectionCrawlDiff, DiffProduct, PriceChange},
+    crawl_run::CollectionCrawlRun,
+    result::{base::CrawlResult, enums::RetailerName},
+};
+use mongodb_connector::{errors::StorageError, storage::Storage};
+
+use crate::clients::utils::get_key;
+
+/// Compares a retailer's fresh crawl against whatever its last successful crawl
+/// left in the live view, needs to run before the fresh crawl gets finalized
+pub(crate) async fn get_crawl_diff(
+    db: &dyn Storage,
+    crawl_run: &CollectionCrawlRun,
+    retailer: RetailerName,
+    crawl_start: u64,
+) -> Result<CollectionCrawlDiff, StorageError> {
+    // without a previous crawl on record, the whole live view is used instead, crawls
+    // recorded before crawl starts were kept fall back to when the retailer started
+    let previous_start =
+        db.get_last_successful_crawl(retailer)
+            .await?
+            .map_or(0, |previous_crawl| {
+                previous_crawl
+                    .crawl_start
+                    .unwrap_or(previous_crawl.start_time)
+            });
+
+    let previous = db.get_live_results(retailer, previous_start).await?;
+    let current = db.get_results_since(retailer, crawl_start).await?;
+
+    let mut diff = CollectionCrawlDiff::new(crawl_run, retailer, crawl_start);
+    diff_results(&mut diff, &previous, &current);
+
+    Ok(diff)
+}
+
+fn diff_results(diff: &mut CollectionCrawlDiff, previous: &[CrawlResult], current: &[CrawlResult]) {
+    // the live view keeps a few days of crawls around, only the latest snapshot counts
+    let mut previous_results: HashMap<String, &CrawlResult> = HashMap::new();
+
+    for result in previous {
+        let key = get_diff_key(result);
+
+        match previous_results.get(&key) {
+            Some(existing) if existing.query_time >= result.query_time => {}
+            _ => {
+                previous_results.insert(key, result);
+            }
+        }
+    }
+
+    for result in current {
+        // snapshots from before the retailer parsed product IDs only have the name and URL
+        let previous_result = previous_results.remove(&get_diff_key(result)).or_else(|| {
+            result
+                .product_id
+                .as_ref()
+                .and_then(|_| previous_results.remove(&get_key(result)))
+        });
+
+        let Some(previous_result) = previous_result else {
+            diff.new_products.push(DiffProduct::from(result));
+            continue;
+        };
+
+        // prices in different currencies can't be compared
+        if previous_result.price.currency != result.price.currency {
+            continue;
+        }
+
+        let change = PriceChange::new(previous_result, result);
+
+        match (previous_result.price.sale_price, result.price.sale_price) {
+            (None, Some(_)) => diff.sales_started.push(change),
+            (Some(_), None) => diff.sales_ended.push(change),
+            _ if change.change < 0 => diff.price_drops.push(change),
+            _ if change.change > 0 => diff.price_increases.push(change),
+            _ => {}
+        }
+    }
+
+    diff.removed_products
+        .extend(previous_results.into_values().map(DiffProduct::from));
+}
+
+/// Variants sharing a URL are told apart by their product ID, results
+/// without one fall back to the same key the sink dedups on
+fn get_diff_key(result: &CrawlResult) -> String {
+    match &result.product_id {
+        Some(product_id) => format!("id:{product_id}"),
+        None => get_key(result),
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use common::{
+    money::Currency,
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    utils::{get_current_time, normalized_relative_days},
+};
+use mongodb_connector::{errors::StorageError, storage::Storage};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::{
+    clients::utils::get_key,
+    lock::{LockError, LockOptions, LockScope, RUN_LOCK_NAME, RunLock},
+};
+
+#[derive(Error, Debug)]
+pub(crate) enum ExportError {
+    #[error("Failed to access export file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to convert result to or from JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to convert result to or from CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Unknown export format for {0}, expected a .ndjson or .csv file")]
+    UnknownFormat(PathBuf),
+    #[error(transparent)]
+    Lock(#[from] LockError),
+    #[error("Failed to import results: {0}")]
+    Storage(#[from] StorageError),
+}
+
+// search only shows results crawled within this many days
+const SEARCH_WINDOW_DAYS: i64 = 2;
+
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// One JSON encoded result per line
+    #[default]
+    Ndjson,
+    /// Flattened results, nested fields are JSON encoded
+    Csv,
+}
+
+impl OutputFormat {
+    fn get_extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Csv => "csv",
+        }
+    }
+
+    fn from_path(path: &Path) -> Result<Self, ExportError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ndjson" | "jsonl") => Ok(OutputFormat::Ndjson),
+            Some("csv") => Ok(OutputFormat::Csv),
+            _ => Err(ExportError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+}
+
+/// A `CrawlResult` flattened into a single CSV row
+#[derive(Serialize, Deserialize)]
+struct CsvResult {
+    name: String,
+    url: String,
+    retailer: RetailerName,
+    category: Category,
+    // JSON encoded
+    taxonomy: String,
+    regular_price: u64,
+    sale_price: Option<u64>,
+    currency: Currency,
+    query_time: u64,
+    description: Option<String>,
+    image_url: Option<String>,
+    // JSON encoded
+    metadata: Option<String>,
+    // missing from exports written before products had IDs
+    #[serde(default)]
+    sku: Option<String>,
+    #[serde(default)]
+    product_id: Option<String>,
+}
+
+impl CsvResult {
+    fn new(result: &CrawlResult) -> Result<Self, ExportError> {
+        let metadata = match &result.metadata {
+            Some(metadata) => Some(serde_json::to_string(metadata)?),
+            None => None,
+        };
+
+        Ok(Self {
+            name: result.name.clone(),
+            url: result.url.clone(),
+            retailer: result.retailer,
+            category: result.category,
+            taxonomy: serde_json::to_string(&result.taxonomy)?,
+            regular_price: result.price.regular_price,
+            sale_price: result.price.sale_price,
+            currency: result.price.currency,
+            query_time: result.query_time,
+            description: result.description.clone(),
+            image_url: result.image_url.clone(),
+            metadata,
+            sku: result.sku.clone(),
+            product_id: result.product_id.clone(),
+        })
+    }
+
+    fn into_result(self) -> Result<CrawlResult, ExportError> {
+        let metadata = match self.metadata {
+            Some(metadata) => Some(serde_json::from_str(&metadata)?),
+            None => None,
+        };
+
+        // not using CrawlResult::new(), it would redo the metadata and query time
+        Ok(CrawlResult {
+            id: None,
+            name: self.name,
+            url: self.url,
+            price: Price {
+                regular_price: self.regular_price,
+                sale_price: self.sale_price,
+                currency: self.currency,
+            },
+            query_time: self.query_time,
+            retailer: self.retailer,
+            category: self.category,
+            taxonomy: serde_json::from_str(&self.taxonomy)?,
+            description: self.description,
+            image_url: self.image_url,
+            metadata,
+            sku: self.sku,
+            product_id: self.product_id,
+            is_variant: false,
+        })
+    }
+}
+
+/// Collects a retailer's deduplicated results while they're streamed
+/// out, and writes them to `<directory>/<retailer>.<format>` at the end
+pub(crate) struct ResultExport {
+    path: PathBuf,
+    format: OutputFormat,
+    results: Vec<CrawlResult>,
+    // index of each result in `results`, so replacements keep their place
+    positions: HashMap<String, usize>,
+}
+
+impl ResultExport {
+    pub(crate) fn new(directory: &Path, format: OutputFormat, retailer: RetailerName) -> Self {
+        let path = directory.join(format!("{retailer}.{}", format.get_extension()));
+
+        Self {
+            path,
+            format,
+            results: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Replaces an earlier result with the same key, keeping the results deduplicated
+    pub(crate) fn push(&mut self, result: &CrawlResult) {
+        let key = get_key(result);
+
+        match self.positions.get(&key) {
+            Some(position) => self.results[*position] = result.clone(),
+            None => {
+                self.positions.insert(key, self.results.len());
+                self.results.push(result.clone());
+            }
+        }
+    }
+
+    pub(crate) fn write(self) -> Result<(), ExportError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = BufWriter::new(File::create(&self.path)?);
+
+        match self.format {
+            OutputFormat::Ndjson => write_ndjson(file, &self.results)?,
+            OutputFormat::Csv => write_csv(file, &self.results)?,
+        }
+
+        info!(
+            "Exported {} results to {}",
+            self.results.len(),
+            self.path.display()
+        );
+
+        Ok(())
+    }
+}
+
+fn write_ndjson(mut file: impl Write, results: &[CrawlResult]) -> Result<(), ExportError> {
+    for result in results {
+        serde_json::to_writer(&mut file, result)?;
+        file.write_all(b"\n")?;
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+fn write_csv(file: impl Write, results: &[CrawlResult]) -> Result<(), ExportError> {
+    let mut writer = csv::Writer::from_writer(file);
+
+    for result in results {
+        writer.serialize(CsvResult::new(result)?)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn read_results(path: &Path) -> Result<Vec<CrawlResult>, ExportError> {
+    let file = BufReader::new(File::open(path)?);
+    let mut results: Vec<CrawlResult> = Vec::new();
+
+    match OutputFormat::from_path(path)? {
+        OutputFormat::Ndjson => {
+            for line in file.lines() {
+                let line = line?;
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                results.push(serde_json::from_str(&line)?);
+            }
+        }
+        OutputFormat::Csv => {
+            for row in csv::Reader::from_reader(file).deserialize::<CsvResult>() {
+                results.push(row?.into_result()?);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Loads exported results from a file, or every export in a directory, into
+/// crawl-results, then publishes them the same way a successful crawl would. Holds
+/// the run lock so no crawl writes at the same time.
+///
+/// Results older than the search window never make it into search, `restamp`
+/// stamps every result with the current time instead, publishing them as a fresh crawl
+pub(crate) async fn import_results(
+    db: &Arc<dyn Storage>,
+    path: &Path,
+    restamp: bool,
+) -> Result<(), ExportError> {
+    let paths: Vec<PathBuf> = match path.is_dir() {
+        true => {
+            let mut paths = Vec::new();
+
+            for entry in fs::read_dir(path)? {
+                let entry_path = entry?.path();
+
+                if OutputFormat::from_path(&entry_path).is_ok() {
+                    paths.push(entry_path);
+                }
+            }
+
+            paths.sort();
+            paths
+        }
+        false => vec![path.to_path_buf()],
+    };
+
+    let mut retailer_results: BTreeMap<RetailerName, Vec<CrawlResult>> = BTreeMap::new();
+
+    for path in paths {
+        let results = read_results(&path)?;
+
+        info!("Read {} results from {}", results.len(), path.display());
+
+        for result in results {
+            retailer_results
+                .entry(result.retailer)
+                .or_default()
+                .push(result);
+        }
+    }
+
+    if retailer_results.is_empty() {
+        warn!("Nothing to import from {}", path.display());
+        return Ok(());
+    }
+
+    let options = LockOptions {
+        scope: LockScope::Run,
+        ttl: Duration::from_secs(300),
+        wait: None,
+    };
+
+    let lock = RunLock::acquire(db, RUN_LOCK_NAME.into(), &options).await?;
+
+    let import_time = get_current_time();
+    let search_window_start = normalized_relative_days(SEARCH_WINDOW_DAYS) as u64;
+
+    for (retailer, mut results) in retailer_results {
+        if restamp {
+            for result in &mut results {
+                result.query_time = import_time;
+            }
+        }
+
+        let (Some(since), Some(latest)) = (
+            results.iter().map(|result| result.query_time).min(),
+            results.iter().map(|result| result.query_time).max(),
+        ) else {
+            continue;
+        };
+
+        if latest < search_window_start {
+            warn!(
+                "{retailer:?} results are older than the {SEARCH_WINDOW_DAYS} day search window and won't show up in search, import with --restamp to publish them as a fresh crawl"
+            );
+        }
+
+        let imported = match db.insert_results(results.iter().collect()).await {
+            Ok(()) => db.finalize_results(retailer, since).await,
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = imported {
+            lock.release().await;
+            return Err(err.into());
+        }
+
+        info!("Imported {} results for {retailer:?}", results.len());
+    }
+
+    lock.release().await;
+
+    Ok(())
+}
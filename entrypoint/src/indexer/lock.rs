@@ -0,0 +1,194 @@
+use std::{
+    env,
+    future::pending,
+    process,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use common::result::enums::RetailerName;
+use mongodb_connector::{errors::StorageError, storage::Storage};
+use rand::Rng;
+use thiserror::Error;
+use tokio::{
+    sync::watch,
+    task::JoinHandle,
+    time::{Instant, sleep},
+};
+use tracing::{info, warn};
+
+use crate::shutdown::is_shutting_down;
+
+pub(crate) const RUN_LOCK_NAME: &str = "indexer";
+
+// how often a waiting indexer checks whether the lock freed up
+const LOCK_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+// how soon a renewal that couldn't reach the DB is tried again
+const RENEW_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+// identifies this process in the run-locks collection, the random suffix keeps
+// two indexers in containers with the same hostname and pid apart
+static LOCK_OWNER: LazyLock<String> = LazyLock::new(|| {
+    let host = env::var("HOSTNAME").unwrap_or("indexer".into());
+
+    format!(
+        "{host}:{}:{:08x}",
+        process::id(),
+        rand::rng().random::<u32>()
+    )
+});
+
+#[derive(Error, Debug)]
+pub(crate) enum LockError {
+    #[error("Lock {0} is held by {1}")]
+    Held(String, String),
+    #[error("Failed to take lock: {0}")]
+    Storage(#[from] StorageError),
+}
+
+/// What a single indexer holds onto while it writes
+#[derive(Debug, Default, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum LockScope {
+    /// Only one indexer runs at a time
+    #[default]
+    Run,
+    /// Indexers can run at the same time, as long as they crawl different retailers
+    Retailer,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LockOptions {
+    pub(crate) scope: LockScope,
+    // the lock expires this long after the last heartbeat
+    pub(crate) ttl: Duration,
+    // how long to wait for a held lock, skips right away if unset
+    pub(crate) wait: Option<Duration>,
+}
+
+/// A lease held in the run-locks collection, kept alive by a heartbeat
+/// until it's released, or until the process dies and it expires
+pub(crate) struct RunLock {
+    db: Arc<dyn Storage>,
+    name: String,
+    heartbeat: JoinHandle<()>,
+    lost: watch::Receiver<bool>,
+}
+
+/// Flips once a lock is lost, so whatever relies on it can stop
+/// before it publishes anything another indexer might be writing over
+#[derive(Clone)]
+pub(crate) struct LockLoss(watch::Receiver<bool>);
+
+impl LockLoss {
+    pub(crate) fn is_lost(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    pub(crate) async fn wait(mut self) {
+        // a released lock can't be lost anymore
+        if self.0.wait_for(|lost| *lost).await.is_err() {
+            pending::<()>().await;
+        }
+    }
+}
+
+impl RunLock {
+    /// Takes the lock, waiting up to `options.wait` for it to free up
+    pub(crate) async fn acquire(
+        db: &Arc<dyn Storage>,
+        name: String,
+        options: &LockOptions,
+    ) -> Result<Self, LockError> {
+        let ttl_secs = options.ttl.as_secs().max(1);
+        let deadline = options.wait.map(|wait| Instant::now() + wait);
+
+        loop {
+            if db.acquire_run_lock(&name, &LOCK_OWNER, ttl_secs).await? {
+                info!("Acquired lock {name}");
+                break;
+            }
+
+            let owner = db
+                .get_run_lock(&name)
+                .await
+                .ok()
+                .flatten()
+                .map_or(String::from("unknown"), |lock| lock.owner);
+
+            match deadline {
+                Some(deadline)
+                    if Instant::now() + LOCK_POLL_INTERVAL < deadline && !is_shutting_down() =>
+                {
+                    info!("Lock {name} is held by {owner}, waiting");
+                    sleep(LOCK_POLL_INTERVAL).await;
+                }
+                _ => return Err(LockError::Held(name, owner)),
+            }
+        }
+
+        let (lost_sender, lost) = watch::channel(false);
+        let heartbeat = tokio::spawn(heartbeat(
+            db.clone(),
+            name.clone(),
+            options.ttl,
+            lost_sender,
+        ));
+
+        Ok(Self {
+            db: db.clone(),
+            name,
+            heartbeat,
+            lost,
+        })
+    }
+
+    pub(crate) fn watch_loss(&self) -> LockLoss {
+        LockLoss(self.lost.clone())
+    }
+
+    pub(crate) async fn release(self) {
+        self.heartbeat.abort();
+        // an unreleased lock frees itself up once its lease runs out
+        match self.db.release_run_lock(&self.name, &LOCK_OWNER).await {
+            Ok(()) => info!("Released lock {}", self.name),
+            Err(err) => warn!("Failed to release lock {}: {err}", self.name),
+        }
+    }
+}
+
+pub(crate) fn get_retailer_lock_name(retailer: RetailerName) -> String {
+    format!("{RUN_LOCK_NAME}:{retailer}")
+}
+
+/// Renews the lease well before it runs out, so a slow renewal doesn't let another
+/// indexer take the lock. Renewals that can't reach the DB are retried for as long as
+/// the lease lasts, once it's gone the lock is reported as lost
+async fn heartbeat(db: Arc<dyn Storage>, name: String, ttl: Duration, lost: watch::Sender<bool>) {
+    let ttl_secs = ttl.as_secs().max(1);
+    let mut expires_at = Instant::now() + ttl;
+
+    loop {
+        sleep(ttl / 3).await;
+
+        loop {
+            match db.renew_run_lock(&name, &LOCK_OWNER, ttl_secs).await {
+                Ok(true) => {
+                    expires_at = Instant::now() + ttl;
+                    break;
+                }
+                // the DB couldn't be reached, so the lock may well still be held
+                Err(err) if Instant::now() + RENEW_RETRY_INTERVAL < expires_at => {
+                    warn!("Failed to renew lock {name}, retrying: {err}");
+                    sleep(RENEW_RETRY_INTERVAL).await;
+                }
+                Ok(false) | Err(_) => {
+                    warn!("Lost lock {name}, another indexer may be writing at the same time");
+                    lost.send_replace(true);
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -1,26 +1,63 @@
-use clap::Parser;
-use common::result::enums::RetailerName;
-use discord::get_indexer_webhook;
+use clap::{Parser, Subcommand};
+use common::{money::ExchangeRate, result::enums::RetailerName};
 use metrics::_private::PROVIDER;
-use mongodb_connector::connector::MongoDBConnector;
-use std::sync::Arc;
-use tokio::task::JoinHandle;
-use tracing::{debug, info};
-use utils::logger::configure_logger;
-
-use crate::retailers::get_retailers;
-
+use mongodb_connector::storage::Storage;
+use std::{env, path::PathBuf, sync::Arc, time::Duration};
+use utils::{logger::configure_logger, storage::StorageBackend};
+
+use crate::{
+    budget::{BudgetPolicy, CrawlBudget},
+    currency_policy::{CurrencyMode, CurrencyPolicy},
+    debug::{DebugArguments, run_debug},
+    export::{OutputFormat, import_results},
+    lock::{LockOptions, LockScope},
+    migrate::migrate_product_ids,
+    queue::QueueOptions,
+    retailers::get_retailer_names,
+    rules::RulesEngine,
+    run::{RunContext, run_retailers},
+    scheduler::{RetailerSchedule, SchedulerOptions, parse_duration, run_scheduler},
+    shutdown::listen_for_shutdown,
+};
+
+mod anomalies;
+mod budget;
 mod clients;
+mod currency_policy;
+mod debug;
+mod diff;
+mod export;
+mod lock;
+mod matching;
+mod migrate;
+mod queue;
 mod retailers;
+mod rules;
+mod run;
+mod scheduler;
+mod shutdown;
+mod writer;
 
 // https://nickb.dev/blog/default-musl-allocator-considered-harmful-to-performance
 #[cfg(target_env = "musl")]
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
+#[derive(Subcommand)]
+enum Command {
+    /// Runs a single page through a retailer's parser and prints what it found,
+    /// without writing to the DB or Discord
+    Debug(DebugArguments),
+    /// Gives price history written before products had IDs its product's ID, best
+    /// run after a crawl so products the retailer has its own ID for already have one
+    MigrateProductIds,
+}
+
 #[derive(Parser)]
-#[command(version)]
+#[command(version, args_conflicts_with_subcommands = true)]
 struct Arguments {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// List of retailers to crawl, crawls all retailers by default
     #[arg(short, long, value_delimiter = ' ', num_args = 0..)]
     retailers: Vec<RetailerName>,
@@ -30,6 +67,89 @@ struct Arguments {
     /// Does not write to DB if set
     #[arg(short, long, default_value_t = false)]
     dry_run: bool,
+    /// Number of products allowed to fail parsing before a retailer is considered failed
+    #[arg(long, default_value_t = 25)]
+    max_product_errors: u64,
+    /// How to handle results that are not priced in CAD
+    #[arg(long, value_enum, default_value_t = CurrencyMode::Reject)]
+    currency_policy: CurrencyMode,
+    /// Exchange rates into CAD used by the convert policy, ie. "USD=1.37 EUR=1.48"
+    #[arg(long, value_delimiter = ' ', num_args = 0..)]
+    exchange_rates: Vec<ExchangeRate>,
+    /// Max number of retailers crawled at the same time, unlimited by default
+    #[arg(long)]
+    retailer_concurrency: Option<usize>,
+    /// Max number of retailers on the same platform or host crawled at the same time
+    #[arg(long)]
+    group_concurrency: Option<usize>,
+    /// Retailers to start before the rest, in order, the rest start by live product count
+    #[arg(long, value_delimiter = ' ', num_args = 0..)]
+    priority: Vec<RetailerName>,
+    /// Whether other indexers are kept out for the whole run, or only from the retailers being crawled
+    #[arg(long, value_enum, default_value_t = LockScope::Run)]
+    lock_scope: LockScope,
+    /// How long a lock outlives its last heartbeat, which frees up the locks of a crashed indexer
+    #[arg(long, value_parser = parse_duration, default_value = "5m")]
+    lock_ttl: Duration,
+    /// How long to wait for another indexer to release its lock, skips right away by default
+    #[arg(long, value_parser = parse_duration)]
+    lock_wait: Option<Duration>,
+    /// Max number of pages fetched at the same time from a single retailer
+    #[arg(long, default_value_t = 2)]
+    page_concurrency: usize,
+    /// Max time a single retailer can spend crawling before it's cancelled, ie. "45m" or "2h"
+    #[arg(long, value_parser = parse_duration)]
+    retailer_timeout: Option<Duration>,
+    /// Max number of page requests a single retailer can make before it's cancelled
+    #[arg(long)]
+    max_requests: Option<u64>,
+    /// What to do with the results of a retailer cancelled by --retailer-timeout or --max-requests
+    #[arg(long, value_enum, default_value_t = BudgetPolicy::Discard)]
+    budget_policy: BudgetPolicy,
+    /// JSON file of rules used to recategorize or drop results, uses the built in rules by default
+    #[arg(long)]
+    rules: Option<PathBuf>,
+    /// Continues retailers from where an interrupted run left off, skipping finished retailers
+    #[arg(long, default_value_t = false)]
+    resume: bool,
+    /// How long after a retailer's crawl started --resume can still continue it,
+    /// older checkpoints belong to a previous run and are crawled from scratch
+    #[arg(long, value_parser = parse_duration, default_value = "12h")]
+    checkpoint_window: Duration,
+    /// Directory to write each retailer's deduplicated results to, as <retailer>.<format>
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Format of the files written by --output
+    #[arg(long, value_enum, default_value_t = OutputFormat::Ndjson)]
+    output_format: OutputFormat,
+    /// Where results, runs and locks are kept, pair memory with --output to keep the results
+    #[arg(long, value_enum, default_value_t = StorageBackend::Mongodb)]
+    storage: StorageBackend,
+    /// Loads a file, or directory of files, written by --output into the DB instead of crawling
+    #[arg(long, conflicts_with_all = ["daemon", "output", "dry_run"])]
+    import: Option<PathBuf>,
+    /// Stamps imported results with the current time, publishing them as a fresh crawl,
+    /// without it exports older than the 2 day search window never show up in search
+    #[arg(long, requires = "import", default_value_t = false)]
+    restamp: bool,
+    /// Keeps running and crawls retailers on a schedule instead of exiting after a single run
+    #[arg(long, default_value_t = false)]
+    daemon: bool,
+    /// How often retailers are crawled in daemon mode, ie. "30m", "2h" or "1d"
+    #[arg(long, value_parser = parse_duration, default_value = "1d")]
+    interval: Duration,
+    /// Per retailer intervals that override --interval, ie. "Tenda=2h Rangeview=1d"
+    #[arg(long, value_delimiter = ' ', num_args = 0..)]
+    schedules: Vec<RetailerSchedule>,
+    /// Max random delay added to every scheduled crawl
+    #[arg(long, value_parser = parse_duration, default_value = "10m")]
+    jitter: Duration,
+    /// Number of times a failed retailer is retried before waiting for its next scheduled crawl
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+    /// Delay before retrying a failed retailer, doubles with every retry
+    #[arg(long, value_parser = parse_duration, default_value = "15m")]
+    retry_backoff: Duration,
 }
 
 #[tokio::main]
@@ -38,47 +158,98 @@ async fn main() {
 
     configure_logger();
 
-    let mut handles: Vec<JoinHandle<()>> = Vec::new();
-
-    let mongodb = Arc::new(MongoDBConnector::new().await);
-
-    for mut retailer in get_retailers(args.retailers, args.excluded_retailers).await {
-        let db = mongodb.clone();
+    if let Some(Command::Debug(debug_args)) = args.command {
+        if let Err(err) = run_debug(debug_args).await {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
 
-        handles.push(tokio::spawn(async move {
-            let retailer_name = retailer.get_retailer_name();
-
-            info!("Executing {retailer_name:?}");
+        return;
+    }
 
-            let crawl_state = retailer.crawl().await;
-            let results = retailer.get_results();
+    let rules = Arc::new(
+        RulesEngine::new(args.rules.as_deref())
+            .unwrap_or_else(|err| panic!("Expected rules to load: {err}")),
+    );
 
-            debug!("{:?}", results);
+    let db: Arc<dyn Storage> = Arc::from(args.storage.connect().await);
 
-            let mut webhook = get_indexer_webhook().await;
+    if let Some(Command::MigrateProductIds) = args.command {
+        migrate_product_ids(&db)
+            .await
+            .unwrap_or_else(|err| panic!("Expected migration to succeed: {err}"));
 
-            if let Err(err) = crawl_state {
-                webhook.record_retailer_failure(retailer_name, err.to_string());
-            }
+        let _ = PROVIDER.shutdown();
+        return;
+    }
 
-            webhook.append_retailer_stats(retailer_name, &results);
-            webhook.update_main_message().await;
+    if let Some(import) = args.import {
+        import_results(&db, &import, args.restamp)
+            .await
+            .unwrap_or_else(|err| panic!("Expected import to succeed: {err}"));
 
-            if !args.dry_run {
-                retailer.emit_metrics();
-                db.insert_many_results(results).await;
-            }
-        }));
+        let _ = PROVIDER.shutdown();
+        return;
     }
 
-    for handle in handles {
-        let _ = handle.await;
+    listen_for_shutdown();
+
+    let context = Arc::new(RunContext {
+        db,
+        rules,
+        currency_policy: Arc::new(CurrencyPolicy::new(
+            args.currency_policy,
+            args.exchange_rates,
+        )),
+        max_product_errors: args.max_product_errors,
+        page_concurrency: args.page_concurrency,
+        budget: CrawlBudget {
+            timeout: args.retailer_timeout,
+            max_requests: args.max_requests,
+            policy: args.budget_policy,
+        },
+        queue_options: QueueOptions {
+            max_active: args.retailer_concurrency,
+            max_per_group: args.group_concurrency,
+            priority: args.priority,
+        },
+        lock_options: LockOptions {
+            scope: args.lock_scope,
+            ttl: args.lock_ttl,
+            wait: args.lock_wait,
+        },
+        dry_run: args.dry_run,
+        checkpoint_window: args.checkpoint_window,
+        run_args: env::args().collect(),
+        output: args.output,
+        output_format: args.output_format,
+    });
+
+    match args.daemon {
+        true => {
+            let options = SchedulerOptions {
+                default_interval: args.interval,
+                schedules: args.schedules,
+                jitter: args.jitter,
+                max_retries: args.max_retries,
+                retry_backoff: args.retry_backoff,
+            };
+
+            let retailers = get_retailer_names(&args.retailers, &args.excluded_retailers);
+
+            run_scheduler(context, retailers, options, args.resume).await;
+        }
+        false => {
+            run_retailers(
+                context,
+                args.retailers,
+                args.excluded_retailers,
+                args.resume,
+                None,
+            )
+            .await;
+        }
     }
 
-    let mut webhook = get_indexer_webhook().await;
-
-    webhook.finish();
-    webhook.update_main_message().await;
-
     let _ = PROVIDER.shutdown();
 }
@@ -0,0 +1,413 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use common::{
+    canonical_product::{CanonicalLink, ProductAttributes},
+    result::{
+        base::CrawlResult,
+        enums::{Category, RetailerName},
+        metadata::Metadata,
+    },
+};
+use mongodb_connector::{errors::StorageError, storage::Storage};
+use regex::Regex;
+use tracing::debug;
+
+// links below this are more likely to be a different product than the same one
+const MIN_CONFIDENCE: f64 = 0.7;
+
+// only labelled codes count, a bare run of digits is as likely to be a part or model number
+static GTIN_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:gtin|upc|ean|barcode)(?:[\s-]*(?:code|number|no\.?))?\s*[:#]?\s*(\d{12,14})\b",
+    )
+    .expect("GTIN regex to compile")
+});
+
+static GRAINS_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(\d{2,3})\s*-?\s*(?:gr|grains?)\b").expect("Grains regex to compile")
+});
+
+// product lines are listed under their own name by some retailers, so they
+// map back to the brand while staying the model, ie. "American Eagle" is Federal's
+static PRODUCT_LINES: &[(&str, &str)] = &[
+    ("american eagle", "federal"),
+    ("fusion", "federal"),
+    ("umc", "remington"),
+    ("blazer", "cci"),
+    ("gold dot", "speer"),
+];
+
+static BRANDS: &[(&str, &str)] = &[
+    ("federal", "federal"),
+    ("winchester", "winchester"),
+    ("remington", "remington"),
+    ("hornady", "hornady"),
+    ("cci", "cci"),
+    ("speer", "speer"),
+    ("fiocchi", "fiocchi"),
+    ("sellier & bellot", "sellier & bellot"),
+    ("sellier and bellot", "sellier & bellot"),
+    ("sellier bellot", "sellier & bellot"),
+    ("s&b", "sellier & bellot"),
+    ("magtech", "magtech"),
+    ("pmc", "pmc"),
+    ("norma", "norma"),
+    ("lapua", "lapua"),
+    ("sk", "sk"),
+    ("eley", "eley"),
+    ("aguila", "aguila"),
+    ("prvi partizan", "prvi partizan"),
+    ("ppu", "prvi partizan"),
+    ("geco", "geco"),
+    ("barnaul", "barnaul"),
+    ("tula", "tula"),
+    ("wolf", "wolf"),
+    ("norinco", "norinco"),
+    ("igman", "igman"),
+    ("browning", "browning"),
+    ("nosler", "nosler"),
+    ("barnes", "barnes"),
+    ("sierra", "sierra"),
+    ("kent", "kent"),
+    ("challenger", "challenger"),
+];
+
+// checked in order, so the more specific calibers come first
+static CALIBERS: LazyLock<Vec<(Regex, &str)>> = LazyLock::new(|| {
+    [
+        (r"7\.62\s*x\s*54\s*r", "7.62x54r"),
+        (r"7\.62\s*x\s*51|7\.62\s*nato", "7.62x51"),
+        (r"7\.62\s*x\s*39", "7.62x39"),
+        (r"5\.56\s*(?:x\s*45|nato|mm)", "5.56x45"),
+        (r"5\.7\s*x\s*28", "5.7x28"),
+        (r"\.?22\s*(?:wmr|win(?:chester)?\s*mag(?:num)?)", "22wmr"),
+        (r"\.?22\s*(?:lr|long\s*rifle)", "22lr"),
+        (r"\.?17\s*hmr", "17hmr"),
+        (r"\.?223\s*(?:rem(?:ington)?)?\b", "223rem"),
+        (r"\.?300\s*(?:aac\s*)?(?:blk|blackout)", "300blk"),
+        (r"\.?308\s*(?:win(?:chester)?)?\b", "308win"),
+        (r"\.?30-06", "30-06"),
+        (r"\.?303\s*brit(?:ish)?", "303british"),
+        (r"6\.5\s*(?:mm\s*)?(?:creedmoor|cm)\b", "6.5creedmoor"),
+        (r"\.?270\s*win(?:chester)?", "270win"),
+        (r"\.?243\s*win(?:chester)?", "243win"),
+        (r"\.?45\s*(?:acp|auto)", "45acp"),
+        (r"\.?40\s*(?:s\s*&\s*w|sw)", "40sw"),
+        (r"\.?380\s*(?:acp|auto)?\b", "380acp"),
+        (r"\.?38\s*(?:spl|special)", "38spl"),
+        (r"\.?357\s*(?:mag|magnum)", "357mag"),
+        (r"\.?44\s*(?:mag|magnum)", "44mag"),
+        (r"\b10\s*mm\b", "10mm"),
+        (r"\b9\s*(?:mm|x\s*19)\b", "9mm"),
+        (r"\b12\s*(?:ga|gauge|g)\b", "12ga"),
+        (r"\b20\s*(?:ga|gauge|g)\b", "20ga"),
+        (r"\.?410\s*(?:ga|gauge|bore)?\b", "410"),
+    ]
+    .into_iter()
+    .map(|(pattern, caliber)| {
+        // keeps "223" from matching the end of a longer number
+        let regex = Regex::new(&format!("(?i)(?:^|[^0-9])(?:{pattern})"))
+            .expect("Caliber regex to compile");
+        (regex, caliber)
+    })
+    .collect()
+});
+
+// checked in order, so "bthp" wins over "hp"
+static BULLET_TYPES: LazyLock<Vec<(Regex, &str)>> = LazyLock::new(|| {
+    [
+        (r"\b(?:bthp|boat\s*tail\s*hollow\s*point)\b", "bthp"),
+        (r"\b(?:jhp|jacketed\s*hollow\s*point)\b", "jhp"),
+        (r"\b(?:jsp|jacketed\s*soft\s*point)\b", "jsp"),
+        (r"\b(?:fmj|full\s*metal\s*jacket)\b", "fmj"),
+        (r"\b(?:tmj|total\s*metal\s*jacket)\b", "tmj"),
+        (r"\b(?:otm|open\s*tip\s*match)\b", "otm"),
+        (r"\b(?:lrn|lead\s*round\s*nose)\b", "lrn"),
+        (r"\b(?:sp|soft\s*point)\b", "sp"),
+        (r"\b(?:hp|hollow\s*point)\b", "hp"),
+        (r"\bslugs?\b", "slug"),
+        (r"\bbuck(?:shot)?\b", "buckshot"),
+    ]
+    .into_iter()
+    .map(|(pattern, bullet_type)| {
+        let regex = Regex::new(&format!("(?i){pattern}")).expect("Bullet type regex to compile");
+        (regex, bullet_type)
+    })
+    .collect()
+});
+
+/// Relinks the retailer's listings to canonical products from a fresh crawl,
+/// needs a complete crawl, otherwise products it missed get unlinked
+pub(crate) async fn link_canonical_products(
+    db: &dyn Storage,
+    retailer: RetailerName,
+    crawl_start: u64,
+) -> Result<(), StorageError> {
+    let results = db.get_results_since(retailer, crawl_start).await?;
+    let links = get_canonical_links(&results);
+
+    debug!(
+        "Linked {} of {} {retailer:?} results to canonical products",
+        links.len(),
+        results.len()
+    );
+
+    db.replace_canonical_links(retailer, &links).await
+}
+
+/// Matches every result to its canonical product, a retailer listing
+/// the same product more than once only links it once
+fn get_canonical_links(results: &[CrawlResult]) -> Vec<CanonicalLink> {
+    let mut seen_urls: HashSet<&str> = HashSet::new();
+
+    results
+        .iter()
+        .filter(|result| seen_urls.insert(result.url.as_str()))
+        .filter_map(get_canonical_link)
+        .collect()
+}
+
+/// A GTIN is an exact match no matter the category, otherwise ammunition is
+/// matched on its normalized attributes, everything else is left unmatched
+fn get_canonical_link(result: &CrawlResult) -> Option<CanonicalLink> {
+    if let Some(gtin) = find_gtin(result) {
+        let attributes = ProductAttributes {
+            gtin: Some(gtin.clone()),
+            ..ProductAttributes::default()
+        };
+
+        return Some(CanonicalLink::new(
+            format!("gtin:{gtin}"),
+            attributes,
+            result,
+            1.0,
+        ));
+    }
+
+    if result.category != Category::Ammunition {
+        return None;
+    }
+
+    let attributes = get_ammunition_attributes(result);
+
+    // brand, model, caliber and quantity are the bare minimum for two boxes to be the
+    // same box, a brand sells several lines in the same caliber at different prices
+    let (Some(brand), Some(model), Some(caliber), Some(quantity)) = (
+        &attributes.brand,
+        &attributes.model,
+        &attributes.caliber,
+        attributes.quantity,
+    ) else {
+        return None;
+    };
+
+    let mut confidence = 0.6;
+
+    if attributes.grains.is_some() {
+        confidence += 0.2;
+    }
+
+    if attributes.bullet_type.is_some() {
+        confidence += 0.2;
+    }
+
+    if confidence < MIN_CONFIDENCE {
+        return None;
+    }
+
+    let key = format!(
+        "ammo:{brand}:{model}:{caliber}:{}:{}:{quantity}",
+        attributes
+            .grains
+            .map_or(String::from("-"), |grains| grains.to_string()),
+        attributes.bullet_type.as_deref().unwrap_or("-"),
+    );
+
+    Some(CanonicalLink::new(key, attributes, result, confidence))
+}
+
+/// Anything labelled as a GTIN, UPC or EAN that passes the GS1 check digit, padded
+/// out to 14 digits so UPC-A, EAN-13 and GTIN-14 versions of the same code all match
+fn find_gtin(result: &CrawlResult) -> Option<String> {
+    let text = match &result.description {
+        Some(description) => format!("{} {description}", result.name),
+        None => result.name.clone(),
+    };
+
+    GTIN_PATTERN
+        .captures_iter(&text)
+        .filter_map(|capture| capture.get(1))
+        .map(|digits| format!("{:0>14}", digits.as_str()))
+        .find(|gtin| is_valid_gtin(gtin))
+}
+
+fn is_valid_gtin(gtin: &str) -> bool {
+    let digits: Vec<u32> = gtin.chars().filter_map(|char| char.to_digit(10)).collect();
+
+    let Some((check_digit, body)) = digits.split_last() else {
+        return false;
+    };
+
+    // weights alternate 3, 1, 3... starting from the digit next to the check digit
+    let sum: u32 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(position, digit)| match position % 2 {
+            0 => digit * 3,
+            _ => *digit,
+        })
+        .sum();
+
+    (10 - sum % 10) % 10 == *check_digit
+}
+
+/// Metadata set by the retailer wins, the rest is picked out of the name
+fn get_ammunition_attributes(result: &CrawlResult) -> ProductAttributes {
+    let metadata = match &result.metadata {
+        Some(Metadata::Ammunition(ammunition)) => Some(ammunition),
+        _ => None,
+    };
+
+    let name = result.name.to_lowercase();
+
+    let metadata_brand = metadata
+        .and_then(|ammunition| ammunition.brand.as_deref())
+        .map(|brand| brand.to_lowercase());
+
+    let brand = metadata_brand
+        .as_deref()
+        .map(|brand| normalize_brand(brand).unwrap_or(brand.to_string()))
+        .or_else(|| normalize_brand(&name));
+
+    // a product line only counts as the model when it belongs to the brand
+    let model = metadata
+        .and_then(|ammunition| ammunition.model.as_deref())
+        .map(normalize_words)
+        .filter(|model| !model.is_empty())
+        .or_else(|| {
+            [metadata_brand.as_deref(), Some(name.as_str())]
+                .into_iter()
+                .flatten()
+                .filter_map(find_product_line)
+                .find(|(_, line_brand)| brand.as_deref() == Some(*line_brand))
+                .map(|(line, _)| line.to_string())
+        });
+
+    let caliber = metadata
+        .and_then(|ammunition| ammunition.caliber.as_deref())
+        .and_then(normalize_caliber)
+        .or_else(|| normalize_caliber(&name));
+
+    let grains = metadata
+        .and_then(|ammunition| ammunition.grains.as_deref())
+        .and_then(|grains| grains.trim().parse::<u64>().ok())
+        .or_else(|| {
+            GRAINS_PATTERN
+                .captures(&name)
+                .and_then(|capture| capture.get(1))
+                .and_then(|grains| grains.as_str().parse::<u64>().ok())
+        });
+
+    let bullet_type = BULLET_TYPES
+        .iter()
+        .find(|(pattern, _)| pattern.is_match(&name))
+        .map(|(_, bullet_type)| bullet_type.to_string());
+
+    ProductAttributes {
+        gtin: None,
+        brand,
+        model,
+        caliber,
+        grains,
+        bullet_type,
+        quantity: metadata.and_then(|ammunition| ammunition.round_count),
+    }
+}
+
+fn normalize_brand(text: &str) -> Option<String> {
+    find_product_line(text)
+        .or_else(|| find_alias(text, BRANDS))
+        .map(|(_, brand)| brand.to_string())
+}
+
+fn find_product_line(text: &str) -> Option<(&'static str, &'static str)> {
+    find_alias(text, PRODUCT_LINES)
+}
+
+fn find_alias(
+    text: &str,
+    aliases: &[(&'static str, &'static str)],
+) -> Option<(&'static str, &'static str)> {
+    // padded so short aliases like "sk" only match as a whole word
+    let text = format!(" {} ", normalize_words(text));
+
+    aliases
+        .iter()
+        .find(|(alias, _)| text.contains(&format!(" {alias} ")))
+        .copied()
+}
+
+fn normalize_words(text: &str) -> String {
+    let words: Vec<&str> = text
+        .split(|char: char| !char.is_alphanumeric() && char != '&')
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    words.join(" ").to_lowercase()
+}
+
+fn normalize_caliber(text: &str) -> Option<String> {
+    CALIBERS
+        .iter()
+        .find(|(pattern, _)| pattern.is_match(text))
+        .map(|(_, caliber)| caliber.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        money::Money,
+        result::{base::Price, metadata::Ammunition},
+    };
+
+    use super::*;
+
+    fn ammunition(name: &str, brand: &str) -> CrawlResult {
+        let mut metadata = Ammunition::new();
+        metadata.round_count = Some(50);
+        metadata.brand = Some(brand.into());
+
+        CrawlResult::new(
+            name.into(),
+            format!("https://example.com/{name}"),
+            Price::new(Money::cad(2_000)),
+            RetailerName::AlFlahertys,
+            Category::Ammunition,
+        )
+        .with_metadata(Metadata::Ammunition(metadata))
+    }
+
+    #[test]
+    fn keeps_product_lines_apart() {
+        let american_eagle = get_canonical_link(&ammunition(
+            "Federal American Eagle 9mm 115gr FMJ",
+            "Federal",
+        ))
+        .expect("American Eagle to link");
+        let listed_as_line = get_canonical_link(&ammunition(
+            "American Eagle 9mm 115gr FMJ",
+            "American Eagle",
+        ))
+        .expect("American Eagle to link");
+        let syntech = get_canonical_link(&ammunition("Federal 9mm 115gr Syntech TSJ", "Federal"));
+
+        assert_eq!(american_eagle.key, listed_as_line.key);
+        assert_eq!(american_eagle.attributes.brand.as_deref(), Some("federal"));
+        assert_eq!(
+            american_eagle.attributes.model.as_deref(),
+            Some("american eagle")
+        );
+        assert!(syntech.is_none(), "a brand without a model should not link");
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use common::{price_history::CollectionPriceHistory, result::base::CrawlResult};
+use mongodb_connector::{errors::StorageError, storage::Storage};
+use retailers::registry::{RetailerRegistration, get_registered_retailers};
+use thiserror::Error;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::lock::{LockError, LockOptions, LockScope, RUN_LOCK_NAME, RunLock};
+
+#[derive(Error, Debug)]
+pub(crate) enum MigrateError {
+    #[error("Failed to take the run lock: {0}")]
+    Lock(#[from] LockError),
+    #[error("Failed to migrate price history: {0}")]
+    Storage(#[from] StorageError),
+}
+
+/// Backfills product IDs onto price history written before products had them, merging it
+/// into the history crawls have started under the product's ID. Holds the run lock so no
+/// crawl writes history at the same time
+pub(crate) async fn migrate_product_ids(db: &Arc<dyn Storage>) -> Result<(), MigrateError> {
+    let options = LockOptions {
+        scope: LockScope::Run,
+        ttl: Duration::from_secs(300),
+        wait: None,
+    };
+
+    let lock = RunLock::acquire(db, RUN_LOCK_NAME.into(), &options).await?;
+
+    let migrated = migrate_histories(db.as_ref()).await;
+
+    lock.release().await;
+
+    Ok(migrated?)
+}
+
+async fn migrate_histories(db: &dyn Storage) -> Result<(), StorageError> {
+    let retailers: HashMap<String, &'static RetailerRegistration> = get_registered_retailers()
+        .filter_map(|registration| Some((get_host(registration.info.homepage)?, registration)))
+        .collect();
+
+    let histories = db.get_unidentified_price_histories().await?;
+
+    let (mut assigned, mut merged, mut skipped) = (0, 0, 0);
+
+    for history in &histories {
+        let Some((product_id, url)) = get_product_id(db, &retailers, history).await? else {
+            skipped += 1;
+            continue;
+        };
+
+        match db
+            .assign_price_history_product_id(history, &product_id, &url)
+            .await?
+        {
+            true => merged += 1,
+            false => assigned += 1,
+        }
+    }
+
+    info!(
+        "Migrated {} price histories, {assigned} given a product ID, {merged} merged, {skipped} skipped",
+        histories.len()
+    );
+
+    Ok(())
+}
+
+/// The product ID and canonical URL of the history's product, prefers the ID crawls
+/// gave the product, so it can pick up the retailer's own ID
+async fn get_product_id(
+    db: &dyn Storage,
+    retailers: &HashMap<String, &'static RetailerRegistration>,
+    history: &CollectionPriceHistory,
+) -> Result<Option<(String, String)>, StorageError> {
+    let Some(registration) = get_host(&history.url).and_then(|host| retailers.get(&host)) else {
+        warn!("No retailer matches {}, skipping", history.url);
+        return Ok(None);
+    };
+
+    let url = match registration.canonicalize_url(&history.url) {
+        Ok(url) => url,
+        Err(err) => {
+            warn!("Skipping price history: {err}");
+            return Ok(None);
+        }
+    };
+
+    let product_ids = db
+        .get_product_ids_at_url(registration.name, &url, &history.name)
+        .await?;
+
+    let product_id = match product_ids.as_slice() {
+        [] => Some((
+            CrawlResult::get_url_product_id(registration.name, &url),
+            url,
+        )),
+        [product_id] => Some((product_id.clone(), url)),
+        // variants at the same URL that have since been renamed, can't tell which it was
+        _ => {
+            debug!(
+                "{} products at {url}, skipping {}",
+                product_ids.len(),
+                history.name
+            );
+            None
+        }
+    };
+
+    Ok(product_id)
+}
+
+/// Homepages and product URLs don't always agree on the www prefix
+fn get_host(url: &str) -> Option<String> {
+    let host = Url::parse(url).ok()?.host_str()?.to_lowercase();
+
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use common::{
+    result::enums::RetailerName,
+    retailer_info::{CollectionRetailerStats, Platform},
+};
+use retailers::registry::RetailerRegistration;
+
+/// How many retailers are crawled at once, and which ones go first
+#[derive(Debug, Default, Clone)]
+pub(crate) struct QueueOptions {
+    /// Max retailers initializing or crawling at once, unlimited if unset
+    pub(crate) max_active: Option<usize>,
+    /// Max retailers on the same platform or host at once, unlimited if unset
+    pub(crate) max_per_group: Option<usize>,
+    /// Retailers that start before everyone else, in this order
+    pub(crate) priority: Vec<RetailerName>,
+}
+
+/// Hands out retailers in priority order while keeping under the concurrency
+/// limits, a retailer whose group is full is skipped over until a slot frees up
+pub(crate) struct RetailerQueue {
+    pending: Vec<&'static RetailerRegistration>,
+    active: usize,
+    active_groups: HashMap<String, usize>,
+    max_active: usize,
+    max_per_group: usize,
+}
+
+impl RetailerQueue {
+    /// Retailers in `options.priority` go first, followed by the retailers
+    /// with the most live products, since those are the most worth having fresh
+    pub(crate) fn new(
+        mut retailers: Vec<&'static RetailerRegistration>,
+        options: &QueueOptions,
+        stats: &[CollectionRetailerStats],
+    ) -> Self {
+        let live_product_counts: HashMap<RetailerName, u64> = stats
+            .iter()
+            .map(|retailer_stats| (retailer_stats.retailer, retailer_stats.live_product_count))
+            .collect();
+
+        retailers.sort_by_key(|registration| {
+            let priority = options
+                .priority
+                .iter()
+                .position(|retailer| *retailer == registration.name)
+                .unwrap_or(usize::MAX);
+            let live_product_count = live_product_counts
+                .get(&registration.name)
+                .copied()
+                .unwrap_or_default();
+
+            (priority, u64::MAX - live_product_count, registration.name)
+        });
+
+        Self {
+            pending: retailers,
+            active: 0,
+            active_groups: HashMap::new(),
+            max_active: options.max_active.unwrap_or(usize::MAX).max(1),
+            max_per_group: options.max_per_group.unwrap_or(usize::MAX).max(1),
+        }
+    }
+
+    /// The next retailer that's allowed to start, if any
+    pub(crate) fn next(&mut self) -> Option<&'static RetailerRegistration> {
+        if self.active >= self.max_active {
+            return None;
+        }
+
+        let position = self.pending.iter().position(|registration| {
+            let group_active = self
+                .active_groups
+                .get(&get_group(registration))
+                .copied()
+                .unwrap_or_default();
+
+            group_active < self.max_per_group
+        })?;
+
+        let registration = self.pending.remove(position);
+
+        self.active += 1;
+        *self
+            .active_groups
+            .entry(get_group(registration))
+            .or_default() += 1;
+
+        Some(registration)
+    }
+
+    /// Adds a retailer after every retailer already waiting
+    pub(crate) fn push(&mut self, registration: &'static RetailerRegistration) {
+        self.pending.push(registration);
+    }
+
+    /// Frees up the slot a retailer was holding onto
+    pub(crate) fn release(&mut self, registration: &RetailerRegistration) {
+        self.active = self.active.saturating_sub(1);
+
+        if let Some(group_active) = self.active_groups.get_mut(&get_group(registration)) {
+            *group_active = group_active.saturating_sub(1);
+        }
+    }
+}
+
+/// Retailers on the same platform tend to share infrastructure, falling back
+/// to the homepage's host for retailers with an unknown or custom platform
+fn get_group(registration: &RetailerRegistration) -> String {
+    // custom sites share nothing with each other beyond the label
+    if let Some(platform) = registration.info.platforms.first()
+        && *platform != Platform::Custom
+    {
+        return format!("{platform:?}");
+    }
+
+    let homepage = registration.info.homepage;
+    let host = homepage
+        .split_once("://")
+        .map_or(homepage, |(_, rest)| rest);
+
+    host.split('/')
+        .next()
+        .unwrap_or(host)
+        .trim_start_matches("www.")
+        .to_string()
+}
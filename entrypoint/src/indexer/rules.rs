@@ -0,0 +1,246 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    fs,
+    path::Path,
+};
+
+use common::{
+    crawl_run::CrawlRuleDecision,
+    money::{Money, MoneyError},
+    result::{
+        base::CrawlResult,
+        enums::{Category, RetailerName},
+        taxonomy::TaxonomyNode,
+    },
+};
+use regex::Regex;
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::info;
+
+// shipped with the binary, `--rules` swaps these out without a rebuild
+const DEFAULT_RULES: &str = include_str!("rules.json");
+// decisions kept per crawl, the rest only show up in the per-rule counts
+const MAX_SAMPLED_DECISIONS: usize = 50;
+
+#[derive(Error, Debug)]
+pub(crate) enum RulesError {
+    #[error("Failed to read rules file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse rules: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("Rule '{0}' has an invalid pattern: {1}")]
+    InvalidPattern(String, regex::Error),
+    #[error("Rule '{0}' has an invalid price: {1}")]
+    InvalidPrice(String, MoneyError),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub(crate) enum RuleAction {
+    Recategorize {
+        category: Category,
+        #[serde(default)]
+        taxonomy: Option<TaxonomyNode>,
+    },
+    Drop,
+}
+
+/// A rule as it's written in the rules file, every condition is optional
+/// but all of the ones that are set need to match
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDefinition {
+    name: String,
+    #[serde(default)]
+    category: Option<Category>,
+    #[serde(default)]
+    retailers: Vec<RetailerName>,
+    #[serde(default)]
+    name_pattern: Option<String>,
+    #[serde(default)]
+    url_pattern: Option<String>,
+    #[serde(default)]
+    min_price: Option<String>,
+    #[serde(default)]
+    max_price: Option<String>,
+    action: RuleAction,
+}
+
+struct Rule {
+    name: String,
+    category: Option<Category>,
+    retailers: Vec<RetailerName>,
+    name_pattern: Option<Regex>,
+    url_pattern: Option<Regex>,
+    min_price: Option<u64>,
+    max_price: Option<u64>,
+    action: RuleAction,
+}
+
+impl Rule {
+    fn compile(definition: RuleDefinition) -> Result<Self, RulesError> {
+        let name = definition.name;
+
+        let compile_pattern = |pattern: Option<String>| {
+            pattern
+                .map(|pattern| Regex::new(&pattern))
+                .transpose()
+                .map_err(|err| RulesError::InvalidPattern(name.clone(), err))
+        };
+
+        let parse_price = |price: Option<String>| {
+            price
+                .map(|price| Money::parse(&price).map(|money| money.cents()))
+                .transpose()
+                .map_err(|err| RulesError::InvalidPrice(name.clone(), err))
+        };
+
+        Ok(Self {
+            category: definition.category,
+            retailers: definition.retailers,
+            name_pattern: compile_pattern(definition.name_pattern)?,
+            url_pattern: compile_pattern(definition.url_pattern)?,
+            min_price: parse_price(definition.min_price)?,
+            max_price: parse_price(definition.max_price)?,
+            action: definition.action,
+            name,
+        })
+    }
+
+    fn matches(&self, result: &CrawlResult) -> bool {
+        let price = result
+            .price
+            .sale_price
+            .unwrap_or(result.price.regular_price);
+
+        self.category
+            .is_none_or(|category| category == result.category)
+            && (self.retailers.is_empty() || self.retailers.contains(&result.retailer))
+            && self
+                .name_pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&result.name))
+            && self
+                .url_pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&result.url))
+            && self.min_price.is_none_or(|min_price| price >= min_price)
+            && self.max_price.is_none_or(|max_price| price <= max_price)
+    }
+}
+
+/// Why a result was moved or dropped, kept around for auditing
+#[derive(Debug)]
+pub(crate) struct RuleDecision {
+    pub(crate) rule: String,
+    pub(crate) name: String,
+    pub(crate) url: String,
+    pub(crate) from: Category,
+    pub(crate) action: RuleAction,
+}
+
+/// Every product the rules moved or dropped during a crawl, a product
+/// found on several pages is only counted the first time
+#[derive(Debug, Default)]
+pub(crate) struct RuleDecisions {
+    seen: HashSet<String>,
+    pub(crate) counts: BTreeMap<String, u64>,
+    pub(crate) sample: Vec<RuleDecision>,
+}
+
+impl RuleDecisions {
+    pub(crate) fn record(&mut self, key: String, decision: RuleDecision) {
+        if !self.seen.insert(key) {
+            return;
+        }
+
+        *self.counts.entry(decision.rule.clone()).or_default() += 1;
+
+        if self.sample.len() < MAX_SAMPLED_DECISIONS {
+            self.sample.push(decision);
+        }
+    }
+}
+
+impl From<&RuleDecision> for CrawlRuleDecision {
+    fn from(decision: &RuleDecision) -> Self {
+        Self {
+            rule: decision.rule.clone(),
+            name: decision.name.clone(),
+            url: decision.url.clone(),
+            from: decision.from,
+            to: match decision.action {
+                RuleAction::Recategorize { category, .. } => Some(category),
+                RuleAction::Drop => None,
+            },
+        }
+    }
+}
+
+impl Display for RuleDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.action {
+            RuleAction::Recategorize { category, .. } => write!(
+                f,
+                "'{}' moved {} ({}) from {} to {category}",
+                self.rule, self.name, self.url, self.from
+            ),
+            RuleAction::Drop => write!(
+                f,
+                "'{}' dropped {} ({}) from {}",
+                self.rule, self.name, self.url, self.from
+            ),
+        }
+    }
+}
+
+pub(crate) struct RulesEngine {
+    rules: Vec<Rule>,
+}
+
+impl RulesEngine {
+    pub(crate) fn new(rules_file: Option<&Path>) -> Result<Self, RulesError> {
+        let rules_json = match rules_file {
+            Some(path) => fs::read_to_string(path)?,
+            None => DEFAULT_RULES.to_string(),
+        };
+
+        let definitions: Vec<RuleDefinition> = serde_json::from_str(&rules_json)?;
+
+        Ok(Self {
+            rules: definitions
+                .into_iter()
+                .map(Rule::compile)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Applies the first matching rule, the result should be thrown
+    /// away if the returned decision is `RuleAction::Drop`
+    pub(crate) fn apply(&self, result: &mut CrawlResult) -> Option<RuleDecision> {
+        let rule = self.rules.iter().find(|rule| rule.matches(result))?;
+
+        let decision = RuleDecision {
+            rule: rule.name.clone(),
+            name: result.name.clone(),
+            url: result.url.clone(),
+            from: result.category,
+            action: rule.action,
+        };
+
+        if let RuleAction::Recategorize { category, taxonomy } = rule.action {
+            result.category = category;
+            // the old path belonged to the old category
+            result.taxonomy = taxonomy
+                .filter(|node| node.category() == category)
+                .map(|node| node.path())
+                .unwrap_or_default();
+        }
+
+        info!("{decision}");
+
+        Some(decision)
+    }
+}
@@ -0,0 +1,688 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use common::{
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    crawl_run::{CollectionCrawlRun, CrawlRuleDecision, CrawlRunRetailer},
+    result::{counts::ResultCounts, enums::RetailerName},
+    utils::get_current_time,
+};
+use discord::get_indexer_webhook;
+use metrics::put_metric;
+use mongodb_connector::storage::Storage;
+use retailers::{errors::RetailerError, registry::RetailerRegistration};
+use tokio::{
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    task::{Id, JoinSet},
+    time::{Instant, timeout_at},
+};
+use tracing::{info, warn};
+
+use crate::{
+    anomalies::{
+        BASELINE_WINDOW, MAX_BASELINE_SKIPS, find_degraded_categories, get_category_counts,
+    },
+    budget::{BudgetPolicy, CrawlBudget},
+    clients::{
+        base::Client,
+        sink::{ResultSink, SinkReport},
+    },
+    currency_policy::CurrencyPolicy,
+    diff::get_crawl_diff,
+    export::{OutputFormat, ResultExport},
+    lock::{LockLoss, LockOptions, LockScope, RUN_LOCK_NAME, RunLock, get_retailer_lock_name},
+    matching::link_canonical_products,
+    queue::{QueueOptions, RetailerQueue},
+    retailers::{get_retailers, init_retailer},
+    rules::RulesEngine,
+    shutdown::is_shutting_down,
+    writer::write_results,
+};
+
+/// Everything shared by every retailer in a run, which stays the
+/// same across runs when the indexer is running as a daemon
+pub(crate) struct RunContext {
+    pub(crate) db: Arc<dyn Storage>,
+    pub(crate) rules: Arc<RulesEngine>,
+    pub(crate) currency_policy: Arc<CurrencyPolicy>,
+    pub(crate) max_product_errors: u64,
+    pub(crate) page_concurrency: usize,
+    pub(crate) budget: CrawlBudget,
+    pub(crate) queue_options: QueueOptions,
+    pub(crate) lock_options: LockOptions,
+    pub(crate) dry_run: bool,
+    // how long after a crawl started its checkpoints can still be resumed from
+    pub(crate) checkpoint_window: Duration,
+    // recorded with every run in crawl-runs
+    pub(crate) run_args: Vec<String>,
+    // directory each retailer's results are exported to
+    pub(crate) output: Option<PathBuf>,
+    pub(crate) output_format: OutputFormat,
+}
+
+/// Keeps a run going while the scheduler adds retailers to it as they come due,
+/// the run only wraps up once the scheduler drops its end of `additions`
+pub(crate) struct RunFeed {
+    pub(crate) additions: UnboundedReceiver<Vec<RetailerName>>,
+    // every retailer that starts crawling is sent here as soon as it's done
+    pub(crate) outcomes: UnboundedSender<(RetailerName, bool)>,
+}
+
+/// Crawls every matching retailer, as many at once as the queue allows, posts the
+/// run report and records the run in crawl-runs, returning whether each retailer succeeded
+pub(crate) async fn run_retailers(
+    context: Arc<RunContext>,
+    retailer_filter: Vec<RetailerName>,
+    excluded_retailer_filter: Vec<RetailerName>,
+    resume: bool,
+    mut feed: Option<RunFeed>,
+) -> BTreeMap<RetailerName, bool> {
+    // dry runs don't write anything another indexer could step on
+    let run_lock = match context.lock_options.scope == LockScope::Run && !context.dry_run {
+        true => {
+            let name = RUN_LOCK_NAME.to_string();
+
+            match RunLock::acquire(&context.db, name, &context.lock_options).await {
+                Ok(lock) => Some(lock),
+                Err(err) => {
+                    warn!("Skipping run: {err}");
+                    return BTreeMap::new();
+                }
+            }
+        }
+        false => None,
+    };
+
+    get_indexer_webhook().await.start_run();
+
+    let crawl_run = Arc::new(CollectionCrawlRun::new(context.run_args.clone()));
+
+    if !context.dry_run
+        && let Err(err) = context.db.start_crawl_run(&crawl_run).await
+    {
+        warn!("Failed to record the start of the run: {err}");
+    }
+
+    let retailers = get_retailers(&retailer_filter, &excluded_retailer_filter);
+
+    let mut webhook = get_indexer_webhook().await;
+
+    for registration in &retailers {
+        webhook.register_retailer(registration.name);
+    }
+
+    webhook.update_main_message().await;
+    drop(webhook);
+
+    // only the retailers the run started with pick up after an interrupted process
+    let mut resumable: HashSet<RetailerName> = match resume {
+        true => retailers
+            .iter()
+            .map(|registration| registration.name)
+            .collect(),
+        false => HashSet::new(),
+    };
+
+    // without stats, the queue falls back to the registry's order
+    let stats = context.db.get_retailer_stats().await.unwrap_or_else(|err| {
+        warn!("Failed to get retailer stats: {err}");
+        Vec::new()
+    });
+    let mut queue = RetailerQueue::new(retailers, &context.queue_options, &stats);
+
+    let mut tasks: JoinSet<CrawlRunRetailer> = JoinSet::new();
+    // holds onto which retailer each task is for, so a panicked task still frees its slot
+    let mut running: HashMap<Id, &'static RetailerRegistration> = HashMap::new();
+    let mut outcomes: BTreeMap<RetailerName, bool> = BTreeMap::new();
+
+    let run_lock_loss = run_lock.as_ref().map(RunLock::watch_loss);
+    // a shutdown, or losing the run lock, lets the retailers already crawling
+    // wind down, but doesn't start any more
+    let is_stopping =
+        || is_shutting_down() || run_lock_loss.as_ref().is_some_and(LockLoss::is_lost);
+
+    loop {
+        while !is_stopping()
+            && let Some(registration) = queue.next()
+        {
+            let context = context.clone();
+            let crawl_run = crawl_run.clone();
+            let resume = resumable.remove(&registration.name);
+            let run_lock_loss = run_lock_loss.clone();
+
+            let task = tasks.spawn(async move {
+                let record =
+                    start_retailer(&context, &crawl_run, registration, resume, run_lock_loss).await;
+
+                if !context.dry_run
+                    && let Err(err) = context
+                        .db
+                        .record_crawl_run_retailer(crawl_run.id, &record)
+                        .await
+                {
+                    warn!("Failed to record {:?} in the run: {err}", record.retailer);
+                }
+
+                record
+            });
+
+            running.insert(task.id(), registration);
+        }
+
+        let accepting_additions = feed.is_some() && !is_stopping();
+
+        let joined = tokio::select! {
+            Some(joined) = tasks.join_next_with_id() => joined,
+            added = async { feed.as_mut()?.additions.recv().await }, if accepting_additions => {
+                match added {
+                    Some(added) => add_retailers(&mut queue, &mut resumable, &added).await,
+                    None => feed = None,
+                }
+
+                continue;
+            }
+            else => break,
+        };
+
+        let (task_id, succeeded) = match joined {
+            Ok((task_id, record)) => {
+                outcomes.insert(record.retailer, record.succeeded);
+                (task_id, record.succeeded)
+            }
+            Err(err) => {
+                warn!("Retailer task failed: {err}");
+                (err.id(), false)
+            }
+        };
+
+        if let Some(registration) = running.remove(&task_id) {
+            queue.release(registration);
+
+            if let Some(feed) = &feed {
+                let _ = feed.outcomes.send((registration.name, succeeded));
+            }
+        }
+    }
+
+    if !context.dry_run
+        && let Err(err) = context
+            .db
+            .finish_crawl_run(crawl_run.id, get_current_time())
+            .await
+    {
+        warn!("Failed to record the end of the run: {err}");
+    }
+
+    let mut webhook = get_indexer_webhook().await;
+
+    match is_shutting_down() {
+        true => webhook.abort(),
+        false => webhook.finish(),
+    }
+
+    webhook.update_main_message().await;
+    drop(webhook);
+
+    if let Some(run_lock) = run_lock {
+        run_lock.release().await;
+    }
+
+    outcomes
+}
+
+/// Queues up retailers that came due while the run was going, they're crawled
+/// from scratch even when they already finished earlier in the run
+async fn add_retailers(
+    queue: &mut RetailerQueue,
+    resumable: &mut HashSet<RetailerName>,
+    retailer_names: &[RetailerName],
+) {
+    if retailer_names.is_empty() {
+        return;
+    }
+
+    info!("Adding {retailer_names:?} to the current run");
+
+    let mut webhook = get_indexer_webhook().await;
+
+    for registration in get_retailers(retailer_names, &[]) {
+        resumable.remove(&registration.name);
+        webhook.register_retailer(registration.name);
+        queue.push(registration);
+    }
+
+    webhook.update_main_message().await;
+}
+
+/// Initializes the retailer once it's been given a slot, then crawls it
+async fn start_retailer(
+    context: &RunContext,
+    crawl_run: &CollectionCrawlRun,
+    registration: &RetailerRegistration,
+    resume: bool,
+    run_lock_loss: Option<LockLoss>,
+) -> CrawlRunRetailer {
+    let lock = match context.lock_options.scope == LockScope::Retailer && !context.dry_run {
+        true => {
+            let name = get_retailer_lock_name(registration.name);
+
+            match RunLock::acquire(&context.db, name, &context.lock_options).await {
+                Ok(lock) => Some(lock),
+                Err(err) => {
+                    warn!("Skipping {:?}: {err}", registration.name);
+                    return record_failure(registration.name, err.to_string()).await;
+                }
+            }
+        }
+        false => None,
+    };
+
+    // initializing can take a while for retailers that look things up first, so it
+    // comes out of the same time budget as the crawl
+    let deadline = context
+        .budget
+        .timeout
+        .map(|time_budget| (Instant::now() + time_budget, time_budget));
+
+    let init = init_retailer(
+        registration,
+        context.max_product_errors,
+        context.page_concurrency,
+        context.budget.max_requests,
+    );
+
+    let retailer = match deadline {
+        Some((deadline, time_budget)) => timeout_at(deadline, init)
+            .await
+            .unwrap_or(Err(RetailerError::TimeBudgetExceeded(time_budget))),
+        None => init.await,
+    };
+
+    let record = match retailer {
+        Ok(retailer) => {
+            // only one of the two is ever held, depending on the lock scope
+            let lock_loss = run_lock_loss.or_else(|| lock.as_ref().map(RunLock::watch_loss));
+
+            crawl_retailer(context, crawl_run, retailer, resume, deadline, lock_loss).await
+        }
+        Err(err) if err.is_budget_exceeded() => record_timeout(registration.name, err).await,
+        Err(err) => record_failure(registration.name, err.to_string()).await,
+    };
+
+    if let Some(lock) = lock {
+        lock.release().await;
+    }
+
+    record
+}
+
+/// Reports a retailer that ran out of time before it got to crawl
+async fn record_timeout(retailer: RetailerName, err: RetailerError) -> CrawlRunRetailer {
+    warn!("{retailer:?} cancelled: {err}");
+
+    let mut webhook = get_indexer_webhook().await;
+    webhook.record_retailer_timeout(retailer, err.to_string(), false);
+    webhook.update_main_message().await;
+
+    let mut record = CrawlRunRetailer::new(retailer, get_current_time());
+    record.timed_out = true;
+    record.errors.push(err.to_string());
+
+    record
+}
+
+/// Reports a retailer that never got to crawl
+async fn record_failure(retailer: RetailerName, reason: String) -> CrawlRunRetailer {
+    let mut webhook = get_indexer_webhook().await;
+    webhook.record_retailer_failure(retailer, reason.clone());
+    webhook.update_main_message().await;
+
+    let mut record = CrawlRunRetailer::new(retailer, get_current_time());
+    record.errors.push(reason);
+
+    record
+}
+
+async fn crawl_retailer(
+    context: &RunContext,
+    crawl_run: &CollectionCrawlRun,
+    mut retailer: Box<dyn Client + Send>,
+    resume: bool,
+    // when the retailer's time budget runs out, along with the budget itself
+    deadline: Option<(Instant, Duration)>,
+    lock_loss: Option<LockLoss>,
+) -> CrawlRunRetailer {
+    let retailer_name = retailer.get_retailer_name();
+    let mut record = CrawlRunRetailer::new(retailer_name, get_current_time());
+
+    info!("Executing {retailer_name:?}");
+
+    let checkpoint = match resume {
+        true => match context.db.get_crawl_checkpoint(retailer_name).await {
+            Ok(checkpoint) => {
+                checkpoint.filter(|checkpoint| checkpoint.is_resumable(context.checkpoint_window))
+            }
+            Err(err) => return record_failure(retailer_name, err.to_string()).await,
+        },
+        false => None,
+    };
+
+    if let Some(checkpoint) = &checkpoint
+        && checkpoint.finished
+    {
+        info!("{retailer_name:?} already finished during this run, skipping");
+
+        let mut webhook = get_indexer_webhook().await;
+        webhook.append_retailer_stats(retailer_name, &ResultCounts::new());
+        webhook.update_main_message().await;
+
+        record.crawl_start = Some(checkpoint.crawl_start);
+        record.succeeded = true;
+        record.skipped = true;
+        return record;
+    }
+
+    // results are written to crawl-results as they're found, this marks where this
+    // crawl's results start, a resumed crawl keeps the original start
+    let crawl_start = checkpoint
+        .as_ref()
+        .map_or_else(get_current_time, |checkpoint| checkpoint.crawl_start);
+    record.crawl_start = Some(crawl_start);
+    let (mut sink, receiver) = ResultSink::new(
+        retailer_name,
+        crawl_start,
+        context.rules.clone(),
+        context.currency_policy.clone(),
+    );
+
+    match &checkpoint {
+        Some(checkpoint) => {
+            info!(
+                "Resuming {retailer_name:?} from term {}, page {}",
+                checkpoint.term_index, checkpoint.page
+            );
+
+            match context
+                .db
+                .get_results_since(retailer_name, crawl_start)
+                .await
+            {
+                Ok(results) => sink.restore(results),
+                Err(err) => return record_failure(retailer_name, err.to_string()).await,
+            }
+        }
+        // replaces whatever the previous run left behind
+        None => sink.checkpoint(0, 0, None).await,
+    }
+
+    let export = context
+        .output
+        .as_ref()
+        .map(|output| ResultExport::new(output, context.output_format, retailer_name));
+
+    let writer = tokio::spawn(write_results(
+        context.db.clone(),
+        receiver,
+        crawl_start,
+        context.dry_run,
+        export,
+    ));
+
+    let crawl = retailer.crawl(&mut sink, checkpoint.as_ref());
+
+    // dropping the crawl cancels whatever it was waiting on, everything
+    // it streamed out before that is still written
+    let crawl = async {
+        match deadline {
+            Some((deadline, time_budget)) => timeout_at(deadline, crawl)
+                .await
+                .unwrap_or(Err(RetailerError::TimeBudgetExceeded(time_budget))),
+            None => crawl.await,
+        }
+    };
+
+    let crawl_state = match &lock_loss {
+        Some(lock_loss) => tokio::select! {
+            crawl_state = crawl => crawl_state,
+            _ = lock_loss.clone().wait() => Err(RetailerError::LockLost),
+        },
+        None => crawl.await,
+    };
+
+    let SinkReport {
+        counts,
+        rule_decisions,
+        currency_report,
+    } = sink.finish();
+
+    // wait for the last batch to land before anything gets published, results
+    // that never made it to crawl-results would be missing from the live view
+    let written = match writer.await {
+        Ok(written) => written.map_err(|err| err.to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+
+    let crawl_state = match written {
+        Ok(()) => crawl_state,
+        Err(err) => {
+            warn!("{retailer_name:?} result writer failed: {err}");
+            crawl_state.and(Err(RetailerError::GeneralError(format!(
+                "Failed to write results: {err}"
+            ))))
+        }
+    };
+
+    // another indexer could have taken over while the last results were being written,
+    // publishing them would overwrite whatever it's been writing
+    let crawl_state = match lock_loss.as_ref().is_some_and(LockLoss::is_lost) {
+        true => crawl_state.and(Err(RetailerError::LockLost)),
+        false => crawl_state,
+    };
+
+    // a failed crawl already shows up as an error, and would only pollute the baseline
+    let mut crawl_succeeded = crawl_state.is_ok();
+    let timed_out = crawl_state
+        .as_ref()
+        .is_err_and(RetailerError::is_budget_exceeded);
+    let keep_partial = timed_out && context.budget.policy == BudgetPolicy::Keep;
+    // everything written so far stays in crawl-results for --resume to pick back up
+    let interrupted = matches!(crawl_state, Err(RetailerError::Interrupted));
+    let category_counts = get_category_counts(&counts);
+    // without baselines nothing gets flagged as degraded this time around
+    let baselines = context
+        .db
+        .get_crawl_baselines(retailer_name)
+        .await
+        .unwrap_or_else(|err| {
+            warn!("Failed to get {retailer_name:?} crawl baselines: {err}");
+            Vec::new()
+        });
+
+    let degraded = match crawl_succeeded {
+        true => {
+            find_degraded_categories(&category_counts, &baselines, retailer.get_page_anomalies())
+        }
+        false => Vec::new(),
+    };
+
+    // a failed crawl would look like most of the retailer's products disappeared
+    let crawl_diff = match crawl_succeeded && !context.dry_run {
+        true => get_crawl_diff(context.db.as_ref(), crawl_run, retailer_name, crawl_start)
+            .await
+            .inspect_err(|err| warn!("Failed to diff {retailer_name:?} crawl: {err}"))
+            .ok(),
+        false => None,
+    };
+
+    if let Some(crawl_diff) = &crawl_diff {
+        record.changes = Some(crawl_diff.get_summary());
+    }
+
+    let mut webhook = get_indexer_webhook().await;
+
+    if let Some(changes) = record.changes {
+        webhook.record_crawl_diff(retailer_name, changes);
+    }
+
+    if let Err(err) = crawl_state {
+        if timed_out {
+            warn!("{retailer_name:?} cancelled: {err}");
+            webhook.record_retailer_timeout(retailer_name, err.to_string(), keep_partial);
+        } else if interrupted {
+            warn!("{retailer_name:?} stopped: {err}");
+            webhook.record_retailer_interrupted(retailer_name);
+        } else {
+            webhook.record_retailer_failure(retailer_name, err.to_string());
+        }
+
+        record.errors.push(err.to_string());
+    }
+
+    if !currency_report.is_empty() {
+        warn!("{retailer_name:?} returned unexpected currencies: {currency_report:?}");
+        webhook.record_unexpected_currencies(
+            retailer_name,
+            currency_report.rejected,
+            currency_report.converted,
+        );
+    }
+
+    if !degraded.is_empty() {
+        let reasons: Vec<String> = degraded
+            .iter()
+            .map(|degraded| degraded.reason.clone())
+            .collect();
+
+        warn!("{retailer_name:?} has degraded categories: {reasons:?}");
+        webhook.record_degraded_categories(retailer_name, reasons);
+    }
+
+    webhook.record_rule_decisions(retailer_name, rule_decisions.counts.clone());
+    webhook.record_skipped_products(retailer_name, retailer.get_skipped());
+    webhook.append_retailer_stats(retailer_name, &counts);
+    webhook.update_main_message().await;
+
+    // finalizing can take a while, don't hold up the other retailers' reports
+    drop(webhook);
+
+    if !context.dry_run {
+        retailer.emit_metrics(&counts);
+
+        if timed_out {
+            put_metric!(Metrics::RetailerTimedOut, 1, "retailer" => retailer_name.to_string());
+        }
+
+        // partial results stay in crawl-results, and only make it to the live view when
+        // the budget policy keeps them. A crawl that fails to finalize didn't succeed,
+        // so it isn't marked finished and --resume picks it back up
+        if (crawl_succeeded || keep_partial)
+            && let Err(err) = context
+                .db
+                .finalize_results(retailer_name, crawl_start)
+                .await
+        {
+            let reason = format!("Failed to finalize results: {err}");
+
+            warn!("{retailer_name:?} {reason}");
+
+            let mut webhook = get_indexer_webhook().await;
+            webhook.record_retailer_failure(retailer_name, reason.clone());
+            webhook.update_main_message().await;
+
+            record.errors.push(reason);
+            crawl_succeeded = false;
+        }
+
+        if crawl_succeeded {
+            if let Some(crawl_diff) = &crawl_diff
+                && let Err(err) = context.db.insert_crawl_diff(crawl_diff).await
+            {
+                warn!("Failed to save {retailer_name:?} crawl diff: {err}");
+            }
+
+            if let Err(err) =
+                link_canonical_products(context.db.as_ref(), retailer_name, crawl_start).await
+            {
+                warn!("Failed to link {retailer_name:?} canonical products: {err}");
+            }
+
+            if let Err(err) = context
+                .db
+                .save_crawl_checkpoint(&CollectionCrawlCheckpoint::finished(
+                    retailer_name,
+                    crawl_start,
+                ))
+                .await
+            {
+                warn!("Failed to mark {retailer_name:?} finished: {err}");
+            }
+
+            for (category, count) in category_counts {
+                let baseline = baselines
+                    .iter()
+                    .find(|baseline| baseline.category == category);
+
+                // don't start tracking categories the retailer doesn't carry
+                if count == 0 && baseline.is_none() {
+                    continue;
+                }
+
+                let count_dropped = degraded
+                    .iter()
+                    .any(|degraded| degraded.category == category && degraded.count_dropped);
+                let skipped = baseline.map_or(0, |baseline| baseline.skipped);
+
+                // a broken category would drag the baseline down to where it stops being
+                // flagged, unless it stays down long enough to be the retailer's new normal
+                let skip = count_dropped && skipped < MAX_BASELINE_SKIPS;
+                // once rolling, the skips stay maxed out until a crawl stops dropping
+                let new_skipped = match (skip, count_dropped) {
+                    (true, _) => Some(skipped + 1),
+                    (false, false) if skipped > 0 => Some(0),
+                    _ => None,
+                };
+
+                if let Some(new_skipped) = new_skipped
+                    && let Err(err) = context
+                        .db
+                        .set_crawl_baseline_skipped(retailer_name, category, new_skipped)
+                        .await
+                {
+                    warn!("Failed to update {retailer_name:?} {category:?} baseline: {err}");
+                }
+
+                if skip {
+                    continue;
+                }
+
+                if let Err(err) = context
+                    .db
+                    .update_crawl_baseline(retailer_name, category, count, BASELINE_WINDOW)
+                    .await
+                {
+                    warn!("Failed to update {retailer_name:?} {category:?} baseline: {err}");
+                }
+            }
+        }
+    }
+
+    CrawlRunRetailer {
+        end_time: get_current_time(),
+        succeeded: crawl_succeeded,
+        timed_out,
+        interrupted,
+        pages_fetched: retailer.get_pages_fetched(),
+        skipped_products: retailer.get_skipped().len() as u64,
+        rule_counts: rule_decisions.counts,
+        rule_decisions: rule_decisions
+            .sample
+            .iter()
+            .map(CrawlRuleDecision::from)
+            .collect(),
+        ..record.with_counts(counts)
+    }
+}
@@ -0,0 +1,265 @@
+use std::{collections::BTreeMap, str::FromStr, sync::Arc, time::Duration};
+
+use common::result::enums::RetailerName;
+use rand::Rng;
+use thiserror::Error;
+use tokio::{
+    sync::mpsc::{UnboundedSender, unbounded_channel},
+    task::JoinHandle,
+    time::{Instant, sleep_until},
+};
+use tracing::{info, warn};
+
+use crate::{
+    run::{RunContext, RunFeed, run_retailers},
+    shutdown::{is_shutting_down, wait_for_shutdown},
+};
+
+#[derive(Error, Debug)]
+pub(crate) enum ScheduleError {
+    #[error("Invalid duration '{0}', expected a number followed by m, h or d")]
+    InvalidDuration(String),
+    #[error("Invalid schedule '{0}', expected RETAILER=DURATION")]
+    InvalidSchedule(String),
+}
+
+/// Parses durations like "30m", "2h" or "1d"
+pub(crate) fn parse_duration(input: &str) -> Result<Duration, ScheduleError> {
+    let invalid = || ScheduleError::InvalidDuration(input.to_string());
+
+    let input = input.trim();
+    let split = input.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = input.split_at_checked(split).ok_or_else(invalid)?;
+
+    let amount = amount.parse::<u64>().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    if amount == 0 {
+        return Err(invalid());
+    }
+
+    Ok(Duration::from_secs(amount * seconds))
+}
+
+/// How often a single retailer is crawled, ie. "Tenda=2h"
+#[derive(Debug, Clone)]
+pub(crate) struct RetailerSchedule {
+    retailer: RetailerName,
+    interval: Duration,
+}
+
+impl FromStr for RetailerSchedule {
+    type Err = ScheduleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || ScheduleError::InvalidSchedule(input.to_string());
+
+        let Some((retailer, interval)) = input.split_once('=') else {
+            return Err(invalid());
+        };
+
+        let retailer = RetailerName::from_str(retailer.trim()).map_err(|_| invalid())?;
+        let interval = parse_duration(interval)?;
+
+        Ok(Self { retailer, interval })
+    }
+}
+
+pub(crate) struct SchedulerOptions {
+    /// Used by every retailer without its own schedule
+    pub(crate) default_interval: Duration,
+    pub(crate) schedules: Vec<RetailerSchedule>,
+    /// Upper bound of the random delay added to every run,
+    /// keeps retailers on the same interval from drifting into lockstep
+    pub(crate) jitter: Duration,
+    /// Number of times a failed retailer is retried before
+    /// it falls back to its normal interval
+    pub(crate) max_retries: u32,
+    /// Delay before the first retry, doubled for every retry after
+    pub(crate) retry_backoff: Duration,
+}
+
+struct ScheduledRetailer {
+    interval: Duration,
+    next_run: Instant,
+    failures: u32,
+    // part of the current run, and not done crawling yet
+    running: bool,
+}
+
+impl ScheduledRetailer {
+    fn reschedule(&mut self, succeeded: bool, options: &SchedulerOptions) {
+        self.failures = match succeeded {
+            true => 0,
+            false => self.failures + 1,
+        };
+
+        let delay = match self.failures {
+            0 => self.interval,
+            failures if failures > options.max_retries => {
+                self.failures = 0;
+                self.interval
+            }
+            failures => options
+                .retry_backoff
+                .saturating_mul(2_u32.saturating_pow(failures - 1))
+                .min(self.interval),
+        };
+
+        self.next_run = Instant::now() + delay + get_jitter(options.jitter);
+    }
+}
+
+fn get_jitter(max_jitter: Duration) -> Duration {
+    if max_jitter.is_zero() {
+        return Duration::ZERO;
+    }
+
+    rand::rng().random_range(Duration::ZERO..=max_jitter)
+}
+
+/// Keeps crawling the retailers on their schedules until SIGTERM is received.
+///
+/// Retailers that come due while a run is going are added to that run, so a slow
+/// retailer never holds up the rest, and a retailer is never crawled while its
+/// previous crawl is still going. The run wraps up and posts its report once
+/// nothing in it is left crawling. A shutdown winds the run down like any other run.
+pub(crate) async fn run_scheduler(
+    context: Arc<RunContext>,
+    retailers: Vec<RetailerName>,
+    options: SchedulerOptions,
+    mut resume: bool,
+) {
+    let now = Instant::now();
+    let mut scheduled: BTreeMap<RetailerName, ScheduledRetailer> = retailers
+        .into_iter()
+        .map(|retailer| {
+            let interval = options
+                .schedules
+                .iter()
+                .rev()
+                .find(|schedule| schedule.retailer == retailer)
+                .map_or(options.default_interval, |schedule| schedule.interval);
+
+            let state = ScheduledRetailer {
+                interval,
+                next_run: now,
+                failures: 0,
+                running: false,
+            };
+
+            (retailer, state)
+        })
+        .collect();
+
+    if scheduled.is_empty() {
+        warn!("No retailers to schedule, exiting");
+        return;
+    }
+
+    let (outcome_sender, mut outcome_receiver) = unbounded_channel();
+    let mut run: Option<JoinHandle<BTreeMap<RetailerName, bool>>> = None;
+    // dropped once nothing in the run is left crawling, which lets the run wrap up
+    let mut additions: Option<UnboundedSender<Vec<RetailerName>>> = None;
+
+    loop {
+        // a run that's wrapping up still holds the run lock, so the next one waits for it
+        let next_run = match additions.is_some() || run.is_none() {
+            true => scheduled
+                .values()
+                .filter(|state| !state.running)
+                .map(|state| state.next_run)
+                .min(),
+            false => None,
+        };
+
+        tokio::select! {
+            // a run sends every outcome before it finishes, so they're all in before it's joined
+            biased;
+
+            Some((retailer, succeeded)) = outcome_receiver.recv() => {
+                if let Some(state) = scheduled.get_mut(&retailer) {
+                    state.running = false;
+                    state.reschedule(succeeded, &options);
+
+                    if !succeeded {
+                        warn!(
+                            "{retailer:?} failed, next attempt in {:?}",
+                            state.next_run - Instant::now()
+                        );
+                    }
+                }
+
+                if scheduled.values().all(|state| !state.running) {
+                    additions = None;
+                }
+            }
+            _ = async { run.as_mut()?.await.ok() }, if run.is_some() => {
+                run = None;
+                additions = None;
+
+                if is_shutting_down() {
+                    info!("Stopped the current run for shutdown, exiting");
+                    return;
+                }
+
+                // a run skipped because another indexer holds the lock has no outcomes
+                for (retailer, state) in scheduled.iter_mut().filter(|(_, state)| state.running) {
+                    state.running = false;
+                    state.reschedule(false, &options);
+
+                    warn!(
+                        "{retailer:?} was not crawled, next attempt in {:?}",
+                        state.next_run - Instant::now()
+                    );
+                }
+            }
+            _ = sleep_until(next_run.unwrap_or_else(Instant::now)), if next_run.is_some() => {
+                let now = Instant::now();
+                let due: Vec<RetailerName> = scheduled
+                    .iter_mut()
+                    .filter(|(_, state)| !state.running && state.next_run <= now)
+                    .map(|(retailer, state)| {
+                        state.running = true;
+                        *retailer
+                    })
+                    .collect();
+
+                if let Some(additions) = &additions {
+                    // a run that stopped early has its retailers rescheduled once it's joined
+                    let _ = additions.send(due);
+                    continue;
+                }
+
+                info!("Starting scheduled run for {due:?}");
+
+                let (sender, receiver) = unbounded_channel();
+                let feed = RunFeed {
+                    additions: receiver,
+                    outcomes: outcome_sender.clone(),
+                };
+
+                run = Some(tokio::spawn(run_retailers(
+                    context.clone(),
+                    due,
+                    Vec::new(),
+                    resume,
+                    Some(feed),
+                )));
+                additions = Some(sender);
+
+                // only the first run picks up after an interrupted process
+                resume = false;
+            }
+            _ = wait_for_shutdown(), if run.is_none() => {
+                info!("Received shutdown signal, exiting");
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::{process, sync::LazyLock};
+
+use tokio::{
+    signal::unix::{SignalKind, signal},
+    sync::watch,
+};
+use tracing::{info, warn};
+
+// flips to true once, on the first SIGTERM or Ctrl-C
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+/// Whether the indexer was asked to stop, crawls check this
+/// before every page instead of being cancelled mid request
+pub(crate) fn is_shutting_down() -> bool {
+    *SHUTDOWN.borrow()
+}
+
+pub(crate) async fn wait_for_shutdown() {
+    let _ = SHUTDOWN.subscribe().wait_for(|shutdown| *shutdown).await;
+}
+
+/// Starts listening for SIGTERM and Ctrl-C. The first one stops new pages and
+/// retailers from starting, while everything in flight finishes and gets written,
+/// a second one exits right away for when that's taking too long
+pub(crate) fn listen_for_shutdown() {
+    let mut terminate = signal(SignalKind::terminate()).expect("SIGTERM handler to install");
+
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+
+        info!("Received shutdown signal, finishing in flight requests");
+        SHUTDOWN.send_replace(true);
+
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+
+        warn!("Received second shutdown signal, exiting without waiting");
+        process::exit(1);
+    });
+}
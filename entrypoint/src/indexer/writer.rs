@@ -0,0 +1,138 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use common::{crawl_checkpoint::CollectionCrawlCheckpoint, result::base::CrawlResult};
+use mongodb_connector::{errors::StorageError, storage::Storage};
+use tokio::sync::mpsc::Receiver;
+use tracing::{debug, warn};
+
+use crate::{
+    clients::{sink::StreamedResult, utils::get_key},
+    export::ResultExport,
+};
+
+/// Number of results held onto before they're written to crawl-results
+const BATCH_SIZE: usize = 250;
+/// Checkpoints are only saved after a batch is written, unless this long
+/// has passed since the last one was saved, only the latest one is kept
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Writes results into crawl-results as they're streamed out of a client, until the
+/// sink is finished. `since` is the start of the crawl, and scopes replacements
+/// to results written during this crawl. Checkpoints are only saved once everything
+/// before them has been written. Results are also collected into `export`, which
+/// is written out once the sink finishes, even during a dry run or after a failed write
+pub(crate) async fn write_results(
+    db: Arc<dyn Storage>,
+    mut receiver: Receiver<StreamedResult>,
+    since: u64,
+    dry_run: bool,
+    mut export: Option<ResultExport>,
+) -> Result<(), StorageError> {
+    let mut writer = BatchWriter::new(db.as_ref(), since);
+    let mut written = Ok(());
+
+    while let Some(streamed) = receiver.recv().await {
+        if let Some(export) = &mut export
+            && let StreamedResult::New(result) | StreamedResult::Replacement(result) = &streamed
+        {
+            export.push(result);
+        }
+
+        if dry_run {
+            debug!("{streamed:?}");
+            continue;
+        }
+
+        // keeps draining the stream after a failed write, so the sink isn't left hanging
+        if written.is_ok() {
+            written = writer.write(streamed).await;
+        }
+    }
+
+    if !dry_run && written.is_ok() {
+        written = writer.flush().await;
+    }
+
+    if let Some(export) = export
+        && let Err(err) = export.write()
+    {
+        warn!("Failed to write result export: {err}");
+    }
+
+    written
+}
+
+struct BatchWriter<'a> {
+    db: &'a dyn Storage,
+    since: u64,
+    batch: Vec<CrawlResult>,
+    // latest checkpoint that hasn't been saved yet
+    checkpoint: Option<CollectionCrawlCheckpoint>,
+    last_checkpoint: Option<Instant>,
+}
+
+impl<'a> BatchWriter<'a> {
+    fn new(db: &'a dyn Storage, since: u64) -> Self {
+        Self {
+            db,
+            since,
+            batch: Vec::with_capacity(BATCH_SIZE),
+            checkpoint: None,
+            last_checkpoint: None,
+        }
+    }
+
+    async fn write(&mut self, streamed: StreamedResult) -> Result<(), StorageError> {
+        match streamed {
+            StreamedResult::New(result) => self.batch.push(result),
+            StreamedResult::Replacement(result) => {
+                let key = get_key(&result);
+
+                // the original might not have made it out of the batch yet
+                match self
+                    .batch
+                    .iter_mut()
+                    .find(|pending| get_key(pending) == key)
+                {
+                    Some(pending) => *pending = result,
+                    None => self.db.replace_result(&result, self.since).await?,
+                }
+            }
+            StreamedResult::Checkpoint(checkpoint) => {
+                self.checkpoint = Some(checkpoint);
+
+                // the first checkpoint goes out right away, replacing the previous run's
+                let due = self
+                    .last_checkpoint
+                    .is_none_or(|last_checkpoint| last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL);
+
+                if due {
+                    return self.flush().await;
+                }
+            }
+        }
+
+        if self.batch.len() >= BATCH_SIZE {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the batch, then the latest checkpoint, results written past the
+    /// checkpoint are restored into the sink on resume rather than duplicated
+    async fn flush(&mut self) -> Result<(), StorageError> {
+        self.db.insert_results(self.batch.iter().collect()).await?;
+        self.batch.clear();
+
+        if let Some(checkpoint) = self.checkpoint.take() {
+            self.db.save_crawl_checkpoint(&checkpoint).await?;
+            self.last_checkpoint = Some(Instant::now());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use clap::ValueEnum;
+use mongodb_connector::{connector::MongoDBConnector, memory::InMemoryStorage, storage::Storage};
+
+/// Where the API and indexer keep their data, picked with --storage
+#[derive(Debug, Default, Clone, Copy, ValueEnum)]
+pub enum StorageBackend {
+    /// MongoDB at MONGO_DB_HOST and MONGO_DB_PORT
+    #[default]
+    Mongodb,
+    /// Gone once the process exits
+    Memory,
+}
+
+impl StorageBackend {
+    pub async fn connect(self) -> Box<dyn Storage> {
+        match self {
+            StorageBackend::Mongodb => Box::new(MongoDBConnector::new().await),
+            StorageBackend::Memory => Box::new(InMemoryStorage::new()),
+        }
+    }
+}
@@ -44,6 +44,6 @@ impl ImageCache {
             return Some(downloaded_image);
         }
 
-        return None;
+        None
     }
 }
@@ -1,4 +1,8 @@
-use std::{env, sync::LazyLock};
+use std::{
+    env,
+    fmt::{Display, Formatter, Result},
+    sync::LazyLock,
+};
 
 use strum_macros::EnumIter;
 
@@ -21,18 +25,24 @@ pub enum Metrics {
     CrawledAmmunitionNoRoundCount,
     /// Counter for accessory product parsed
     CrawledOther,
+    /// Counter for products skipped since they failed to parse
+    SkippedProduct,
+    /// Counter for retailers cancelled for running past their time or request budget
+    RetailerTimedOut,
 }
 
-impl Metrics {
-    fn to_string(&self) -> String {
-        match self {
-            Metrics::CrawledFirearm => "CRAWLED_FIREARM".to_string(),
-            Metrics::CrawledAmmunition => "CRAWLED_AMMUNITION".to_string(),
-            Metrics::CrawledOther => "CRAWLED_OTHER".to_string(),
-            Metrics::CrawledAmmunitionNoRoundCount => {
-                "CRAWLED_AMMUNITION_NO_ROUND_COUNT".to_string()
-            }
-        }
+impl Display for Metrics {
+    fn fmt(&self, format: &mut Formatter) -> Result {
+        let name = match self {
+            Metrics::CrawledFirearm => "CRAWLED_FIREARM",
+            Metrics::CrawledAmmunition => "CRAWLED_AMMUNITION",
+            Metrics::CrawledOther => "CRAWLED_OTHER",
+            Metrics::CrawledAmmunitionNoRoundCount => "CRAWLED_AMMUNITION_NO_ROUND_COUNT",
+            Metrics::SkippedProduct => "SKIPPED_PRODUCT",
+            Metrics::RetailerTimedOut => "RETAILER_TIMED_OUT",
+        };
+
+        write!(format, "{name}")
     }
 }
 
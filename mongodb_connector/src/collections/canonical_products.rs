@@ -0,0 +1,134 @@
+use common::{
+    canonical_product::{CanonicalLink, CollectionCanonicalProduct},
+    result::enums::RetailerName,
+    utils::get_current_time,
+};
+use mongodb::{
+    Client, Collection, Database, IndexModel,
+    bson::{doc, to_bson},
+    options::IndexOptions,
+};
+use tracing::warn;
+
+use crate::{
+    constants::{COLLECTION_CANONICAL_PRODUCTS_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
+
+const URL_INDEX_NAME: &str = "listing_url_index";
+const RETAILER_INDEX_NAME: &str = "listing_retailer_index";
+
+pub(crate) struct CanonicalProductsCollection {
+    collection: Collection<CollectionCanonicalProduct>,
+}
+
+impl CanonicalProductsCollection {
+    pub(crate) async fn new(client: Client) -> Self {
+        let db = client.database(DATABASE_NAME);
+
+        Self::create_collection(&db).await;
+
+        Self {
+            collection: db
+                .collection::<CollectionCanonicalProduct>(COLLECTION_CANONICAL_PRODUCTS_NAME),
+        }
+    }
+
+    async fn create_collection(db: &Database) {
+        db.create_collection(COLLECTION_CANONICAL_PRODUCTS_NAME)
+            .await
+            .unwrap_or_else(|_| {
+                panic!("Creating {COLLECTION_CANONICAL_PRODUCTS_NAME} collection to not fail")
+            });
+
+        let indexes = vec![
+            IndexModel::builder()
+                .keys(doc! { "listings.url": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .name(URL_INDEX_NAME.to_string())
+                        .build(),
+                )
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "listings.retailer": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .name(RETAILER_INDEX_NAME.to_string())
+                        .build(),
+                )
+                .build(),
+        ];
+
+        db.collection::<CollectionCanonicalProduct>(COLLECTION_CANONICAL_PRODUCTS_NAME)
+            .create_indexes(indexes)
+            .await
+            .unwrap();
+    }
+
+    /// Drops every listing of the retailer, along with the products left without any
+    pub(crate) async fn unlink_retailer(&self, retailer: RetailerName) -> Result<(), StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+        self.collection
+            .update_many(
+                doc! { "listings.retailer": retailer.clone() },
+                doc! { "$pull": { "listings": { "retailer": retailer } } },
+            )
+            .await?;
+
+        self.collection
+            .delete_many(doc! { "listings": { "$size": 0 } })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Adds the listing to its product, creating the product if it's the first listing
+    pub(crate) async fn link_listing(&self, link: &CanonicalLink) -> Result<(), StorageError> {
+        let category = to_bson(&link.category).expect("Category to serialize correctly");
+        let attributes =
+            to_bson(&link.attributes).expect("ProductAttributes to serialize correctly");
+        let listing = to_bson(&link.listing).expect("CanonicalListing to serialize correctly");
+
+        self.collection
+            .update_one(
+                doc! { "_id": link.key.clone() },
+                doc! {
+                    "$setOnInsert": {
+                        "category": category,
+                        "name": link.listing.name.clone(),
+                        "attributes": attributes,
+                    },
+                    "$set": { "updated_at": get_current_time() as i64 },
+                    "$push": { "listings": listing },
+                },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Products with a listing at any of the URLs
+    pub(crate) async fn get_products_by_url(
+        &self,
+        urls: Vec<String>,
+    ) -> Result<Vec<CollectionCanonicalProduct>, StorageError> {
+        let mut cursor = self
+            .collection
+            .find(doc! { "listings.url": { "$in": urls } })
+            .await?;
+
+        let mut products: Vec<CollectionCanonicalProduct> = Vec::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(product) => products.push(product),
+                Err(err) => warn!("Skipping canonical product that failed to deserialize: {err}"),
+            }
+        }
+
+        Ok(products)
+    }
+}
@@ -0,0 +1,137 @@
+use common::{
+    crawl_baseline::CollectionCrawlBaseline,
+    result::enums::{Category, RetailerName},
+};
+use mongodb::{
+    Client, Collection, Database, IndexModel,
+    bson::{doc, to_bson},
+    options::IndexOptions,
+};
+
+use crate::{
+    constants::{COLLECTION_CRAWL_BASELINES_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
+
+const INDEX_NAME: &str = "retailer_category_index";
+
+pub(crate) struct CrawlBaselinesCollection {
+    collection: Collection<CollectionCrawlBaseline>,
+}
+
+impl CrawlBaselinesCollection {
+    pub(crate) async fn new(client: Client) -> Self {
+        let db = client.database(DATABASE_NAME);
+
+        Self::create_collection(&db).await;
+
+        Self {
+            collection: db.collection::<CollectionCrawlBaseline>(COLLECTION_CRAWL_BASELINES_NAME),
+        }
+    }
+
+    async fn create_collection(db: &Database) {
+        db.create_collection(COLLECTION_CRAWL_BASELINES_NAME)
+            .await
+            .unwrap_or_else(|_| {
+                panic!("Creating {COLLECTION_CRAWL_BASELINES_NAME} collection to not fail")
+            });
+
+        let index = IndexModel::builder()
+            .keys(doc! {
+                "retailer": 1,
+                "category": 1
+            })
+            .options(
+                IndexOptions::builder()
+                    .name(INDEX_NAME.to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+
+        db.collection::<CollectionCrawlBaseline>(COLLECTION_CRAWL_BASELINES_NAME)
+            .create_index(index)
+            .await
+            .unwrap();
+    }
+
+    pub(crate) async fn get_baselines(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Vec<CollectionCrawlBaseline>, StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+        let mut cursor = self.collection.find(doc! { "retailer": retailer }).await?;
+
+        let mut baselines = Vec::new();
+
+        while cursor.advance().await? {
+            let Ok(baseline) = cursor.deserialize_current() else {
+                continue;
+            };
+
+            baselines.push(baseline);
+        }
+
+        Ok(baselines)
+    }
+
+    /// Appends the latest count, only keeping the last `window` counts around
+    pub(crate) async fn push_count(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        count: u64,
+        window: u64,
+    ) -> Result<(), StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+        let category = to_bson(&category).expect("Category to serialize correctly");
+
+        self.collection
+            .update_one(
+                doc! {
+                    "retailer": retailer,
+                    "category": category
+                },
+                doc! {
+                    "$push": doc! {
+                        "counts": doc! {
+                            "$each": [count as i64],
+                            "$slice": -(window as i64)
+                        }
+                    }
+                },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn set_skipped(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        skipped: u64,
+    ) -> Result<(), StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+        let category = to_bson(&category).expect("Category to serialize correctly");
+
+        self.collection
+            .update_one(
+                doc! {
+                    "retailer": retailer,
+                    "category": category
+                },
+                doc! {
+                    "$set": doc! { "skipped": skipped as i64 },
+                    "$setOnInsert": doc! { "counts": [] }
+                },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+}
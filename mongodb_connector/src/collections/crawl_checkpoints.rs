@@ -0,0 +1,80 @@
+use common::{crawl_checkpoint::CollectionCrawlCheckpoint, result::enums::RetailerName};
+use mongodb::{
+    Client, Collection, Database, IndexModel,
+    bson::{doc, to_bson},
+    options::IndexOptions,
+};
+
+use crate::{
+    constants::{COLLECTION_CRAWL_CHECKPOINTS_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
+
+const INDEX_NAME: &str = "retailer_index";
+
+pub(crate) struct CrawlCheckpointsCollection {
+    collection: Collection<CollectionCrawlCheckpoint>,
+}
+
+impl CrawlCheckpointsCollection {
+    pub(crate) async fn new(client: Client) -> Self {
+        let db = client.database(DATABASE_NAME);
+
+        Self::create_collection(&db).await;
+
+        Self {
+            collection: db
+                .collection::<CollectionCrawlCheckpoint>(COLLECTION_CRAWL_CHECKPOINTS_NAME),
+        }
+    }
+
+    async fn create_collection(db: &Database) {
+        db.create_collection(COLLECTION_CRAWL_CHECKPOINTS_NAME)
+            .await
+            .unwrap_or_else(|_| {
+                panic!("Creating {COLLECTION_CRAWL_CHECKPOINTS_NAME} collection to not fail")
+            });
+
+        let index = IndexModel::builder()
+            .keys(doc! { "retailer": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name(INDEX_NAME.to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+
+        db.collection::<CollectionCrawlCheckpoint>(COLLECTION_CRAWL_CHECKPOINTS_NAME)
+            .create_index(index)
+            .await
+            .unwrap();
+    }
+
+    pub(crate) async fn get_checkpoint(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlCheckpoint>, StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+        Ok(self
+            .collection
+            .find_one(doc! { "retailer": retailer })
+            .await?)
+    }
+
+    /// Only one checkpoint is kept per retailer, the latest one wins
+    pub(crate) async fn save_checkpoint(
+        &self,
+        checkpoint: &CollectionCrawlCheckpoint,
+    ) -> Result<(), StorageError> {
+        let retailer = to_bson(&checkpoint.retailer).expect("RetailerName to serialize correctly");
+
+        self.collection
+            .replace_one(doc! { "retailer": retailer }, checkpoint)
+            .upsert(true)
+            .await?;
+
+        Ok(())
+    }
+}
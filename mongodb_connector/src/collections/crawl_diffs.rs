@@ -0,0 +1,88 @@
+use common::{crawl_diff::CollectionCrawlDiff, result::enums::RetailerName};
+use mongodb::{
+    Client, Collection, Database, IndexModel,
+    bson::{doc, oid::ObjectId, to_bson},
+    options::IndexOptions,
+};
+use tracing::warn;
+
+use crate::{
+    constants::{COLLECTION_CRAWL_DIFFS_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
+
+const INDEX_NAME: &str = "retailer_crawl_start_index";
+
+pub(crate) struct CrawlDiffsCollection {
+    collection: Collection<CollectionCrawlDiff>,
+}
+
+impl CrawlDiffsCollection {
+    pub(crate) async fn new(client: Client) -> Self {
+        let db = client.database(DATABASE_NAME);
+
+        Self::create_collection(&db).await;
+
+        Self {
+            collection: db.collection::<CollectionCrawlDiff>(COLLECTION_CRAWL_DIFFS_NAME),
+        }
+    }
+
+    async fn create_collection(db: &Database) {
+        db.create_collection(COLLECTION_CRAWL_DIFFS_NAME)
+            .await
+            .unwrap_or_else(|_| {
+                panic!("Creating {COLLECTION_CRAWL_DIFFS_NAME} collection to not fail")
+            });
+
+        let index = IndexModel::builder()
+            .keys(doc! {
+                "retailer": 1,
+                "crawl_start": -1
+            })
+            .options(IndexOptions::builder().name(INDEX_NAME.to_string()).build())
+            .build();
+
+        db.collection::<CollectionCrawlDiff>(COLLECTION_CRAWL_DIFFS_NAME)
+            .create_index(index)
+            .await
+            .unwrap();
+    }
+
+    pub(crate) async fn insert_diff(&self, diff: &CollectionCrawlDiff) -> Result<(), StorageError> {
+        self.collection.insert_one(diff).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn get_run_diffs(
+        &self,
+        run_id: ObjectId,
+    ) -> Result<Vec<CollectionCrawlDiff>, StorageError> {
+        let mut cursor = self.collection.find(doc! { "run_id": run_id }).await?;
+
+        let mut diffs = Vec::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(diff) => diffs.push(diff),
+                Err(err) => warn!("Skipping crawl diff that failed to deserialize: {err}"),
+            }
+        }
+
+        Ok(diffs)
+    }
+
+    pub(crate) async fn get_latest_diff(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlDiff>, StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+        Ok(self
+            .collection
+            .find_one(doc! { "retailer": retailer })
+            .sort(doc! { "crawl_start": -1 })
+            .await?)
+    }
+}
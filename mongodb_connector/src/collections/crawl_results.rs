@@ -1,7 +1,14 @@
-use common::result::base::CrawlResult;
-use mongodb::{Client, Collection, Database, bson::doc};
+use common::result::{base::CrawlResult, enums::RetailerName};
+use mongodb::{
+    Client, Collection, Database,
+    bson::{doc, to_bson},
+};
+use tracing::warn;
 
-use crate::constants::{COLLECTION_CRAWL_RESULTS_NAME, DATABASE_NAME, VIEW_LIVE_DATA_NAME};
+use crate::{
+    constants::{COLLECTION_CRAWL_RESULTS_NAME, DATABASE_NAME, VIEW_LIVE_DATA_NAME},
+    errors::StorageError,
+};
 
 pub(crate) struct CrawlResultsCollection {
     collection: Collection<CrawlResult>,
@@ -26,18 +33,117 @@ impl CrawlResultsCollection {
             });
     }
 
-    pub(crate) async fn insert_results(&self, results: Vec<&CrawlResult>) {
-        self.collection.insert_many(results).await.unwrap();
+    pub(crate) async fn insert_results(
+        &self,
+        results: Vec<&CrawlResult>,
+    ) -> Result<(), StorageError> {
+        self.collection.insert_many(results).await?;
+
+        Ok(())
+    }
+
+    /// Swaps out a result from the current crawl for a better ranked duplicate
+    pub(crate) async fn replace_result(
+        &self,
+        result: &CrawlResult,
+        since: u64,
+    ) -> Result<(), StorageError> {
+        let retailer = to_bson(&result.retailer).expect("RetailerName to serialize correctly");
+
+        self.collection
+            .replace_one(
+                doc! {
+                    "name": &result.name,
+                    "url": &result.url,
+                    "retailer": retailer,
+                    "query_time": {"$gte": since as i64},
+                },
+                result,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn get_results_since(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+        let mut cursor = self
+            .collection
+            .find(doc! { "retailer": retailer, "query_time": {"$gte": since as i64} })
+            .await?;
+
+        let mut results: Vec<CrawlResult> = Vec::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(result) => results.push(result),
+                Err(err) => warn!("Skipping crawl result that failed to deserialize: {err}"),
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Product IDs the retailer's results at the URL were given, only those with
+    /// the same name if there are any, so variants sharing a URL stay apart
+    pub(crate) async fn get_product_ids_at_url(
+        &self,
+        retailer: RetailerName,
+        url: &str,
+        name: &str,
+    ) -> Result<Vec<String>, StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+        let filter = doc! {
+            "retailer": retailer,
+            "url": url,
+            "product_id": { "$type": "string" },
+        };
+
+        let mut named_filter = filter.clone();
+        named_filter.insert("name", name);
+
+        for filter in [named_filter, filter] {
+            let product_ids: Vec<String> = self
+                .collection
+                .distinct("product_id", filter)
+                .await?
+                .into_iter()
+                .filter_map(|product_id| product_id.as_str().map(String::from))
+                .collect();
+
+            if !product_ids.is_empty() {
+                return Ok(product_ids);
+            }
+        }
+
+        Ok(Vec::new())
     }
 
-    pub(crate) async fn update_view(&self, prev_days: i64) {
+    /// Only merges in the given retailer's results from the crawl that started at `since`,
+    /// so partial results a failed crawl left behind never make it into the live view
+    pub(crate) async fn update_view(
+        &self,
+        prev_days: i64,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<(), StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+        let min_query_time = prev_days.max(since as i64);
+
         self.collection
             .aggregate(vec![
-                doc! {"$match": {"query_time": {"$gte": prev_days}}},
+                doc! {"$match": {"query_time": {"$gte": min_query_time}, "retailer": retailer}},
                 doc! {"$merge": {"into": VIEW_LIVE_DATA_NAME, "whenMatched": "keepExisting", "on": "_id"}},
             ])
             .with_type::<CrawlResult>()
-            .await
-            .unwrap();
+            .await?;
+
+        Ok(())
     }
 }
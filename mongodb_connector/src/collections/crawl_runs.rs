@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use common::{
+    crawl_run::{CollectionCrawlRun, CrawlRunRetailer},
+    result::enums::RetailerName,
+};
+use mongodb::{
+    Client, Collection, Database, IndexModel,
+    bson::{doc, oid::ObjectId, to_bson},
+    options::IndexOptions,
+};
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::{
+    constants::{COLLECTION_CRAWL_RUNS_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
+
+const INDEX_NAME: &str = "start_time_index";
+
+#[derive(Deserialize)]
+struct LastSuccessfulCrawl {
+    #[serde(rename = "_id")]
+    retailer: RetailerName,
+    end_time: u64,
+}
+
+pub(crate) struct CrawlRunsCollection {
+    collection: Collection<CollectionCrawlRun>,
+}
+
+impl CrawlRunsCollection {
+    pub(crate) async fn new(client: Client) -> Self {
+        let db = client.database(DATABASE_NAME);
+
+        Self::create_collection(&db).await;
+
+        Self {
+            collection: db.collection::<CollectionCrawlRun>(COLLECTION_CRAWL_RUNS_NAME),
+        }
+    }
+
+    async fn create_collection(db: &Database) {
+        db.create_collection(COLLECTION_CRAWL_RUNS_NAME)
+            .await
+            .unwrap_or_else(|_| {
+                panic!("Creating {COLLECTION_CRAWL_RUNS_NAME} collection to not fail")
+            });
+
+        let index = IndexModel::builder()
+            .keys(doc! { "start_time": -1 })
+            .options(IndexOptions::builder().name(INDEX_NAME.to_string()).build())
+            .build();
+
+        db.collection::<CollectionCrawlRun>(COLLECTION_CRAWL_RUNS_NAME)
+            .create_index(index)
+            .await
+            .unwrap();
+    }
+
+    pub(crate) async fn insert_run(&self, run: &CollectionCrawlRun) -> Result<(), StorageError> {
+        self.collection.insert_one(run).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn push_retailer(
+        &self,
+        run_id: ObjectId,
+        retailer: &CrawlRunRetailer,
+    ) -> Result<(), StorageError> {
+        let retailer = to_bson(retailer).expect("CrawlRunRetailer to serialize correctly");
+
+        self.collection
+            .update_one(
+                doc! { "_id": run_id },
+                doc! { "$push": doc! { "retailers": retailer } },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn finish_run(
+        &self,
+        run_id: ObjectId,
+        end_time: u64,
+    ) -> Result<(), StorageError> {
+        self.collection
+            .update_one(
+                doc! { "_id": run_id },
+                doc! { "$set": doc! { "end_time": end_time as i64 } },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// The retailer's latest crawl that succeeded, from any run, ignoring
+    /// crawls skipped for already finishing in an interrupted run
+    pub(crate) async fn get_last_successful_retailer(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CrawlRunRetailer>, StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+        let element_filter = doc! {
+            "retailer": retailer.clone(),
+            "succeeded": true,
+            "skipped": { "$ne": true },
+        };
+        let unwound_filter = doc! {
+            "retailers.retailer": retailer,
+            "retailers.succeeded": true,
+            "retailers.skipped": { "$ne": true },
+        };
+
+        let mut cursor = self
+            .collection
+            .aggregate([
+                doc! { "$match": { "retailers": { "$elemMatch": element_filter } } },
+                doc! { "$unwind": "$retailers" },
+                doc! { "$match": unwound_filter },
+                doc! { "$sort": { "retailers.start_time": -1 } },
+                doc! { "$limit": 1 },
+                doc! { "$replaceRoot": { "newRoot": "$retailers" } },
+            ])
+            .with_type::<CrawlRunRetailer>()
+            .await?;
+
+        match cursor.advance().await? {
+            true => Ok(cursor.deserialize_current().ok()),
+            false => Ok(None),
+        }
+    }
+
+    /// When each retailer's latest successful crawl ended, ignoring crawls
+    /// skipped for already finishing in an interrupted run
+    pub(crate) async fn get_last_successful_end_times(
+        &self,
+    ) -> Result<HashMap<RetailerName, u64>, StorageError> {
+        let mut cursor = self
+            .collection
+            .aggregate([
+                doc! { "$unwind": "$retailers" },
+                doc! {
+                    "$match": {
+                        "retailers.succeeded": true,
+                        "retailers.skipped": { "$ne": true },
+                    }
+                },
+                doc! {
+                    "$group": {
+                        "_id": "$retailers.retailer",
+                        "end_time": { "$max": "$retailers.end_time" },
+                    }
+                },
+            ])
+            .with_type::<LastSuccessfulCrawl>()
+            .await?;
+
+        let mut end_times = HashMap::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(crawl) => {
+                    end_times.insert(crawl.retailer, crawl.end_time);
+                }
+                Err(err) => warn!("Skipping crawl that failed to deserialize: {err}"),
+            }
+        }
+
+        Ok(end_times)
+    }
+
+    /// Latest runs first, only runs that crawled `retailer` if it's set
+    pub(crate) async fn get_runs(
+        &self,
+        retailer: Option<RetailerName>,
+        limit: i64,
+    ) -> Result<Vec<CollectionCrawlRun>, StorageError> {
+        let filter = match retailer {
+            Some(retailer) => {
+                let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+                doc! { "retailers.retailer": retailer }
+            }
+            None => doc! {},
+        };
+
+        let mut cursor = self
+            .collection
+            .find(filter)
+            .sort(doc! { "start_time": -1 })
+            .limit(limit)
+            .await?;
+
+        let mut runs = Vec::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(run) => runs.push(run),
+                Err(err) => warn!("Skipping crawl run that failed to deserialize: {err}"),
+            }
+        }
+
+        Ok(runs)
+    }
+}
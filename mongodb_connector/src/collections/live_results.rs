@@ -1,17 +1,19 @@
 use common::{
-    result::base::CrawlResult,
+    result::{base::CrawlResult, enums::RetailerName},
+    retailer_info::CollectionRetailerStats,
     search_params::{ApiSearchInput, CollectionSearchResults},
 };
 use mongodb::{
     Client, Collection, Database, IndexModel,
-    bson::{doc, oid::ObjectId},
+    bson::{doc, oid::ObjectId, to_bson},
     options::IndexOptions,
 };
 use serde::Deserialize;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::{
     constants::{DATABASE_NAME, VIEW_LIVE_DATA_NAME, VIEW_LIVE_DATA_SEARCH_INDEX},
+    errors::StorageError,
     query_pipeline::traits::SearchPipeline,
 };
 
@@ -76,7 +78,7 @@ impl LiveResultsView {
     pub(crate) async fn search_items(
         &self,
         query_params: &ApiSearchInput,
-    ) -> CollectionSearchResults {
+    ) -> Result<CollectionSearchResults, StorageError> {
         let pipeline_documents = SearchPipeline::new(query_params.clone()).get_search_documents();
 
         debug!("Using: {:?}", pipeline_documents);
@@ -85,36 +87,104 @@ impl LiveResultsView {
             .collection
             .aggregate(pipeline_documents)
             .with_type::<PaginatedSearchOutput>()
-            .await
-            .unwrap();
+            .await?;
 
         let mut result = CollectionSearchResults::new();
 
-        while cursor.advance().await.unwrap_or(false) {
-            let paginated_result = cursor.deserialize_current().unwrap();
+        while cursor.advance().await? {
+            let paginated_result = cursor.deserialize_current()?;
 
             result.total_count += paginated_result.get_count();
             result.items.extend(paginated_result.items);
         }
 
-        result
+        Ok(result)
     }
 
-    pub(crate) async fn prune_results(&self, prev_days: i64) {
+    pub(crate) async fn prune_results(&self, prev_days: i64) -> Result<(), StorageError> {
         self.collection
             .delete_many(doc! {
                 "query_time": {"$lt": prev_days}
             })
-            .await
-            .unwrap();
+            .await?;
+
+        Ok(())
     }
 
-    pub(crate) async fn find_result(&self, object_id: ObjectId) -> Option<CrawlResult> {
-        self.collection
+    /// Live product counts per retailer, each product only counts once no matter how
+    /// many snapshots of it are live, the same product as the price history uses
+    pub(crate) async fn get_retailer_stats(
+        &self,
+    ) -> Result<Vec<CollectionRetailerStats>, StorageError> {
+        let mut cursor = self
+            .collection
+            .aggregate([
+                doc! {
+                    "$group": {
+                        "_id": {
+                            "retailer": "$retailer",
+                            "product": {
+                                "$ifNull": ["$product_id", { "url": "$url", "name": "$name" }]
+                            },
+                        },
+                    }
+                },
+                doc! {
+                    "$group": {
+                        "_id": "$_id.retailer",
+                        "live_product_count": { "$sum": 1 },
+                    }
+                },
+            ])
+            .with_type::<CollectionRetailerStats>()
+            .await?;
+
+        let mut stats = Vec::new();
+
+        while cursor.advance().await? {
+            let Ok(retailer_stats) = cursor.deserialize_current() else {
+                continue;
+            };
+
+            stats.push(retailer_stats);
+        }
+
+        Ok(stats)
+    }
+
+    pub(crate) async fn get_retailer_results(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError> {
+        let retailer = to_bson(&retailer).expect("RetailerName to serialize correctly");
+
+        let mut cursor = self
+            .collection
+            .find(doc! { "retailer": retailer, "query_time": {"$gte": since as i64} })
+            .await?;
+
+        let mut results: Vec<CrawlResult> = Vec::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(result) => results.push(result),
+                Err(err) => warn!("Skipping live result that failed to deserialize: {err}"),
+            }
+        }
+
+        Ok(results)
+    }
+
+    pub(crate) async fn find_result(
+        &self,
+        object_id: ObjectId,
+    ) -> Result<Option<CrawlResult>, StorageError> {
+        Ok(self
+            .collection
             .find_one(doc! {
                 "_id": object_id
             })
-            .await
-            .unwrap()
+            .await?)
     }
 }
@@ -1,7 +1,10 @@
 use common::messages::Message;
 use mongodb::{Client, Collection, Database};
 
-use crate::constants::{COLLECTION_MESSAGES_NAME, DATABASE_NAME};
+use crate::{
+    constants::{COLLECTION_MESSAGES_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
 
 pub(crate) struct MessagesCollection {
     collection: Collection<Message>,
@@ -26,7 +29,9 @@ impl MessagesCollection {
             });
     }
 
-    pub(crate) async fn insert_message(&self, message: Message) {
-        let _ = self.collection.insert_one(message).await.unwrap();
+    pub(crate) async fn insert_message(&self, message: Message) -> Result<(), StorageError> {
+        self.collection.insert_one(message).await?;
+
+        Ok(())
     }
 }
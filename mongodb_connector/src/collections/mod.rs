@@ -1,4 +1,10 @@
+pub(crate) mod canonical_products;
+pub(crate) mod crawl_baselines;
+pub(crate) mod crawl_checkpoints;
+pub(crate) mod crawl_diffs;
 pub(crate) mod crawl_results;
+pub(crate) mod crawl_runs;
 pub(crate) mod live_results;
 pub(crate) mod messages;
 pub(crate) mod price_history;
+pub(crate) mod run_locks;
@@ -7,10 +7,19 @@ use mongodb::{
     bson::{doc, to_bson},
     options::IndexOptions,
 };
+use tracing::warn;
 
-use crate::constants::{COLLECTION_PRICE_HISTORY_NAME, DATABASE_NAME};
+use crate::{
+    constants::{COLLECTION_PRICE_HISTORY_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
 
-const INDEX_NAME: &str = "search_index";
+// text index history used to be looked up with, it doesn't help equality lookups
+const LEGACY_INDEX_NAME: &str = "search_index";
+// non unique index product IDs used to be looked up with, replaced by the unique one
+const LEGACY_PRODUCT_ID_INDEX_NAME: &str = "product_id_index";
+const PRODUCT_ID_INDEX_NAME: &str = "product_id_unique_index";
+const NAME_URL_INDEX_NAME: &str = "name_url_index";
 
 pub(crate) struct PriceHistoryCollection {
     collection: Collection<CollectionPriceHistory>,
@@ -34,38 +43,63 @@ impl PriceHistoryCollection {
                 panic!("Creating {COLLECTION_PRICE_HISTORY_NAME} collection to not fail")
             });
 
-        let index = IndexModel::builder()
-            .keys(doc! {
-                "name": "text",
-                "url": "text"
-            })
-            .options(IndexOptions::builder().name(INDEX_NAME.to_string()).build())
+        let collection = db.collection::<CollectionPriceHistory>(COLLECTION_PRICE_HISTORY_NAME);
+
+        // already gone on anything set up after the switch to product IDs
+        let _ = collection.drop_index(LEGACY_INDEX_NAME).await;
+        let _ = collection.drop_index(LEGACY_PRODUCT_ID_INDEX_NAME).await;
+
+        // a product only ever has a single history, history written
+        // before products had IDs is left out until it's migrated
+        let product_id_index = IndexModel::builder()
+            .keys(doc! { "product_id": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name(PRODUCT_ID_INDEX_NAME.to_string())
+                    .unique(true)
+                    .partial_filter_expression(doc! { "product_id": { "$type": "string" } })
+                    .build(),
+            )
             .build();
 
-        db.collection::<CollectionPriceHistory>(COLLECTION_PRICE_HISTORY_NAME)
-            .create_index(index)
-            .await
-            .unwrap();
+        if let Err(err) = collection.create_index(product_id_index).await {
+            warn!(
+                "Failed to create unique {PRODUCT_ID_INDEX_NAME}, products may have more than one history: {err}"
+            );
+        }
+
+        let indexes = vec![
+            IndexModel::builder()
+                .keys(doc! { "name": 1, "url": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .name(NAME_URL_INDEX_NAME.to_string())
+                        .build(),
+                )
+                .build(),
+        ];
+
+        collection.create_indexes(indexes).await.unwrap();
     }
 
+    /// Looks the history up by the result's product ID, falling back to its
+    /// name and URL for results written before products had IDs
     pub(crate) async fn get_price_history(
         &self,
-        name: impl Into<String>,
-        url: impl Into<String>,
-    ) -> CollectionPriceHistory {
-        self.collection
-            .find_one(doc! {
-                "name": name.into(),
-                "url": url.into()
-            })
-            .await
-            .unwrap_or_else(|_| {
-                panic!("find_one call to not fail for {COLLECTION_PRICE_HISTORY_NAME}")
-            })
-            .expect("find_one call to actually find something")
+        result: &CrawlResult,
+    ) -> Result<Option<CollectionPriceHistory>, StorageError> {
+        let filter = match &result.product_id {
+            Some(product_id) => doc! { "product_id": product_id },
+            None => doc! { "name": result.name.clone(), "url": result.url.clone() },
+        };
+
+        Ok(self.collection.find_one(filter).await?)
     }
 
-    pub(crate) async fn update_collection(&self, results: Vec<&CrawlResult>) {
+    pub(crate) async fn update_collection(
+        &self,
+        results: Vec<&CrawlResult>,
+    ) -> Result<(), StorageError> {
         for result in results {
             let price_obj = PriceHistoryEntry {
                 regular_price: result.price.regular_price,
@@ -76,7 +110,26 @@ impl PriceHistoryCollection {
             let parsed_price =
                 to_bson(&price_obj).expect("PriceHistoryEntry to deserialize correctly");
 
-            let Ok(update_result) = self
+            // renames and slug changes carry over the history, the latest name and URL win
+            if let Some(product_id) = &result.product_id {
+                self.collection
+                    .update_one(
+                        doc! { "product_id": product_id },
+                        doc! {
+                            "$set": {
+                                "name": result.name.clone(),
+                                "url": result.url.clone(),
+                            },
+                            "$push": { "price_history": parsed_price },
+                        },
+                    )
+                    .upsert(true)
+                    .await?;
+
+                continue;
+            }
+
+            let update_result = self
                 .collection
                 .update_one(
                     doc! {
@@ -89,22 +142,97 @@ impl PriceHistoryCollection {
                         }
                     },
                 )
-                .await
-            else {
-                // TODO: this normally shouldn't fail, but handle this better
-                continue;
-            };
+                .await?;
 
             if update_result.matched_count == 0 {
-                let _ = self
-                    .collection
+                self.collection
                     .insert_one(CollectionPriceHistory {
+                        product_id: None,
                         name: result.name.clone(),
                         url: result.url.clone(),
                         price_history: vec![price_obj],
                     })
-                    .await;
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// History written before products had IDs
+    pub(crate) async fn get_unidentified_histories(
+        &self,
+    ) -> Result<Vec<CollectionPriceHistory>, StorageError> {
+        let mut cursor = self
+            .collection
+            .find(doc! { "product_id": { "$exists": false } })
+            .await?;
+
+        let mut histories: Vec<CollectionPriceHistory> = Vec::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(history) => histories.push(history),
+                Err(err) => warn!("Skipping price history that failed to deserialize: {err}"),
             }
         }
+
+        Ok(histories)
+    }
+
+    /// Gives the history a product ID, merging its entries into the product's
+    /// history if a crawl already started one, returns whether it was merged
+    pub(crate) async fn assign_product_id(
+        &self,
+        history: &CollectionPriceHistory,
+        product_id: &str,
+        url: &str,
+    ) -> Result<bool, StorageError> {
+        let filter = doc! {
+            "name": history.name.clone(),
+            "url": history.url.clone(),
+            "product_id": { "$exists": false },
+        };
+
+        let existing = self
+            .collection
+            .find_one(doc! { "product_id": product_id })
+            .await?;
+
+        if existing.is_none() {
+            self.collection
+                .update_one(
+                    filter,
+                    doc! { "$set": { "product_id": product_id, "url": url } },
+                )
+                .await?;
+
+            return Ok(false);
+        }
+
+        let entries =
+            to_bson(&history.price_history).expect("PriceHistoryEntry to serialize correctly");
+
+        // failing leaves the old history in place, so nothing is lost and it can be retried
+        let update_result = self
+            .collection
+            .update_one(
+                doc! { "product_id": product_id },
+                doc! {
+                    "$push": {
+                        "price_history": {
+                            "$each": entries,
+                            "$sort": { "query_time": 1 },
+                        }
+                    }
+                },
+            )
+            .await?;
+
+        if update_result.matched_count > 0 {
+            self.collection.delete_one(filter).await?;
+        }
+
+        Ok(true)
     }
 }
@@ -0,0 +1,117 @@
+use common::{run_lock::CollectionRunLock, utils::get_current_time};
+use mongodb::{
+    Client, Collection, Database,
+    bson::doc,
+    error::{ErrorKind, WriteFailure},
+};
+
+use crate::{
+    constants::{COLLECTION_RUN_LOCKS_NAME, DATABASE_NAME},
+    errors::StorageError,
+};
+
+const DUPLICATE_KEY_ERROR: i32 = 11000;
+
+pub(crate) struct RunLocksCollection {
+    collection: Collection<CollectionRunLock>,
+}
+
+impl RunLocksCollection {
+    pub(crate) async fn new(client: Client) -> Self {
+        let db = client.database(DATABASE_NAME);
+
+        Self::create_collection(&db).await;
+
+        Self {
+            collection: db.collection::<CollectionRunLock>(COLLECTION_RUN_LOCKS_NAME),
+        }
+    }
+
+    async fn create_collection(db: &Database) {
+        db.create_collection(COLLECTION_RUN_LOCKS_NAME)
+            .await
+            .unwrap_or_else(|_| {
+                panic!("Creating {COLLECTION_RUN_LOCKS_NAME} collection to not fail")
+            });
+    }
+
+    /// Takes the lock if nobody holds it, its lease expired, or `owner` already holds it.
+    /// The lock's name is its `_id`, so two owners racing to insert it can't both win
+    pub(crate) async fn try_acquire(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError> {
+        let now = get_current_time();
+
+        let result = self
+            .collection
+            .update_one(
+                doc! {
+                    "_id": name,
+                    "$or": [
+                        { "expires_at": { "$lte": now as i64 } },
+                        { "owner": owner },
+                    ],
+                },
+                doc! {
+                    "$set": {
+                        "owner": owner,
+                        "acquired_at": now as i64,
+                        "expires_at": (now + ttl_secs) as i64,
+                    },
+                },
+            )
+            .upsert(true)
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            // the lock exists, but the filter didn't match it, so someone else holds it
+            Err(err) => match *err.kind {
+                ErrorKind::Write(WriteFailure::WriteError(ref write_error))
+                    if write_error.code == DUPLICATE_KEY_ERROR =>
+                {
+                    Ok(false)
+                }
+                _ => Err(err.into()),
+            },
+        }
+    }
+
+    /// Extends the lease, a failed update doesn't mean `owner` no longer holds the lock
+    pub(crate) async fn renew(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError> {
+        let expires_at = get_current_time() + ttl_secs;
+
+        let result = self
+            .collection
+            .update_one(
+                doc! { "_id": name, "owner": owner },
+                doc! { "$set": { "expires_at": expires_at as i64 } },
+            )
+            .await?;
+
+        Ok(result.matched_count == 1)
+    }
+
+    pub(crate) async fn release(&self, name: &str, owner: &str) -> Result<(), StorageError> {
+        self.collection
+            .delete_one(doc! { "_id": name, "owner": owner })
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn get_lock(
+        &self,
+        name: &str,
+    ) -> Result<Option<CollectionRunLock>, StorageError> {
+        Ok(self.collection.find_one(doc! { "_id": name }).await?)
+    }
+}
@@ -1,9 +1,20 @@
 use std::{env, str::FromStr, sync::LazyLock};
 
+use async_trait::async_trait;
 use common::{
+    canonical_product::{CanonicalLink, CollectionCanonicalProduct},
+    crawl_baseline::CollectionCrawlBaseline,
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    crawl_diff::CollectionCrawlDiff,
+    crawl_run::{CollectionCrawlRun, CrawlRunRetailer},
     messages::Message,
     price_history::{ApiPriceHistoryInput, CollectionPriceHistory},
-    result::base::CrawlResult,
+    result::{
+        base::CrawlResult,
+        enums::{Category, RetailerName},
+    },
+    retailer_info::CollectionRetailerStats,
+    run_lock::CollectionRunLock,
     search_params::{ApiSearchInput, CollectionSearchResults},
     utils::normalized_relative_days,
 };
@@ -11,9 +22,13 @@ use mongodb::{Client, bson::oid::ObjectId};
 use tracing::warn;
 
 use crate::collections::{
-    crawl_results::CrawlResultsCollection, live_results::LiveResultsView,
-    messages::MessagesCollection, price_history::PriceHistoryCollection,
+    canonical_products::CanonicalProductsCollection, crawl_baselines::CrawlBaselinesCollection,
+    crawl_checkpoints::CrawlCheckpointsCollection, crawl_diffs::CrawlDiffsCollection,
+    crawl_results::CrawlResultsCollection, crawl_runs::CrawlRunsCollection,
+    live_results::LiveResultsView, messages::MessagesCollection,
+    price_history::PriceHistoryCollection, run_locks::RunLocksCollection,
 };
+use crate::{errors::StorageError, storage::Storage};
 
 static CONNECTION_URI: LazyLock<String> = LazyLock::new(|| {
     let host = env::var("MONGO_DB_HOST").unwrap_or("localhost".into());
@@ -23,10 +38,16 @@ static CONNECTION_URI: LazyLock<String> = LazyLock::new(|| {
 });
 
 pub struct MongoDBConnector {
+    canonical_products: CanonicalProductsCollection,
+    crawl_baselines: CrawlBaselinesCollection,
+    crawl_checkpoints: CrawlCheckpointsCollection,
+    crawl_diffs: CrawlDiffsCollection,
     crawl_results: CrawlResultsCollection,
+    crawl_runs: CrawlRunsCollection,
     live_results: LiveResultsView,
     messages: MessagesCollection,
     price_history: PriceHistoryCollection,
+    run_locks: RunLocksCollection,
 }
 
 impl MongoDBConnector {
@@ -36,51 +57,294 @@ impl MongoDBConnector {
             .unwrap();
 
         Self {
+            canonical_products: CanonicalProductsCollection::new(client.clone()).await,
+            crawl_baselines: CrawlBaselinesCollection::new(client.clone()).await,
+            crawl_checkpoints: CrawlCheckpointsCollection::new(client.clone()).await,
+            crawl_diffs: CrawlDiffsCollection::new(client.clone()).await,
             crawl_results: CrawlResultsCollection::new(client.clone()).await,
+            crawl_runs: CrawlRunsCollection::new(client.clone()).await,
             live_results: LiveResultsView::new(client.clone()).await,
             messages: MessagesCollection::new(client.clone()).await,
-            price_history: PriceHistoryCollection::new(client).await,
+            price_history: PriceHistoryCollection::new(client.clone()).await,
+            run_locks: RunLocksCollection::new(client).await,
         }
     }
+}
 
-    pub async fn insert_message(&self, message: Message) {
-        self.messages.insert_message(message).await;
+#[async_trait]
+impl Storage for MongoDBConnector {
+    async fn insert_message(&self, message: Message) -> Result<(), StorageError> {
+        self.messages.insert_message(message).await
     }
 
-    pub async fn search_items(&self, query_params: &ApiSearchInput) -> CollectionSearchResults {
+    async fn search_items(
+        &self,
+        query_params: &ApiSearchInput,
+    ) -> Result<CollectionSearchResults, StorageError> {
         self.live_results.search_items(query_params).await
     }
 
-    pub async fn insert_many_results(&self, results: Vec<&CrawlResult>) {
-        self.crawl_results.insert_results(results.clone()).await;
+    async fn insert_results(&self, results: Vec<&CrawlResult>) -> Result<(), StorageError> {
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        self.crawl_results.insert_results(results).await
+    }
+
+    async fn replace_result(&self, result: &CrawlResult, since: u64) -> Result<(), StorageError> {
+        self.crawl_results.replace_result(result, since).await
+    }
 
+    async fn finalize_results(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<(), StorageError> {
         let prev_days = normalized_relative_days(3);
 
-        self.live_results.prune_results(prev_days).await;
-        self.crawl_results.update_view(prev_days).await;
-        self.price_history.update_collection(results).await;
+        self.live_results.prune_results(prev_days).await?;
+        self.crawl_results
+            .update_view(prev_days, retailer, since)
+            .await?;
+
+        let results = self
+            .crawl_results
+            .get_results_since(retailer, since)
+            .await?;
+        self.price_history
+            .update_collection(results.iter().collect())
+            .await
+    }
+
+    async fn get_results_since(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError> {
+        self.crawl_results.get_results_since(retailer, since).await
+    }
+
+    async fn get_live_results(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError> {
+        self.live_results
+            .get_retailer_results(retailer, since)
+            .await
+    }
+
+    async fn get_crawl_checkpoint(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlCheckpoint>, StorageError> {
+        self.crawl_checkpoints.get_checkpoint(retailer).await
     }
 
-    pub async fn get_pricing_history(
+    async fn save_crawl_checkpoint(
+        &self,
+        checkpoint: &CollectionCrawlCheckpoint,
+    ) -> Result<(), StorageError> {
+        self.crawl_checkpoints.save_checkpoint(checkpoint).await
+    }
+
+    async fn start_crawl_run(&self, run: &CollectionCrawlRun) -> Result<(), StorageError> {
+        self.crawl_runs.insert_run(run).await
+    }
+
+    async fn record_crawl_run_retailer(
+        &self,
+        run_id: ObjectId,
+        retailer: &CrawlRunRetailer,
+    ) -> Result<(), StorageError> {
+        self.crawl_runs.push_retailer(run_id, retailer).await
+    }
+
+    async fn finish_crawl_run(&self, run_id: ObjectId, end_time: u64) -> Result<(), StorageError> {
+        self.crawl_runs.finish_run(run_id, end_time).await
+    }
+
+    async fn get_crawl_runs(
+        &self,
+        retailer: Option<RetailerName>,
+        limit: i64,
+    ) -> Result<Vec<CollectionCrawlRun>, StorageError> {
+        self.crawl_runs.get_runs(retailer, limit).await
+    }
+
+    async fn get_last_successful_crawl(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CrawlRunRetailer>, StorageError> {
+        self.crawl_runs.get_last_successful_retailer(retailer).await
+    }
+
+    async fn insert_crawl_diff(&self, diff: &CollectionCrawlDiff) -> Result<(), StorageError> {
+        self.crawl_diffs.insert_diff(diff).await
+    }
+
+    async fn get_crawl_diffs(
+        &self,
+        run_id: ObjectId,
+    ) -> Result<Vec<CollectionCrawlDiff>, StorageError> {
+        self.crawl_diffs.get_run_diffs(run_id).await
+    }
+
+    async fn get_latest_crawl_diff(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlDiff>, StorageError> {
+        self.crawl_diffs.get_latest_diff(retailer).await
+    }
+
+    async fn replace_canonical_links(
+        &self,
+        retailer: RetailerName,
+        links: &[CanonicalLink],
+    ) -> Result<(), StorageError> {
+        self.canonical_products.unlink_retailer(retailer).await?;
+
+        for link in links {
+            self.canonical_products.link_listing(link).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_canonical_products(
+        &self,
+        urls: Vec<String>,
+    ) -> Result<Vec<CollectionCanonicalProduct>, StorageError> {
+        if urls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.canonical_products.get_products_by_url(urls).await
+    }
+
+    async fn acquire_run_lock(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError> {
+        self.run_locks.try_acquire(name, owner, ttl_secs).await
+    }
+
+    async fn renew_run_lock(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError> {
+        self.run_locks.renew(name, owner, ttl_secs).await
+    }
+
+    async fn release_run_lock(&self, name: &str, owner: &str) -> Result<(), StorageError> {
+        self.run_locks.release(name, owner).await
+    }
+
+    async fn get_run_lock(&self, name: &str) -> Result<Option<CollectionRunLock>, StorageError> {
+        self.run_locks.get_lock(name).await
+    }
+
+    async fn get_retailer_stats(&self) -> Result<Vec<CollectionRetailerStats>, StorageError> {
+        let mut stats = self.live_results.get_retailer_stats().await?;
+        let mut end_times = self.crawl_runs.get_last_successful_end_times().await?;
+
+        for retailer_stats in &mut stats {
+            retailer_stats.last_crawled = end_times.remove(&retailer_stats.retailer);
+        }
+
+        // crawled successfully, but nothing of it is live anymore
+        stats.extend(
+            end_times
+                .into_iter()
+                .map(|(retailer, end_time)| CollectionRetailerStats {
+                    retailer,
+                    live_product_count: 0,
+                    last_crawled: Some(end_time),
+                }),
+        );
+
+        Ok(stats)
+    }
+
+    async fn get_crawl_baselines(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Vec<CollectionCrawlBaseline>, StorageError> {
+        self.crawl_baselines.get_baselines(retailer).await
+    }
+
+    async fn update_crawl_baseline(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        count: u64,
+        window: u64,
+    ) -> Result<(), StorageError> {
+        self.crawl_baselines
+            .push_count(retailer, category, count, window)
+            .await
+    }
+
+    async fn set_crawl_baseline_skipped(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        skipped: u64,
+    ) -> Result<(), StorageError> {
+        self.crawl_baselines
+            .set_skipped(retailer, category, skipped)
+            .await
+    }
+
+    async fn get_pricing_history(
         &self,
         query: ApiPriceHistoryInput,
-    ) -> Option<CollectionPriceHistory> {
-        let Some(result) = self.live_results.find_result(query.id).await else {
+    ) -> Result<Option<CollectionPriceHistory>, StorageError> {
+        let Some(result) = self.live_results.find_result(query.id).await? else {
             warn!("Invalid ID, no results found for {}", query.id.to_string());
 
-            return None;
+            return Ok(None);
         };
 
-        Some(
-            self.price_history
-                .get_price_history(result.name, result.url)
-                .await,
-        )
+        self.price_history.get_price_history(&result).await
+    }
+
+    async fn get_unidentified_price_histories(
+        &self,
+    ) -> Result<Vec<CollectionPriceHistory>, StorageError> {
+        self.price_history.get_unidentified_histories().await
+    }
+
+    async fn assign_price_history_product_id(
+        &self,
+        history: &CollectionPriceHistory,
+        product_id: &str,
+        url: &str,
+    ) -> Result<bool, StorageError> {
+        self.price_history
+            .assign_product_id(history, product_id, url)
+            .await
+    }
+
+    async fn get_product_ids_at_url(
+        &self,
+        retailer: RetailerName,
+        url: &str,
+        name: &str,
+    ) -> Result<Vec<String>, StorageError> {
+        self.crawl_results
+            .get_product_ids_at_url(retailer, url, name)
+            .await
     }
 
-    pub async fn find_result(&self, object_id: String) -> Option<CrawlResult> {
+    async fn find_result(&self, object_id: String) -> Result<Option<CrawlResult>, StorageError> {
         let Ok(id) = ObjectId::from_str(&object_id) else {
-            return None;
+            return Ok(None);
         };
 
         self.live_results.find_result(id).await
@@ -8,3 +8,15 @@ pub(crate) const VIEW_LIVE_DATA_SEARCH_INDEX: &str = "name_text";
 pub(crate) const COLLECTION_MESSAGES_NAME: &str = "messages";
 
 pub(crate) const COLLECTION_PRICE_HISTORY_NAME: &str = "price-history";
+
+pub(crate) const COLLECTION_CRAWL_BASELINES_NAME: &str = "crawl-baselines";
+
+pub(crate) const COLLECTION_CRAWL_CHECKPOINTS_NAME: &str = "crawl-checkpoints";
+
+pub(crate) const COLLECTION_CRAWL_RUNS_NAME: &str = "crawl-runs";
+
+pub(crate) const COLLECTION_CRAWL_DIFFS_NAME: &str = "crawl-diffs";
+
+pub(crate) const COLLECTION_RUN_LOCKS_NAME: &str = "run-locks";
+
+pub(crate) const COLLECTION_CANONICAL_PRODUCTS_NAME: &str = "canonical-products";
@@ -2,3 +2,9 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum QueryOptionError {}
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("MongoDB call failed: {0}")]
+    MongoDB(#[from] mongodb::error::Error),
+}
@@ -2,4 +2,6 @@ pub(crate) mod collections;
 pub mod connector;
 pub(crate) mod constants;
 pub mod errors;
+pub mod memory;
 pub mod query_pipeline;
+pub mod storage;
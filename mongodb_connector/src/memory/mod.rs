@@ -0,0 +1,798 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use async_trait::async_trait;
+use common::{
+    canonical_product::{CanonicalLink, CollectionCanonicalProduct},
+    crawl_baseline::CollectionCrawlBaseline,
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    crawl_diff::CollectionCrawlDiff,
+    crawl_run::{CollectionCrawlRun, CrawlRunRetailer},
+    messages::Message,
+    price_history::{ApiPriceHistoryInput, CollectionPriceHistory, PriceHistoryEntry},
+    result::{
+        base::CrawlResult,
+        enums::{Category, RetailerName},
+    },
+    retailer_info::CollectionRetailerStats,
+    run_lock::CollectionRunLock,
+    search_params::{ApiSearchInput, CollectionSearchResults},
+    utils::{get_current_time, normalized_relative_days},
+};
+use mongodb::bson::oid::ObjectId;
+use tracing::warn;
+
+use crate::{errors::StorageError, storage::Storage};
+
+mod search;
+
+/// Every collection the MongoDB connector writes to, kept as plain vectors
+#[derive(Default)]
+struct MemoryState {
+    canonical_products: Vec<CollectionCanonicalProduct>,
+    crawl_baselines: Vec<CollectionCrawlBaseline>,
+    crawl_checkpoints: HashMap<RetailerName, CollectionCrawlCheckpoint>,
+    crawl_diffs: Vec<CollectionCrawlDiff>,
+    crawl_results: Vec<CrawlResult>,
+    crawl_runs: Vec<CollectionCrawlRun>,
+    live_results: Vec<CrawlResult>,
+    messages: Vec<Message>,
+    price_history: Vec<CollectionPriceHistory>,
+    run_locks: HashMap<String, CollectionRunLock>,
+}
+
+/// Storage that lives and dies with the process, for running the API and
+/// indexer without MongoDB. Behaves like `MongoDBConnector`, search included
+#[derive(Default)]
+pub struct InMemoryStorage {
+    state: Mutex<MemoryState>,
+}
+
+impl InMemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // nothing panics while holding the lock in a way that leaves the state half written
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl Storage for InMemoryStorage {
+    async fn insert_message(&self, message: Message) -> Result<(), StorageError> {
+        self.state().messages.push(message);
+
+        Ok(())
+    }
+
+    async fn search_items(
+        &self,
+        query_params: &ApiSearchInput,
+    ) -> Result<CollectionSearchResults, StorageError> {
+        let state = self.state();
+
+        Ok(search::search_items(
+            &state.live_results,
+            &state.canonical_products,
+            query_params,
+        ))
+    }
+
+    async fn insert_results(&self, results: Vec<&CrawlResult>) -> Result<(), StorageError> {
+        let mut state = self.state();
+
+        for result in results {
+            let mut result = result.clone();
+
+            // MongoDB would assign the ID on insert
+            if result.id.is_none() {
+                result.id = Some(ObjectId::new().to_hex());
+            }
+
+            state.crawl_results.push(result);
+        }
+
+        Ok(())
+    }
+
+    async fn replace_result(&self, result: &CrawlResult, since: u64) -> Result<(), StorageError> {
+        let mut state = self.state();
+
+        let existing = state.crawl_results.iter_mut().find(|existing| {
+            existing.name == result.name
+                && existing.url == result.url
+                && existing.retailer == result.retailer
+                && existing.query_time >= since
+        });
+
+        if let Some(existing) = existing {
+            let id = existing.id.take();
+
+            *existing = result.clone();
+            existing.id = id;
+        }
+
+        Ok(())
+    }
+
+    async fn finalize_results(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<(), StorageError> {
+        let prev_days = normalized_relative_days(3);
+
+        let mut state = self.state();
+        let state = &mut *state;
+
+        state
+            .live_results
+            .retain(|result| result.query_time as i64 >= prev_days);
+
+        // results already in the live view are kept as they are, like the $merge
+        for result in &state.crawl_results {
+            if result.retailer != retailer
+                || (result.query_time as i64) < prev_days
+                || result.query_time < since
+                || state.live_results.iter().any(|live| live.id == result.id)
+            {
+                continue;
+            }
+
+            state.live_results.push(result.clone());
+        }
+
+        let results: Vec<&CrawlResult> = state
+            .crawl_results
+            .iter()
+            .filter(|result| result.retailer == retailer && result.query_time >= since)
+            .collect();
+
+        update_price_history(&mut state.price_history, results);
+
+        Ok(())
+    }
+
+    async fn get_results_since(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError> {
+        Ok(get_retailer_results(
+            &self.state().crawl_results,
+            retailer,
+            since,
+        ))
+    }
+
+    async fn get_live_results(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError> {
+        Ok(get_retailer_results(
+            &self.state().live_results,
+            retailer,
+            since,
+        ))
+    }
+
+    async fn get_crawl_checkpoint(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlCheckpoint>, StorageError> {
+        Ok(self.state().crawl_checkpoints.get(&retailer).cloned())
+    }
+
+    async fn save_crawl_checkpoint(
+        &self,
+        checkpoint: &CollectionCrawlCheckpoint,
+    ) -> Result<(), StorageError> {
+        self.state()
+            .crawl_checkpoints
+            .insert(checkpoint.retailer, checkpoint.clone());
+
+        Ok(())
+    }
+
+    async fn start_crawl_run(&self, run: &CollectionCrawlRun) -> Result<(), StorageError> {
+        self.state().crawl_runs.push(run.clone());
+
+        Ok(())
+    }
+
+    async fn record_crawl_run_retailer(
+        &self,
+        run_id: ObjectId,
+        retailer: &CrawlRunRetailer,
+    ) -> Result<(), StorageError> {
+        if let Some(run) = self
+            .state()
+            .crawl_runs
+            .iter_mut()
+            .find(|run| run.id == run_id)
+        {
+            run.retailers.push(retailer.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn finish_crawl_run(&self, run_id: ObjectId, end_time: u64) -> Result<(), StorageError> {
+        if let Some(run) = self
+            .state()
+            .crawl_runs
+            .iter_mut()
+            .find(|run| run.id == run_id)
+        {
+            run.end_time = Some(end_time);
+        }
+
+        Ok(())
+    }
+
+    async fn get_crawl_runs(
+        &self,
+        retailer: Option<RetailerName>,
+        limit: i64,
+    ) -> Result<Vec<CollectionCrawlRun>, StorageError> {
+        let mut runs: Vec<CollectionCrawlRun> = self
+            .state()
+            .crawl_runs
+            .iter()
+            .filter(|run| {
+                retailer.is_none_or(|retailer| {
+                    run.retailers
+                        .iter()
+                        .any(|crawled| crawled.retailer == retailer)
+                })
+            })
+            .cloned()
+            .collect();
+
+        runs.sort_by_key(|run| std::cmp::Reverse(run.start_time));
+
+        // a limit of 0 is no limit to MongoDB
+        if limit != 0 {
+            runs.truncate(limit.unsigned_abs() as usize);
+        }
+
+        Ok(runs)
+    }
+
+    async fn get_last_successful_crawl(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CrawlRunRetailer>, StorageError> {
+        Ok(self
+            .state()
+            .crawl_runs
+            .iter()
+            .flat_map(|run| &run.retailers)
+            .filter(|crawled| crawled.retailer == retailer && crawled.succeeded && !crawled.skipped)
+            .max_by_key(|crawled| crawled.start_time)
+            .cloned())
+    }
+
+    async fn insert_crawl_diff(&self, diff: &CollectionCrawlDiff) -> Result<(), StorageError> {
+        self.state().crawl_diffs.push(diff.clone());
+
+        Ok(())
+    }
+
+    async fn get_crawl_diffs(
+        &self,
+        run_id: ObjectId,
+    ) -> Result<Vec<CollectionCrawlDiff>, StorageError> {
+        Ok(self
+            .state()
+            .crawl_diffs
+            .iter()
+            .filter(|diff| diff.run_id == run_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_latest_crawl_diff(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlDiff>, StorageError> {
+        Ok(self
+            .state()
+            .crawl_diffs
+            .iter()
+            .filter(|diff| diff.retailer == retailer)
+            .max_by_key(|diff| diff.crawl_start)
+            .cloned())
+    }
+
+    async fn replace_canonical_links(
+        &self,
+        retailer: RetailerName,
+        links: &[CanonicalLink],
+    ) -> Result<(), StorageError> {
+        let mut state = self.state();
+
+        for product in &mut state.canonical_products {
+            product
+                .listings
+                .retain(|listing| listing.retailer != retailer);
+        }
+
+        state
+            .canonical_products
+            .retain(|product| !product.listings.is_empty());
+
+        for link in links {
+            let updated_at = get_current_time();
+
+            match state
+                .canonical_products
+                .iter_mut()
+                .find(|product| product.key == link.key)
+            {
+                Some(product) => {
+                    product.listings.push(link.listing.clone());
+                    product.updated_at = updated_at;
+                }
+                None => state.canonical_products.push(CollectionCanonicalProduct {
+                    key: link.key.clone(),
+                    category: link.category,
+                    name: link.listing.name.clone(),
+                    attributes: link.attributes.clone(),
+                    listings: vec![link.listing.clone()],
+                    updated_at,
+                }),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_canonical_products(
+        &self,
+        urls: Vec<String>,
+    ) -> Result<Vec<CollectionCanonicalProduct>, StorageError> {
+        Ok(self
+            .state()
+            .canonical_products
+            .iter()
+            .filter(|product| {
+                product
+                    .listings
+                    .iter()
+                    .any(|listing| urls.contains(&listing.url))
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn acquire_run_lock(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError> {
+        let now = get_current_time();
+        let mut state = self.state();
+
+        if let Some(lock) = state.run_locks.get(name)
+            && lock.expires_at > now
+            && lock.owner != owner
+        {
+            return Ok(false);
+        }
+
+        state.run_locks.insert(
+            name.to_string(),
+            CollectionRunLock {
+                name: name.to_string(),
+                owner: owner.to_string(),
+                acquired_at: now,
+                expires_at: now + ttl_secs,
+            },
+        );
+
+        Ok(true)
+    }
+
+    async fn renew_run_lock(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError> {
+        let renewed = match self.state().run_locks.get_mut(name) {
+            Some(lock) if lock.owner == owner => {
+                lock.expires_at = get_current_time() + ttl_secs;
+                true
+            }
+            _ => false,
+        };
+
+        Ok(renewed)
+    }
+
+    async fn release_run_lock(&self, name: &str, owner: &str) -> Result<(), StorageError> {
+        let mut state = self.state();
+
+        if state
+            .run_locks
+            .get(name)
+            .is_some_and(|lock| lock.owner == owner)
+        {
+            state.run_locks.remove(name);
+        }
+
+        Ok(())
+    }
+
+    async fn get_run_lock(&self, name: &str) -> Result<Option<CollectionRunLock>, StorageError> {
+        Ok(self.state().run_locks.get(name).cloned())
+    }
+
+    async fn get_retailer_stats(&self) -> Result<Vec<CollectionRetailerStats>, StorageError> {
+        let state = self.state();
+        let mut stats: BTreeMap<RetailerName, CollectionRetailerStats> = BTreeMap::new();
+        let mut seen: HashSet<(RetailerName, String)> = HashSet::new();
+
+        let new_stats = |retailer| CollectionRetailerStats {
+            retailer,
+            live_product_count: 0,
+            last_crawled: None,
+        };
+
+        // each product only counts once, however many snapshots of it are live
+        for result in &state.live_results {
+            let product = match &result.product_id {
+                Some(product_id) => product_id.clone(),
+                None => format!("{}:{}", result.url, result.name),
+            };
+
+            if seen.insert((result.retailer, product)) {
+                stats
+                    .entry(result.retailer)
+                    .or_insert_with(|| new_stats(result.retailer))
+                    .live_product_count += 1;
+            }
+        }
+
+        let successful_crawls = state
+            .crawl_runs
+            .iter()
+            .flat_map(|run| &run.retailers)
+            .filter(|crawled| crawled.succeeded && !crawled.skipped);
+
+        for crawled in successful_crawls {
+            let retailer_stats = stats
+                .entry(crawled.retailer)
+                .or_insert_with(|| new_stats(crawled.retailer));
+
+            retailer_stats.last_crawled = retailer_stats.last_crawled.max(Some(crawled.end_time));
+        }
+
+        Ok(stats.into_values().collect())
+    }
+
+    async fn get_crawl_baselines(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Vec<CollectionCrawlBaseline>, StorageError> {
+        Ok(self
+            .state()
+            .crawl_baselines
+            .iter()
+            .filter(|baseline| baseline.retailer == retailer)
+            .cloned()
+            .collect())
+    }
+
+    async fn update_crawl_baseline(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        count: u64,
+        window: u64,
+    ) -> Result<(), StorageError> {
+        let mut state = self.state();
+        let counts = &mut get_baseline(&mut state.crawl_baselines, retailer, category).counts;
+
+        counts.push(count);
+
+        let excess = counts.len().saturating_sub(window as usize);
+        counts.drain(..excess);
+
+        Ok(())
+    }
+
+    async fn set_crawl_baseline_skipped(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        skipped: u64,
+    ) -> Result<(), StorageError> {
+        let mut state = self.state();
+
+        get_baseline(&mut state.crawl_baselines, retailer, category).skipped = skipped;
+
+        Ok(())
+    }
+
+    async fn get_pricing_history(
+        &self,
+        query: ApiPriceHistoryInput,
+    ) -> Result<Option<CollectionPriceHistory>, StorageError> {
+        let state = self.state();
+        let id = query.id.to_hex();
+
+        let Some(result) = state
+            .live_results
+            .iter()
+            .find(|result| result.id.as_ref() == Some(&id))
+        else {
+            warn!("Invalid ID, no results found for {id}");
+
+            return Ok(None);
+        };
+
+        Ok(state
+            .price_history
+            .iter()
+            .find(|history| is_result_history(history, result))
+            .cloned())
+    }
+
+    async fn get_unidentified_price_histories(
+        &self,
+    ) -> Result<Vec<CollectionPriceHistory>, StorageError> {
+        Ok(self
+            .state()
+            .price_history
+            .iter()
+            .filter(|history| history.product_id.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn assign_price_history_product_id(
+        &self,
+        history: &CollectionPriceHistory,
+        product_id: &str,
+        url: &str,
+    ) -> Result<bool, StorageError> {
+        let mut state = self.state();
+
+        let Some(index) = state.price_history.iter().position(|existing| {
+            existing.product_id.is_none()
+                && existing.name == history.name
+                && existing.url == history.url
+        }) else {
+            return Ok(false);
+        };
+
+        let merged = match state
+            .price_history
+            .iter_mut()
+            .find(|existing| existing.product_id.as_deref() == Some(product_id))
+        {
+            Some(existing) => {
+                existing
+                    .price_history
+                    .extend(history.price_history.iter().cloned());
+                existing.price_history.sort_by_key(|entry| entry.query_time);
+
+                state.price_history.remove(index);
+
+                true
+            }
+            None => {
+                let unidentified = &mut state.price_history[index];
+
+                unidentified.product_id = Some(product_id.to_string());
+                unidentified.url = url.to_string();
+
+                false
+            }
+        };
+
+        Ok(merged)
+    }
+
+    async fn get_product_ids_at_url(
+        &self,
+        retailer: RetailerName,
+        url: &str,
+        name: &str,
+    ) -> Result<Vec<String>, StorageError> {
+        let state = self.state();
+
+        let at_url: Vec<&CrawlResult> = state
+            .crawl_results
+            .iter()
+            .filter(|result| {
+                result.retailer == retailer && result.url == url && result.product_id.is_some()
+            })
+            .collect();
+
+        let named: Vec<&CrawlResult> = at_url
+            .iter()
+            .copied()
+            .filter(|result| result.name == name)
+            .collect();
+
+        let results = match named.is_empty() {
+            true => at_url,
+            false => named,
+        };
+
+        let mut product_ids: Vec<String> = results
+            .into_iter()
+            .filter_map(|result| result.product_id.clone())
+            .collect();
+
+        product_ids.sort();
+        product_ids.dedup();
+
+        Ok(product_ids)
+    }
+
+    async fn find_result(&self, object_id: String) -> Result<Option<CrawlResult>, StorageError> {
+        Ok(self
+            .state()
+            .live_results
+            .iter()
+            .find(|result| result.id.as_ref() == Some(&object_id))
+            .cloned())
+    }
+}
+
+fn get_retailer_results(
+    results: &[CrawlResult],
+    retailer: RetailerName,
+    since: u64,
+) -> Vec<CrawlResult> {
+    results
+        .iter()
+        .filter(|result| result.retailer == retailer && result.query_time >= since)
+        .cloned()
+        .collect()
+}
+
+/// The retailer's baseline for the category, starting an empty one if there isn't one yet
+fn get_baseline(
+    baselines: &mut Vec<CollectionCrawlBaseline>,
+    retailer: RetailerName,
+    category: Category,
+) -> &mut CollectionCrawlBaseline {
+    let index = match baselines
+        .iter()
+        .position(|baseline| baseline.retailer == retailer && baseline.category == category)
+    {
+        Some(index) => index,
+        None => {
+            baselines.push(CollectionCrawlBaseline {
+                retailer,
+                category,
+                counts: Vec::new(),
+                skipped: 0,
+            });
+
+            baselines.len() - 1
+        }
+    };
+
+    &mut baselines[index]
+}
+
+/// Same lookup as the price-history collection, by product ID if the
+/// result has one, otherwise by name and URL
+fn is_result_history(history: &CollectionPriceHistory, result: &CrawlResult) -> bool {
+    match &result.product_id {
+        Some(product_id) => history.product_id.as_ref() == Some(product_id),
+        None => history.name == result.name && history.url == result.url,
+    }
+}
+
+fn update_price_history(histories: &mut Vec<CollectionPriceHistory>, results: Vec<&CrawlResult>) {
+    for result in results {
+        let entry = PriceHistoryEntry {
+            regular_price: result.price.regular_price,
+            sale_price: result.price.sale_price,
+            query_time: result.query_time,
+        };
+
+        match histories
+            .iter_mut()
+            .find(|history| is_result_history(history, result))
+        {
+            Some(history) => {
+                // renames and slug changes carry over the history, the latest name and URL win
+                if result.product_id.is_some() {
+                    history.name = result.name.clone();
+                    history.url = result.url.clone();
+                }
+
+                history.price_history.push(entry);
+            }
+            None => histories.push(CollectionPriceHistory {
+                product_id: result.product_id.clone(),
+                name: result.name.clone(),
+                url: result.url.clone(),
+                price_history: vec![entry],
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        money::Money,
+        result::base::{CrawlResult, Price},
+    };
+
+    use super::*;
+
+    fn crawl_result(name: &str, query_time: u64) -> CrawlResult {
+        let mut result = CrawlResult::new(
+            name.into(),
+            format!("https://example.com/{name}"),
+            Price::new(Money::cad(10_000)),
+            RetailerName::AlFlahertys,
+            Category::Firearm,
+        );
+        result.query_time = query_time;
+
+        result
+    }
+
+    #[tokio::test]
+    async fn only_publishes_finalized_crawl() {
+        let db = InMemoryStorage::new();
+        let failed_start = get_current_time() - 60;
+        let succeeded_start = get_current_time();
+
+        // a crawl that failed part way through, and never got finalized
+        let failed = crawl_result("partial", failed_start);
+        db.insert_results(vec![&failed]).await.unwrap();
+
+        let succeeded = crawl_result("complete", succeeded_start);
+        db.insert_results(vec![&succeeded]).await.unwrap();
+        db.finalize_results(RetailerName::AlFlahertys, succeeded_start)
+            .await
+            .unwrap();
+
+        let live_results = db
+            .get_live_results(RetailerName::AlFlahertys, 0)
+            .await
+            .unwrap();
+
+        assert_eq!(live_results.len(), 1);
+        assert_eq!(live_results[0].name, "complete");
+    }
+
+    #[tokio::test]
+    async fn counts_each_live_product_once() {
+        let db = InMemoryStorage::new();
+        let now = get_current_time();
+
+        // the live view keeps snapshots from earlier crawls around
+        for query_time in [now - 60, now] {
+            let result = crawl_result("complete", query_time);
+            db.insert_results(vec![&result]).await.unwrap();
+            db.finalize_results(RetailerName::AlFlahertys, query_time)
+                .await
+                .unwrap();
+        }
+
+        let stats = db.get_retailer_stats().await.unwrap();
+
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].live_product_count, 1);
+        assert_eq!(stats[0].last_crawled, None);
+    }
+}
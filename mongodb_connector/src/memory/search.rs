@@ -0,0 +1,167 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+use common::{
+    canonical_product::CollectionCanonicalProduct,
+    result::{base::CrawlResult, enums::Category, metadata::Metadata},
+    search_params::{ApiSearchInput, CollectionSearchResults, Sort},
+    utils::normalized_relative_days,
+};
+
+use crate::query_pipeline::page_stage::MAX_ITEMS_PER_PAGE;
+
+/// Runs the search pipeline over the live results, stage for stage: match,
+/// group by name and URL, sort, group by canonical product when asked to, then page
+pub(super) fn search_items(
+    live_results: &[CrawlResult],
+    canonical_products: &[CollectionCanonicalProduct],
+    query_params: &ApiSearchInput,
+) -> CollectionSearchResults {
+    let terms = get_search_terms(&query_params.query);
+    let min_query_time = normalized_relative_days(2);
+
+    let mut seen: HashSet<(&str, &str)> = HashSet::new();
+
+    let mut matches: Vec<(f64, &CrawlResult)> = live_results
+        .iter()
+        .filter(|result| result.query_time as i64 >= min_query_time)
+        .filter(|result| matches_filters(result, query_params))
+        .filter_map(|result| Some((get_text_score(&result.name, &terms)?, result)))
+        .filter(|(_, result)| seen.insert((result.url.as_str(), result.name.as_str())))
+        .collect();
+
+    match query_params.sort {
+        Sort::Relevant => matches.sort_by(|(score, result), (other_score, other)| {
+            other_score
+                .total_cmp(score)
+                .then_with(|| result.name.cmp(&other.name))
+        }),
+        Sort::PriceAsc => matches.sort_by(|(_, result), (_, other)| {
+            compare_prices(result, other).then_with(|| result.name.cmp(&other.name))
+        }),
+        Sort::PriceDesc => matches.sort_by(|(_, result), (_, other)| {
+            compare_prices(other, result).then_with(|| result.name.cmp(&other.name))
+        }),
+    }
+
+    if query_params.grouped {
+        let products_by_url: HashMap<&str, &str> = canonical_products
+            .iter()
+            .flat_map(|product| {
+                product
+                    .listings
+                    .iter()
+                    .map(|listing| (listing.url.as_str(), product.key.as_str()))
+            })
+            .collect();
+
+        let mut seen_products: HashSet<&str> = HashSet::new();
+
+        matches.retain(|(_, result)| {
+            products_by_url
+                .get(result.url.as_str())
+                .is_none_or(|key| seen_products.insert(key))
+        });
+    }
+
+    let page = query_params.page.unwrap_or(0) * MAX_ITEMS_PER_PAGE;
+
+    CollectionSearchResults {
+        total_count: matches.len() as u64,
+        items: matches
+            .into_iter()
+            .skip(page as usize)
+            .take(MAX_ITEMS_PER_PAGE as usize)
+            .map(|(_, result)| result.clone())
+            .collect(),
+    }
+}
+
+/// Every term is searched as a quoted phrase, so they all have to be in the name
+fn get_search_terms(query: &str) -> Vec<String> {
+    query
+        .split(' ')
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Stands in for MongoDB's text score, shorter names with more matches score
+/// higher, `None` if any of the terms is missing
+fn get_text_score(name: &str, terms: &[String]) -> Option<f64> {
+    let name = name.to_lowercase();
+
+    if terms.is_empty() || terms.iter().any(|term| !name.contains(term.as_str())) {
+        return None;
+    }
+
+    let occurrences: usize = terms
+        .iter()
+        .map(|term| name.matches(term.as_str()).count())
+        .sum();
+    let word_count = name.split_whitespace().count().max(1);
+
+    Some(occurrences as f64 / word_count as f64)
+}
+
+fn matches_filters(result: &CrawlResult, query_params: &ApiSearchInput) -> bool {
+    let final_price = result
+        .price
+        .sale_price
+        .unwrap_or(result.price.regular_price);
+
+    if query_params
+        .min_price
+        .is_some_and(|min_price| final_price < min_price.cents())
+        || query_params
+            .max_price
+            .is_some_and(|max_price| final_price > max_price.cents())
+    {
+        return false;
+    }
+
+    let category_matches = match query_params.category == Category::default() {
+        true => {
+            [Category::Firearm, Category::Other, Category::Ammunition].contains(&result.category)
+        }
+        false => result.category == query_params.category,
+    };
+
+    // results store their full taxonomy path, so this also matches child nodes
+    let subcategory_matches = query_params
+        .subcategory
+        .is_none_or(|subcategory| result.taxonomy.contains(&subcategory));
+
+    let retailer_matches =
+        query_params.retailers.is_empty() || query_params.retailers.contains(&result.retailer);
+
+    category_matches && subcategory_matches && retailer_matches
+}
+
+/// Ammunition is compared by its price per round where the round count is known
+fn compare_prices(result: &CrawlResult, other: &CrawlResult) -> Ordering {
+    let product_price = |result: &CrawlResult| {
+        result
+            .price
+            .sale_price
+            .unwrap_or(result.price.regular_price)
+    };
+
+    let final_price = |result: &CrawlResult| {
+        let round_count = match &result.metadata {
+            Some(Metadata::Ammunition(ammunition)) => ammunition.round_count.unwrap_or(0),
+            _ => 0,
+        };
+
+        match round_count {
+            0 => product_price(result) as f64,
+            round_count => product_price(result) as f64 / round_count as f64,
+        }
+    };
+
+    final_price(result)
+        .total_cmp(&final_price(other))
+        .then_with(|| product_price(result).cmp(&product_price(other)))
+}
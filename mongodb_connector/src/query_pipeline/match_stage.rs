@@ -37,13 +37,13 @@ impl MatchStage {
 
         if let Some(min_price) = self.search_query.min_price {
             documents.push(doc! {
-                "$gte": [final_price_doc.clone(), min_price]
+                "$gte": [final_price_doc.clone(), min_price.cents() as i64]
             });
         }
 
         if let Some(max_price) = self.search_query.max_price {
             documents.push(doc! {
-                "$lte": [final_price_doc, max_price]
+                "$lte": [final_price_doc, max_price.cents() as i64]
             });
         }
 
@@ -92,6 +92,11 @@ impl StageDocument for MatchStage {
             match_filter.insert("category", self.search_query.category.to_string());
         }
 
+        // results store their full taxonomy path, so this also matches child nodes
+        if let Some(subcategory) = self.search_query.subcategory {
+            match_filter.insert("taxonomy", subcategory.to_string());
+        }
+
         if !self.search_query.retailers.is_empty() {
             let retailer_strings = self
                 .search_query
@@ -1,4 +1,4 @@
 pub(super) mod match_stage;
-pub(super) mod page_stage;
+pub(crate) mod page_stage;
 pub(super) mod sort_stage;
 pub(super) mod traits;
@@ -3,7 +3,7 @@ use mongodb::bson::{Document, doc};
 
 use super::traits::StageDocument;
 
-const MAX_ITEMS_PER_PAGE: u32 = 32;
+pub(crate) const MAX_ITEMS_PER_PAGE: u32 = 32;
 
 pub(super) struct PageStage {
     search_query: ApiSearchInput,
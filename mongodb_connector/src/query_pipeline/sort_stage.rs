@@ -11,6 +11,33 @@ impl SortStage {
     pub(super) fn new(search_query: ApiSearchInput) -> Self {
         Self { search_query }
     }
+
+    /// Sorts on the fields the stage documents add, so it
+    /// still works after a `$group` drops the text score
+    pub(super) fn get_sort_document(&self) -> Document {
+        match self.search_query.sort {
+            Sort::Relevant => doc! {
+                "$sort": {
+                    "score": -1,
+                    "name": 1,
+                }
+            },
+            Sort::PriceAsc => doc! {
+                "$sort": {
+                    "final_price": 1,
+                    "product_price": 1,
+                    "name": 1,
+                }
+            },
+            Sort::PriceDesc => doc! {
+                "$sort": {
+                    "final_price": -1,
+                    "product_price": -1,
+                    "name": 1,
+                }
+            },
+        }
+    }
 }
 
 impl StageDocument for SortStage {
@@ -69,44 +96,19 @@ impl StageDocument for SortStage {
 
         match self.search_query.sort {
             Sort::Relevant => {
-                sort_docs.extend([
-                    doc! {
-                        "$addFields": {
-                            "score": {
-                                "$meta": "textScore"
-                            }
-                        }
-                    },
-                    doc! {
-                        "$sort": {
-                            "score": -1,
-                            "name": 1,
-                        }
-                    },
-                ]);
-            }
-            Sort::PriceAsc => {
-                sort_docs.extend(final_price);
-                sort_docs.push(doc! {
-                    "$sort": {
-                        "final_price": 1,
-                        "product_price": 1,
-                        "name": 1,
-                    }
-                });
-            }
-            Sort::PriceDesc => {
-                sort_docs.extend(final_price);
                 sort_docs.push(doc! {
-                    "$sort": {
-                        "final_price": -1,
-                        "product_price": -1,
-                        "name": 1,
+                    "$addFields": {
+                        "score": {
+                            "$meta": "textScore"
+                        }
                     }
                 });
             }
+            Sort::PriceAsc | Sort::PriceDesc => sort_docs.extend(final_price),
         };
 
+        sort_docs.push(self.get_sort_document());
+
         sort_docs
     }
 }
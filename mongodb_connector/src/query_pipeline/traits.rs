@@ -3,6 +3,8 @@ use mongodb::bson::Document;
 use mongodb::bson::doc;
 use tracing::trace;
 
+use crate::constants::COLLECTION_CANONICAL_PRODUCTS_NAME;
+
 use super::match_stage::MatchStage;
 use super::page_stage::PageStage;
 use super::sort_stage::SortStage;
@@ -38,7 +40,44 @@ impl SearchPipeline {
                 }
             },
         ]);
-        documents.extend(SortStage::new(self.search_query.clone()).get_stage_documents());
+        let sort_stage = SortStage::new(self.search_query.clone());
+        documents.extend(sort_stage.get_stage_documents());
+
+        // listings of the same canonical product collapse into whichever sorts first,
+        // listings that aren't matched to a product stay on their own
+        if self.search_query.grouped {
+            documents.extend([
+                doc! {
+                    "$lookup": {
+                        "from": COLLECTION_CANONICAL_PRODUCTS_NAME,
+                        "localField": "url",
+                        "foreignField": "listings.url",
+                        "pipeline": [{ "$project": { "_id": 1 } }],
+                        "as": "canonical_products",
+                    }
+                },
+                doc! {
+                    "$group": {
+                        "_id": {
+                            "$ifNull": [{ "$first": "$canonical_products._id" }, "$_id"]
+                        },
+                        "doc": {
+                            "$first": "$$ROOT"
+                        }
+                    }
+                },
+                doc! {
+                    "$replaceRoot": {
+                        "newRoot": "$doc"
+                    }
+                },
+                doc! {
+                    "$unset": "canonical_products"
+                },
+                sort_stage.get_sort_document(),
+            ]);
+        }
+
         documents.push(doc! {
             "$facet": {
                 "items": PageStage::new(self.search_query.clone()).get_stage_documents(),
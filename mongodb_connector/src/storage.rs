@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use common::{
+    canonical_product::{CanonicalLink, CollectionCanonicalProduct},
+    crawl_baseline::CollectionCrawlBaseline,
+    crawl_checkpoint::CollectionCrawlCheckpoint,
+    crawl_diff::CollectionCrawlDiff,
+    crawl_run::{CollectionCrawlRun, CrawlRunRetailer},
+    messages::Message,
+    price_history::{ApiPriceHistoryInput, CollectionPriceHistory},
+    result::{
+        base::CrawlResult,
+        enums::{Category, RetailerName},
+    },
+    retailer_info::CollectionRetailerStats,
+    run_lock::CollectionRunLock,
+    search_params::{ApiSearchInput, CollectionSearchResults},
+};
+use mongodb::bson::oid::ObjectId;
+
+use crate::errors::StorageError;
+
+/// Everything the API and indexer read and write, implemented by `MongoDBConnector`
+/// and by `InMemoryStorage` for running without a database
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn insert_message(&self, message: Message) -> Result<(), StorageError>;
+
+    async fn search_items(
+        &self,
+        query_params: &ApiSearchInput,
+    ) -> Result<CollectionSearchResults, StorageError>;
+
+    /// Writes a batch of results into crawl-results, without touching the live view
+    async fn insert_results(&self, results: Vec<&CrawlResult>) -> Result<(), StorageError>;
+
+    async fn replace_result(&self, result: &CrawlResult, since: u64) -> Result<(), StorageError>;
+
+    /// Publishes everything the retailer wrote since `since` into the live view and
+    /// price history, only meant to be called once the retailer crawled successfully
+    async fn finalize_results(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<(), StorageError>;
+
+    /// Every result the retailer wrote to crawl-results since `since`
+    async fn get_results_since(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError>;
+
+    /// Every live result of the retailer written since `since`, which
+    /// can include more than one snapshot of the same product
+    async fn get_live_results(
+        &self,
+        retailer: RetailerName,
+        since: u64,
+    ) -> Result<Vec<CrawlResult>, StorageError>;
+
+    async fn get_crawl_checkpoint(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlCheckpoint>, StorageError>;
+
+    async fn save_crawl_checkpoint(
+        &self,
+        checkpoint: &CollectionCrawlCheckpoint,
+    ) -> Result<(), StorageError>;
+
+    async fn start_crawl_run(&self, run: &CollectionCrawlRun) -> Result<(), StorageError>;
+
+    /// Adds a finished retailer to its run
+    async fn record_crawl_run_retailer(
+        &self,
+        run_id: ObjectId,
+        retailer: &CrawlRunRetailer,
+    ) -> Result<(), StorageError>;
+
+    async fn finish_crawl_run(&self, run_id: ObjectId, end_time: u64) -> Result<(), StorageError>;
+
+    /// The `limit` latest runs, only including runs that crawled `retailer` if it's set
+    async fn get_crawl_runs(
+        &self,
+        retailer: Option<RetailerName>,
+        limit: i64,
+    ) -> Result<Vec<CollectionCrawlRun>, StorageError>;
+
+    /// Crawls skipped by --resume for already being finished don't count
+    async fn get_last_successful_crawl(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CrawlRunRetailer>, StorageError>;
+
+    async fn insert_crawl_diff(&self, diff: &CollectionCrawlDiff) -> Result<(), StorageError>;
+
+    /// Every retailer's diff from a single run
+    async fn get_crawl_diffs(
+        &self,
+        run_id: ObjectId,
+    ) -> Result<Vec<CollectionCrawlDiff>, StorageError>;
+
+    async fn get_latest_crawl_diff(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Option<CollectionCrawlDiff>, StorageError>;
+
+    /// Swaps out every canonical product listing of the retailer for `links`
+    async fn replace_canonical_links(
+        &self,
+        retailer: RetailerName,
+        links: &[CanonicalLink],
+    ) -> Result<(), StorageError>;
+
+    /// Canonical products with a listing at any of the URLs
+    async fn get_canonical_products(
+        &self,
+        urls: Vec<String>,
+    ) -> Result<Vec<CollectionCanonicalProduct>, StorageError>;
+
+    /// Returns whether `owner` now holds the lock, for `ttl_secs` unless it's renewed
+    async fn acquire_run_lock(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError>;
+
+    /// Returns whether `owner` still holds the lock, an error means the DB couldn't
+    /// be reached and the lock may well still be held
+    async fn renew_run_lock(
+        &self,
+        name: &str,
+        owner: &str,
+        ttl_secs: u64,
+    ) -> Result<bool, StorageError>;
+
+    async fn release_run_lock(&self, name: &str, owner: &str) -> Result<(), StorageError>;
+
+    async fn get_run_lock(&self, name: &str) -> Result<Option<CollectionRunLock>, StorageError>;
+
+    async fn get_retailer_stats(&self) -> Result<Vec<CollectionRetailerStats>, StorageError>;
+
+    async fn get_crawl_baselines(
+        &self,
+        retailer: RetailerName,
+    ) -> Result<Vec<CollectionCrawlBaseline>, StorageError>;
+
+    /// Appends the latest count, only keeping the last `window` counts around
+    async fn update_crawl_baseline(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        count: u64,
+        window: u64,
+    ) -> Result<(), StorageError>;
+
+    /// Sets how many crawls in a row were left out of the baseline
+    async fn set_crawl_baseline_skipped(
+        &self,
+        retailer: RetailerName,
+        category: Category,
+        skipped: u64,
+    ) -> Result<(), StorageError>;
+
+    async fn get_pricing_history(
+        &self,
+        query: ApiPriceHistoryInput,
+    ) -> Result<Option<CollectionPriceHistory>, StorageError>;
+
+    /// Price history written before products had IDs
+    async fn get_unidentified_price_histories(
+        &self,
+    ) -> Result<Vec<CollectionPriceHistory>, StorageError>;
+
+    /// Gives old price history its product ID and canonical URL, merging it
+    /// into the product's current history, returns whether it was merged
+    async fn assign_price_history_product_id(
+        &self,
+        history: &CollectionPriceHistory,
+        product_id: &str,
+        url: &str,
+    ) -> Result<bool, StorageError>;
+
+    /// Product IDs the retailer's crawls gave results at the URL, only
+    /// those with the same name if there are any
+    async fn get_product_ids_at_url(
+        &self,
+        retailer: RetailerName,
+        url: &str,
+        name: &str,
+    ) -> Result<Vec<String>, StorageError>;
+
+    async fn find_result(&self, object_id: String) -> Result<Option<CrawlResult>, StorageError>;
+}
@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use common::money::MoneyError;
 use crawler::errors::CrawlerError;
 use thiserror::Error;
 
@@ -9,6 +12,8 @@ pub enum RetailerError {
     CrawlerInitFailed(#[from] CrawlerError),
     #[error("Failed to parse price into u64: {0}")]
     InvalidNumber(String),
+    #[error("Failed to parse price: {0}")]
+    InvalidPrice(#[from] MoneyError),
     #[error("API request is missing key in JSON response: {0}")]
     ApiResponseMissingKey(String),
     #[error("API request has wrong shape: {0}")]
@@ -17,8 +22,30 @@ pub enum RetailerError {
     HtmlElementMissingAttribute(String, String),
     #[error("Missing element {0} from HTML")]
     HtmlMissingElement(String),
+    #[error("Invalid URL {0}: {1}")]
+    InvalidUrl(String, String),
+    #[error("Skipped {0} products, exceeding the error budget of {1}")]
+    ErrorBudgetExceeded(u64, u64),
     #[error("General error: {0}")]
     GeneralError(String),
     #[error("Failed to deserialize JSON string into Value {0}")]
     InvalidApiResponse(#[from] serde_json::Error),
+    #[error("Crawl ran past its time budget of {0:?}")]
+    TimeBudgetExceeded(Duration),
+    #[error("Crawl ran past its budget of {0} requests")]
+    RequestBudgetExceeded(u64),
+    #[error("Crawl was interrupted by the indexer shutting down")]
+    Interrupted,
+    #[error("Crawl was stopped after losing the indexer lock")]
+    LockLost,
+}
+
+impl RetailerError {
+    /// The crawl was cut short by the indexer, rather than the retailer failing
+    pub fn is_budget_exceeded(&self) -> bool {
+        matches!(
+            self,
+            RetailerError::TimeBudgetExceeded(_) | RetailerError::RequestBudgetExceeded(_)
+        )
+    }
 }
@@ -1,7 +1,5 @@
 pub mod errors;
+pub mod registry;
 mod retailers;
 pub mod structures;
 pub(crate) mod utils;
-
-pub use retailers::gql;
-pub use retailers::html;
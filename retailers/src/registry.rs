@@ -0,0 +1,137 @@
+use common::{money::Currency, result::enums::RetailerName, retailer_info::RetailerInfo};
+
+use crate::{
+    errors::RetailerError,
+    structures::{GqlRetailerSuper, HtmlRetailerSuper},
+    utils::url::canonicalize_url,
+};
+
+pub enum RetailerFactory {
+    Html(fn() -> Box<dyn HtmlRetailerSuper>),
+    Gql(fn() -> Box<dyn GqlRetailerSuper>),
+}
+
+/// Everything the indexer needs to know about a retailer, submitted
+/// from the retailer's own module through `register_retailer!`
+pub struct RetailerRegistration {
+    pub name: RetailerName,
+    pub info: RetailerInfo,
+    pub factory: RetailerFactory,
+}
+
+impl RetailerRegistration {
+    /// Canonicalizes a product URL the same way the retailer's crawls do
+    pub fn canonicalize_url(&self, url: &str) -> Result<String, RetailerError> {
+        let rules = match &self.factory {
+            RetailerFactory::Html(factory) => factory().get_url_rules(),
+            RetailerFactory::Gql(factory) => factory().get_url_rules(),
+        };
+
+        canonicalize_url(self.info.homepage, url, &rules)
+    }
+}
+
+inventory::collect!(RetailerRegistration);
+
+/// Only implemented by `register_retailer!`, the `*RetailerSuper` traits
+/// require it so a retailer that was never registered fails to compile
+pub trait RegisteredRetailer {}
+
+pub fn get_registered_retailers() -> impl Iterator<Item = &'static RetailerRegistration> {
+    inventory::iter::<RetailerRegistration>.into_iter()
+}
+
+/// Only implemented by `register_retailer!`, once for the marker of every registered
+/// `RetailerName` variant, so registering a retailer twice is a conflicting implementation
+pub(crate) trait RegisteredName {}
+
+pub(crate) struct NameMarker<const RETAILER: usize>;
+
+/// Fails to compile unless every `RetailerName` is registered, the match has to name
+/// every variant and a forgotten `register_retailer!` leaves its marker unimplemented
+macro_rules! assert_registry_complete {
+    ($($retailer:ident),* $(,)?) => {
+        const _: () = {
+            const fn registered<T: RegisteredName>() {}
+
+            #[allow(dead_code)]
+            const fn check(retailer: RetailerName) {
+                match retailer {
+                    $(RetailerName::$retailer => {
+                        registered::<NameMarker<{ RetailerName::$retailer as usize }>>()
+                    })*
+                }
+            }
+        };
+    };
+}
+
+assert_registry_complete!(
+    ReliableGun,
+    LeverArms,
+    ItalianSportingGoods,
+    AlFlahertys,
+    BullseyeNorth,
+    CalgaryShootingCentre,
+    CanadasGunStore,
+    FirearmsOutletCanada,
+    TheAmmoSource,
+    Tenda,
+    Rdsc,
+    G4CGunStore,
+    Tillsonburg,
+    DanteSports,
+    SelectShootingSupplies,
+    RangeviewSports,
+    TrueNorthArms,
+    DominionOutdoors,
+    ProphetRiver,
+    InternationalShootingSupplies,
+    InterSurplus,
+    GreatNorthGun,
+    ClintonSportingGoods,
+    AlSimmons,
+    SJHardware,
+    VictoryRidgeSports,
+    Marstar,
+    MagDump,
+    SoleyOutdoors,
+    BartonsBigCountry,
+);
+
+/// The currency the retailer registered with, for parsers that only get the retailer's name
+pub(crate) fn get_retailer_currency(retailer: RetailerName) -> Currency {
+    get_registered_retailers()
+        .find(|registration| registration.name == retailer)
+        .map(|registration| registration.info.currency)
+        .unwrap_or_default()
+}
+
+/// Registers a retailer with the indexer, the retailer struct needs to share
+/// its name with the `RetailerName` variant and have a `new()` constructor
+///
+/// ```ignore
+/// register_retailer!(Html, AlSimmons, RetailerInfo { display_name: "Al Simmons Gun Shop", .. });
+/// ```
+macro_rules! register_retailer {
+    ($kind:ident, $retailer:ident, $info:expr) => {
+        impl $crate::registry::RegisteredRetailer for $retailer {}
+
+        impl $crate::registry::RegisteredName
+            for $crate::registry::NameMarker<
+                { common::result::enums::RetailerName::$retailer as usize },
+            >
+        {
+        }
+
+        inventory::submit! {
+            $crate::registry::RetailerRegistration {
+                name: common::result::enums::RetailerName::$retailer,
+                info: $info,
+                factory: $crate::registry::RetailerFactory::$kind(|| Box::new($retailer::new())),
+            }
+        }
+    };
+}
+
+pub(crate) use register_retailer;
@@ -1,3 +1 @@
 mod prophet_river;
-
-pub use prophet_river::prophet_river::ProphetRiver;
@@ -29,6 +29,7 @@ pub(super) const API_QUERY_REQUEST: &str = r#"
 						}
 					}
 				}
+				entityId
 				name
 				inventory {
 					isInStock
@@ -41,9 +42,11 @@ pub(super) const API_QUERY_REQUEST: &str = r#"
 				prices(currencyCode: CAD) {
 					price {
 						value
+						currencyCode
 					}
 					salePrice {
 						value
+						currencyCode
 					}
 				}
 			}
@@ -1,7 +1,12 @@
-use common::result::{base::Price, enums::Category};
+use std::str::FromStr;
+
+use common::{
+    money::{Currency, Money},
+    result::{base::Price, enums::Category},
+};
 use serde::Deserialize;
 
-use crate::{errors::RetailerError, utils::conversions::price_to_cents};
+use crate::errors::RetailerError;
 
 #[derive(Deserialize, Debug)]
 pub(super) struct ApiResponse {
@@ -41,6 +46,7 @@ pub(super) struct ApiProductsEdge {
 #[serde(rename_all = "camelCase")]
 pub(super) struct ApiProductNode {
     pub(super) categories: ApiCategories,
+    pub(super) entity_id: u64,
     pub(super) name: String,
     pub(super) inventory: ApiInventory,
     pub(super) path: String,
@@ -56,18 +62,11 @@ pub(super) struct ApiProductPrice {
 }
 
 impl ApiProductPrice {
-    fn float_to_cents(original_price: f32) -> Result<u64, RetailerError> {
-        price_to_cents(original_price.to_string())
-    }
-
     pub(super) fn get_price(&self) -> Result<Price, RetailerError> {
-        let mut price = Price {
-            regular_price: Self::float_to_cents(self.price.value)?,
-            sale_price: None,
-        };
+        let mut price = Price::new(self.price.get_money()?);
 
         if let Some(sale_price) = &self.sale_price {
-            price.sale_price = Some(Self::float_to_cents(sale_price.value)?);
+            price = price.with_sale_price(sale_price.get_money()?);
         }
 
         Ok(price)
@@ -75,8 +74,23 @@ impl ApiProductPrice {
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub(super) struct ApiPrice {
     pub(super) value: f32,
+    pub(super) currency_code: String,
+}
+
+impl ApiPrice {
+    fn get_money(&self) -> Result<Money, RetailerError> {
+        let currency = Currency::from_str(&self.currency_code).map_err(|_| {
+            RetailerError::ApiResponseInvalidShape(format!(
+                "Unknown currency: {}",
+                self.currency_code
+            ))
+        })?;
+
+        Ok(Money::from_f32(self.value, currency)?)
+    }
 }
 
 #[derive(Deserialize, Debug)]
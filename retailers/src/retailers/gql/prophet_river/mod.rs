@@ -1,3 +1,4 @@
 mod api_request;
 mod api_response_objects;
+#[allow(clippy::module_inception)]
 pub mod prophet_river;
@@ -1,5 +1,9 @@
 use async_trait::async_trait;
-use common::result::{base::CrawlResult, enums::RetailerName};
+use common::{
+    money::Currency,
+    result::{base::CrawlResult, enums::RetailerName},
+    retailer_info::{Platform, RetailerInfo},
+};
 use crawler::{
     request::{Request, RequestBuilder},
     traits::HttpMethod,
@@ -11,11 +15,12 @@ use tracing::warn;
 
 use crate::{
     errors::RetailerError,
+    registry::register_retailer,
     retailers::gql::prophet_river::{
         api_request::{API_QUERY_REQUEST, PAGINATION_REPLACEMENT_KEY},
-        api_response_objects::ApiResponse,
+        api_response_objects::{ApiProductsEdge, ApiResponse},
     },
-    structures::{GqlRetailer, GqlRetailerSuper, Retailer},
+    structures::{GqlRetailer, GqlRetailerSuper, ParsedPage, Retailer},
     utils::regex::unwrap_regex_capture,
 };
 
@@ -53,10 +58,67 @@ impl ProphetRiver {
 
         Ok(token)
     }
+
+    fn parse_product(&self, edge: ApiProductsEdge) -> Result<Option<CrawlResult>, RetailerError> {
+        let node = edge.node;
+
+        if !node.inventory.is_in_stock {
+            return Ok(None);
+        }
+
+        if node.inventory.has_variant_inventory {
+            return Err(RetailerError::GeneralError(format!(
+                "Failed to parse object {} since it contains variants",
+                node.name
+            )));
+        }
+
+        let Some(category) = node.categories.get_category() else {
+            warn!(
+                "Skipping unrecognized item: {} (listed under {:?})",
+                node.name, node.categories
+            );
+            return Ok(None);
+        };
+
+        let url = format!("{MAIN_URL}{}", node.path);
+
+        let image_url = match node.default_image {
+            Some(api_image) => api_image.url,
+            None => DEFAULT_IMAGE_URL.into(),
+        };
+
+        let new_result = CrawlResult::new(
+            node.name,
+            url,
+            node.prices.get_price()?,
+            self.get_retailer_name(),
+            category,
+        )
+        .with_image_url(image_url)
+        .with_sku(node.entity_id.to_string());
+
+        Ok(Some(new_result))
+    }
 }
 
 impl GqlRetailerSuper for ProphetRiver {}
 
+register_retailer!(
+    Gql,
+    ProphetRiver,
+    RetailerInfo {
+        display_name: "Prophet River",
+        homepage: "https://store.prophetriver.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::BigCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 #[async_trait]
 impl Retailer for ProphetRiver {
     async fn init(&mut self) -> Result<(), RetailerError> {
@@ -104,50 +166,13 @@ impl GqlRetailer for ProphetRiver {
         Ok(request)
     }
 
-    async fn parse_response(&self, response: &str) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    async fn parse_response(&self, response: &str) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let response_objects = serde_json::from_str::<ApiResponse>(response)?;
 
         for edge in response_objects.data.site.products.edges {
-            let node = edge.node;
-
-            if !node.inventory.is_in_stock {
-                continue;
-            }
-
-            if node.inventory.has_variant_inventory {
-                return Err(RetailerError::GeneralError(format!(
-                    "Failed to parse object {} since it contains variants",
-                    node.name
-                )));
-            }
-
-            let Some(category) = node.categories.get_category() else {
-                warn!(
-                    "Skipping unrecognized item: {} (listed under {:?})",
-                    node.name, node.categories
-                );
-                continue;
-            };
-
-            let url = format!("{MAIN_URL}{}", node.path);
-
-            let image_url = match node.default_image {
-                Some(api_image) => api_image.url,
-                None => DEFAULT_IMAGE_URL.into(),
-            };
-
-            let new_result = CrawlResult::new(
-                node.name,
-                url,
-                node.prices.get_price()?,
-                self.get_retailer_name(),
-                category,
-            )
-            .with_image_url(image_url);
-
-            results.push(new_result);
+            results.record(self.parse_product(edge));
         }
 
         Ok(results)
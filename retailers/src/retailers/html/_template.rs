@@ -3,15 +3,18 @@ use common::result::{
     base::{CrawlResult, Price},
     enums::{Category, RetailerName},
 };
+use common::money::Currency;
+use common::retailer_info::RetailerInfo;
 use crawler::request::{Request, RequestBuilder};
 use scraper::{Html, Selector};
 use tracing::{debug, error};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -34,6 +37,21 @@ impl aaa {
 
 impl HtmlRetailerSuper for aaa {}
 
+register_retailer!(
+    Html,
+    aaa,
+    RetailerInfo {
+        display_name: "aaa",
+        homepage: "aaa",
+        province: None,
+        city: None,
+        platforms: &[],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for aaa {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::aaa
@@ -60,10 +78,10 @@ impl HtmlRetailer for aaa {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let fragment = Html::parse_document(response);
 
@@ -81,7 +99,7 @@ impl HtmlRetailer for aaa {
         }])
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         Ok(0)
     }
 }
@@ -1,7 +1,13 @@
+use std::str::FromStr;
+
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::{
     request::{Request, RequestBuilder},
@@ -12,11 +18,11 @@ use serde_json::Value;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
-    utils::{
-        conversions::price_to_cents,
-        ecommerce::{BigCommerce, BigCommerceNested},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
     },
+    utils::ecommerce::{BigCommerce, BigCommerceNested},
 };
 
 const PAGE_LIMIT: u64 = 36;
@@ -70,10 +76,71 @@ impl AlFlahertys {
     pub fn new() -> Self {
         Self {}
     }
+
+    fn parse_product(
+        &self,
+        product: &ApiRecord,
+        search_term: &HtmlSearchQuery,
+        bigcommerce: &mut BigCommerce,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        if product.in_stock.to_lowercase() != "yes" {
+            return Ok(None);
+        }
+
+        let currency = Currency::from_str(&product.currency).map_err(|_| {
+            RetailerError::ApiResponseInvalidShape(format!(
+                "Unknown currency: {}",
+                product.currency
+            ))
+        })?;
+
+        if product.total_variants > 0 {
+            let _ = bigcommerce.enqueue_nested_product(
+                product.name.clone(),
+                product.image_url.clone(),
+                format!("{}?setCurrencyId=1", product.url),
+                search_term.category,
+            );
+            return Ok(None);
+        }
+
+        let mut price = Price::new(Money::parse_with_default(&product.base_price, currency)?);
+
+        if product.base_price != product.sale_price {
+            price =
+                price.with_sale_price(Money::parse_with_default(&product.sale_price, currency)?);
+        }
+
+        let new_result = CrawlResult::new(
+            product.name.clone(),
+            product.url.clone(),
+            price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(product.image_url.clone());
+
+        Ok(Some(new_result))
+    }
 }
 
 impl HtmlRetailerSuper for AlFlahertys {}
 
+register_retailer!(
+    Html,
+    AlFlahertys,
+    RetailerInfo {
+        display_name: "Al Flaherty's",
+        homepage: "https://alflahertys.com",
+        province: Some(Province::Alberta),
+        city: Some("Edmonton"),
+        platforms: &[Platform::BigCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for AlFlahertys {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::AlFlahertys
@@ -108,11 +175,11 @@ impl HtmlRetailer for AlFlahertys {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
+    ) -> Result<ParsedPage, RetailerError> {
         let mut bigcommerce = BigCommerce::new();
-        let mut results: Vec<CrawlResult> = Vec::new();
+        let mut results = ParsedPage::new();
 
         let response = serde_json::from_str::<ApiResponse>(response)?;
 
@@ -121,40 +188,7 @@ impl HtmlRetailer for AlFlahertys {
         };
 
         for product in &query_results.records {
-            if product.in_stock.to_lowercase() != "yes" || product.currency.to_lowercase() != "cad"
-            {
-                continue;
-            }
-
-            if product.total_variants > 0 {
-                let _ = bigcommerce.enqueue_nested_product(
-                    product.name.clone(),
-                    product.image_url.clone(),
-                    format!("{}?setCurrencyId=1", product.url),
-                    search_term.category,
-                );
-                continue;
-            }
-
-            let mut price = Price {
-                regular_price: price_to_cents(product.base_price.clone())?,
-                sale_price: None,
-            };
-
-            if product.base_price != product.sale_price {
-                price.sale_price = Some(price_to_cents(product.sale_price.clone())?);
-            }
-
-            let new_result = CrawlResult::new(
-                product.name.clone(),
-                product.url.clone(),
-                price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(product.image_url.clone());
-
-            results.push(new_result);
+            results.record(self.parse_product(product, search_term, &mut bigcommerce));
         }
 
         results.extend(
@@ -205,7 +239,7 @@ impl HtmlRetailer for AlFlahertys {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let response = serde_json::from_str::<ApiResponse>(response)?;
 
         let Some(query_results) = response.query_results.first() else {
@@ -214,4 +248,8 @@ impl HtmlRetailer for AlFlahertys {
 
         Ok(query_results.meta.total_results_found / PAGE_LIMIT + 1)
     }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageCount
+    }
 }
@@ -1,24 +1,29 @@
 use std::{collections::HashMap, time::Duration};
 
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::{
     request::{Request, RequestBuilder},
     unprotected::UnprotectedCrawler,
 };
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Deserializer};
 use tokio::time::sleep;
 use tracing::{debug, warn};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         generic_sitemap::get_search_queries,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
@@ -116,92 +121,116 @@ impl BartonsBigCountry {
         }
     }
 
-    fn get_price(api_price: ApiResponsePrice) -> Result<Price, RetailerError> {
-        let mut price = Price {
-            regular_price: price_to_cents(api_price.price.to_string())?,
-            sale_price: None,
-        };
+    fn get_price(&self, api_price: ApiResponsePrice) -> Result<Price, RetailerError> {
+        let current_price = Money::from_f32(api_price.price, self.get_currency())?;
 
         if let Some(old_price) = api_price.price_old {
-            price.sale_price = Some(price.regular_price);
-            price.regular_price = price_to_cents(old_price.to_string())?;
+            let old_price = Money::from_f32(old_price, self.get_currency())?;
+
+            return Ok(Price::new(old_price).with_sale_price(current_price));
+        }
+
+        Ok(Price::new(current_price))
+    }
+
+    fn get_product_link(product: ElementRef) -> Result<Option<String>, RetailerError> {
+        let wrapper = extract_element_from_element(product, "a")?;
+
+        let link = element_extract_attr(wrapper, "href")?;
+
+        if !link.starts_with(PRODUCT_BASE_URL) {
+            warn!("Link is not same as retailer: {link}");
+            return Ok(None);
         }
 
-        Ok(price)
+        Ok(Some(link))
     }
 
     async fn parse_links(
         &self,
         product_links: Vec<String>,
         search_term: &HtmlSearchQuery,
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
+
+        for product_url in product_links {
+            results.record_all(self.parse_link(product_url, search_term).await);
+        }
+
+        Ok(results)
+    }
+
+    /// Parses a single product, which may expand into multiple variants
+    async fn parse_link(
+        &self,
+        product_url: String,
+        search_term: &HtmlSearchQuery,
     ) -> Result<Vec<CrawlResult>, RetailerError> {
         let mut results: Vec<CrawlResult> = Vec::new();
 
-        for product_url in product_links {
-            let request = RequestBuilder::new()
-                .set_url(product_url.replace(".html", ".ajax"))
-                .build();
-            let crawler = UnprotectedCrawler::make_web_request(request).await?;
+        let request = RequestBuilder::new()
+            .set_url(product_url.replace(".html", ".ajax"))
+            .build();
+        let crawler = UnprotectedCrawler::make_web_request(request).await?;
 
-            let parsed_product = serde_json::from_str::<ApiResponse>(&crawler.body)?;
+        let parsed_product = serde_json::from_str::<ApiResponse>(&crawler.body)?;
 
-            if !parsed_product.stock.available {
-                continue;
-            }
+        if !parsed_product.stock.available {
+            return Ok(results);
+        }
 
-            sleep(Duration::from_secs(2)).await;
+        sleep(Duration::from_secs(2)).await;
 
-            if parsed_product.variants.len() == 0 {
-                let price = Self::get_price(parsed_product.price)?;
+        if parsed_product.variants.is_empty() {
+            let price = self.get_price(parsed_product.price)?;
 
-                let new_result = CrawlResult::new(
-                    parsed_product.fulltitle,
-                    product_url,
-                    price,
-                    self.get_retailer_name(),
-                    search_term.category,
-                )
-                .with_image_url(parsed_product.image);
+            let new_result = CrawlResult::new(
+                parsed_product.fulltitle,
+                product_url,
+                price,
+                self.get_retailer_name(),
+                search_term.category,
+            )
+            .with_image_url(parsed_product.image);
 
-                results.push(new_result);
+            results.push(new_result);
 
+            return Ok(results);
+        }
+
+        for nested_product in parsed_product.variants {
+            if !nested_product.stock.available {
                 continue;
             }
 
-            for nested_product in parsed_product.variants {
-                if !nested_product.stock.available {
-                    continue;
-                }
-
-                let price = Self::get_price(nested_product.price)?;
+            let price = self.get_price(nested_product.price)?;
 
-                let mut product_name = parsed_product.fulltitle.clone();
+            let mut product_name = parsed_product.fulltitle.clone();
 
-                if let Some((_, variant_name)) = nested_product.title.split_once(" : ") {
-                    product_name = format!("{product_name} - {variant_name}");
-                }
+            if let Some((_, variant_name)) = nested_product.title.split_once(" : ") {
+                product_name = format!("{product_name} - {variant_name}");
+            }
 
-                let mut new_result = CrawlResult::new(
-                    product_name,
-                    nested_product.url,
-                    price,
-                    self.get_retailer_name(),
-                    search_term.category,
-                );
+            let mut new_result = CrawlResult::new(
+                product_name,
+                nested_product.url,
+                price,
+                self.get_retailer_name(),
+                search_term.category,
+            );
 
-                let mut image_url = parsed_product.image.replace("/50x50", "/512x512");
+            let mut image_url = parsed_product.image.replace("/50x50", "/512x512");
 
-                if let Some(nested_image_id) = nested_product.image {
-                    image_url = image_url.replace(
-                        &parsed_product.image_id.to_string(),
-                        &nested_image_id.to_string(),
-                    );
-                }
+            if let Some(nested_image_id) = nested_product.image {
+                image_url = image_url.replace(
+                    &parsed_product.image_id.to_string(),
+                    &nested_image_id.to_string(),
+                );
+            }
 
-                new_result = new_result.with_image_url(image_url);
+            new_result = new_result.with_image_url(image_url);
 
-                results.push(new_result);
-            }
+            results.push(new_result);
         }
 
         Ok(results)
@@ -210,6 +239,21 @@ impl BartonsBigCountry {
 
 impl HtmlRetailerSuper for BartonsBigCountry {}
 
+register_retailer!(
+    Html,
+    BartonsBigCountry,
+    RetailerInfo {
+        display_name: "Barton's Big Country",
+        homepage: "https://www.bartonsbigcountry.ca",
+        province: None,
+        city: None,
+        platforms: &[Platform::Lightspeed],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 #[async_trait]
 impl Retailer for BartonsBigCountry {
     fn get_retailer_name(&self) -> RetailerName {
@@ -276,9 +320,9 @@ impl HtmlRetailer for BartonsBigCountry {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
+    ) -> Result<ParsedPage, RetailerError> {
         let products = {
             let html = Html::parse_document(response);
             let product_selector =
@@ -289,32 +333,30 @@ impl HtmlRetailer for BartonsBigCountry {
                 .collect::<Vec<_>>()
         };
 
+        let mut results = ParsedPage::new();
         let mut product_links: Vec<String> = Vec::new();
 
         for html_doc in products {
             let product_inner = Html::parse_fragment(&html_doc);
             let product = product_inner.root_element();
 
-            let wrapper = extract_element_from_element(product, "a")?;
-
-            let link = element_extract_attr(wrapper, "href")?;
-
-            if !link.starts_with(PRODUCT_BASE_URL) {
-                warn!("Link is not same as retailer: {link}");
-                continue;
+            match Self::get_product_link(product) {
+                Ok(Some(link)) => product_links.push(link),
+                Ok(None) => {}
+                Err(err) => results.push_error(err),
             }
-
-            product_links.push(link);
         }
 
-        Ok(self.parse_links(product_links, search_term).await?)
+        results.extend(self.parse_links(product_links, search_term).await?);
+
+        Ok(results)
     }
 
     fn get_search_terms(&self) -> Vec<HtmlSearchQuery> {
         self.search_queries.clone()
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let fragment = Html::parse_document(response);
         let page_number_selector =
             Selector::parse("div.pager > ul > li:not(.active).number > a").unwrap();
@@ -1,7 +1,11 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
@@ -9,9 +13,12 @@ use tracing::{debug, warn};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
+    },
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -34,7 +41,7 @@ impl BullseyeNorth {
         Self {}
     }
 
-    fn get_price(product_element: ElementRef) -> Result<Price, RetailerError> {
+    fn get_price(&self, product_element: ElementRef) -> Result<Price, RetailerError> {
         /*
         <span class="pricing">
             <strong class="itemPrice">$239.99</strong>
@@ -47,11 +54,6 @@ impl BullseyeNorth {
 
         let price_element = extract_element_from_element(product_element, "span.pricing")?;
 
-        let mut price = Price {
-            regular_price: 0,
-            sale_price: None,
-        };
-
         match extract_element_from_element(price_element, "strong.salePrice") {
             Ok(sale_element) => {
                 let normal_price_element =
@@ -59,8 +61,14 @@ impl BullseyeNorth {
 
                 let normal_price = element_to_text(normal_price_element);
 
-                price.regular_price = price_to_cents(normal_price)?;
-                price.sale_price = Some(price_to_cents(element_to_text(sale_element))?);
+                Ok(Price::new(Money::parse_with_default(
+                    &normal_price,
+                    self.get_currency(),
+                )?)
+                .with_sale_price(Money::parse_with_default(
+                    &element_to_text(sale_element),
+                    self.get_currency(),
+                )?))
             }
             Err(_) => {
                 let normal_price_element =
@@ -68,16 +76,67 @@ impl BullseyeNorth {
 
                 let normal_price = element_to_text(normal_price_element);
 
-                price.regular_price = price_to_cents(normal_price)?;
+                Ok(Price::new(Money::parse_with_default(
+                    &normal_price,
+                    self.get_currency(),
+                )?))
             }
-        };
+        }
+    }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let name_element = extract_element_from_element(product, "span.name")?;
+        let image_element = extract_element_from_element(product, "span.image > img")?;
+
+        let url = element_extract_attr(product, "href")?;
+        let name = element_to_text(name_element);
+        let mut image = element_extract_attr(image_element, "src")?;
+
+        if image.starts_with("/") {
+            image = format!("{MAIN_URL}{image}");
+        }
+
+        if extract_element_from_element(product, "span.stock").is_err() {
+            debug!("Skipping not in stock product {}", name);
+            return Ok(None);
+        }
 
-        Ok(price)
+        let price = self.get_price(product)?;
+
+        let new_result = CrawlResult::new(
+            name,
+            url,
+            price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image.to_string());
+
+        Ok(Some(new_result))
     }
 }
 
 impl HtmlRetailerSuper for BullseyeNorth {}
 
+register_retailer!(
+    Html,
+    BullseyeNorth,
+    RetailerInfo {
+        display_name: "Bullseye North",
+        homepage: "https://www.bullseyenorth.com",
+        province: Some(Province::Ontario),
+        city: Some("London"),
+        platforms: &[Platform::Custom],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for BullseyeNorth {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::BullseyeNorth
@@ -104,44 +163,17 @@ impl HtmlRetailer for BullseyeNorth {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
         let product_selector = Selector::parse("a.product").unwrap();
 
         for product in html.select(&product_selector) {
-            let name_element = extract_element_from_element(product, "span.name")?;
-            let image_element = extract_element_from_element(product, "span.image > img")?;
-
-            let url = element_extract_attr(product, "href")?;
-            let name = element_to_text(name_element);
-            let mut image = element_extract_attr(image_element, "src")?;
-
-            if image.starts_with("/") {
-                image = format!("{MAIN_URL}{image}");
-            }
-
-            if extract_element_from_element(product, "span.stock").is_err() {
-                debug!("Skipping not in stock product {}", name);
-                continue;
-            }
-
-            let price = Self::get_price(product)?;
-
-            let new_result = CrawlResult::new(
-                name,
-                url,
-                price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image.to_string());
-
-            results.push(new_result);
+            results.record(self.parse_product(product, search_term));
         }
 
         Ok(results)
@@ -189,7 +221,7 @@ impl HtmlRetailer for BullseyeNorth {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let html = Html::parse_document(response);
 
         let Ok(max_pages_el) = extract_element_from_element(html.root_element(), "p.paginTotals")
@@ -203,4 +235,8 @@ impl HtmlRetailer for BullseyeNorth {
 
         string_to_u64(max_page_count)
     }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageCount
+    }
 }
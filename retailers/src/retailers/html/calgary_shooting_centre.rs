@@ -1,16 +1,20 @@
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::price_to_cents,
         ecommerce::{BigCommerce, BigCommerceNested},
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
@@ -34,7 +38,7 @@ impl CalgaryShootingCentre {
     }
 
     /// For regular parcing using HTML elements
-    fn get_price_from_element(product_element: ElementRef) -> Result<Price, RetailerError> {
+    fn get_price_from_element(&self, product_element: ElementRef) -> Result<Price, RetailerError> {
         /*
         <span data-product-price-without-tax="" class="price price--withoutTax price--main">$2,160.00</span>
         <span data-product-non-sale-price-without-tax="" class="price price--non-sale">$2,400.00</span>
@@ -48,22 +52,79 @@ impl CalgaryShootingCentre {
         let price_str = element_to_text(price_main);
         let price_non_sale_str = element_to_text(price_non_sale);
 
-        let mut price = Price {
-            regular_price: price_to_cents(price_str)?,
-            sale_price: None,
-        };
+        let price = Money::parse_with_default(&price_str, self.get_currency())?;
 
         if !price_non_sale_str.is_empty() {
-            price.sale_price = Some(price.regular_price);
-            price.regular_price = price_to_cents(price_non_sale_str)?;
+            return Ok(Price::new(Money::parse_with_default(
+                &price_non_sale_str,
+                self.get_currency(),
+            )?)
+            .with_sale_price(price));
+        }
+
+        Ok(Price::new(price))
+    }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+        bigcommerce_helper: &mut BigCommerce,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let name_link_element = extract_element_from_element(product, "h4.card-title > a")?;
+
+        let image_element =
+            extract_element_from_element(product, "div.card-img-container > img.card-image")?;
+
+        let url = element_extract_attr(name_link_element, "href")?;
+        let name = element_to_text(name_link_element);
+        let image = element_extract_attr(image_element, "src")?;
+
+        let price_element = extract_element_from_element(product, "span.price--main")?;
+
+        // CSC doesn't list round count in the title: force the crawler to visit the page
+        // a `-` indicates variants, meaning we have to visit page
+        if element_to_text(price_element).contains("-")
+            || search_term.category == Category::Ammunition
+        {
+            let _ =
+                bigcommerce_helper.enqueue_nested_product_element(product, search_term.category);
+
+            return Ok(None);
         }
 
-        Ok(price)
+        let price = self.get_price_from_element(product)?;
+
+        let new_result = CrawlResult::new(
+            name,
+            url,
+            price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image.to_string());
+
+        Ok(Some(new_result))
     }
 }
 
 impl HtmlRetailerSuper for CalgaryShootingCentre {}
 
+register_retailer!(
+    Html,
+    CalgaryShootingCentre,
+    RetailerInfo {
+        display_name: "Calgary Shooting Centre",
+        homepage: "https://store.theshootingcentre.com",
+        province: Some(Province::Alberta),
+        city: Some("Calgary"),
+        platforms: &[Platform::BigCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for CalgaryShootingCentre {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::CalgaryShootingCentre
@@ -91,12 +152,12 @@ impl HtmlRetailer for CalgaryShootingCentre {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
+    ) -> Result<ParsedPage, RetailerError> {
         let mut bigcommerce_helper = BigCommerce::new();
 
-        let mut results: Vec<CrawlResult> = Vec::new();
+        let mut results = ParsedPage::new();
 
         let products = {
             let html = Html::parse_document(response);
@@ -111,40 +172,7 @@ impl HtmlRetailer for CalgaryShootingCentre {
             let html = Html::parse_fragment(&inner_html);
             let product = html.root_element();
 
-            let name_link_element = extract_element_from_element(product, "h4.card-title > a")?;
-
-            let image_element =
-                extract_element_from_element(product, "div.card-img-container > img.card-image")?;
-
-            let url = element_extract_attr(name_link_element, "href")?;
-            let name = element_to_text(name_link_element);
-            let image = element_extract_attr(image_element, "src")?;
-
-            let price_element = extract_element_from_element(product, "span.price--main")?;
-
-            // CSC doesn't list round count in the title: force the crawler to visit the page
-            // a `-` indicates variants, meaning we have to visit page
-            if element_to_text(price_element).contains("-")
-                || search_term.category == Category::Ammunition
-            {
-                let _ = bigcommerce_helper
-                    .enqueue_nested_product_element(product, search_term.category);
-
-                continue;
-            }
-
-            let price = Self::get_price_from_element(product)?;
-
-            let new_result = CrawlResult::new(
-                name,
-                url,
-                price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image.to_string());
-
-            results.push(new_result);
+            results.record(self.parse_product(product, search_term, &mut bigcommerce_helper));
         }
 
         results.extend(
@@ -185,7 +213,7 @@ impl HtmlRetailer for CalgaryShootingCentre {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         BigCommerce::parse_max_pages(response)
     }
 }
@@ -1,19 +1,23 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
-use scraper::{Html, Selector};
-use tracing::{debug, error};
+use scraper::{ElementRef, Html, Selector};
+use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
-    utils::{
-        conversions::price_to_cents,
-        html::{element_extract_attr, element_to_text, extract_element_from_element},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
     },
+    utils::html::{element_extract_attr, element_to_text, extract_element_from_element},
 };
 
 // items per page is constant, for some reason
@@ -33,10 +37,70 @@ impl CanadasGunStore {
     pub fn new() -> Self {
         Self {}
     }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let stock_element = extract_element_from_element(product, "span.product_status")?;
+
+        if element_to_text(stock_element) != "In Stock" {
+            debug!("Skipping out of stock item");
+            return Ok(None);
+        }
+
+        let name_link_element = extract_element_from_element(product, "h4.store_product_name > a")?;
+        let image_element = extract_element_from_element(product, "img.product_image")?;
+        let price_element = extract_element_from_element(product, "div.product_price")?;
+        let url = format!(
+            "{BASE_URL}{}",
+            element_extract_attr(name_link_element, "href")?
+        );
+
+        let name = element_to_text(name_link_element);
+
+        let image_src = element_extract_attr(image_element, "src")?;
+        let image_url = match image_src.starts_with("/") {
+            true => format!("{BASE_URL}{image_src}"),
+            false => image_src,
+        };
+
+        let firearm_price = Price::new(Money::parse_with_default(
+            &element_to_text(price_element),
+            self.get_currency(),
+        )?);
+
+        let new_result = CrawlResult::new(
+            name,
+            url,
+            firearm_price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image_url.to_string());
+
+        Ok(Some(new_result))
+    }
 }
 
 impl HtmlRetailerSuper for CanadasGunStore {}
 
+register_retailer!(
+    Html,
+    CanadasGunStore,
+    RetailerInfo {
+        display_name: "Canada's Gun Store",
+        homepage: "https://www.canadasgunstore.ca",
+        province: None,
+        city: None,
+        platforms: &[Platform::Custom],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for CanadasGunStore {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::CanadasGunStore
@@ -62,57 +126,17 @@ impl HtmlRetailer for CanadasGunStore {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
         let product_selector = Selector::parse("div.product_body").unwrap();
 
         for product in html.select(&product_selector) {
-            let stock_element = extract_element_from_element(product, "span.product_status")?;
-
-            if element_to_text(stock_element) != "In Stock" {
-                debug!("Skipping out of stock item");
-                continue;
-            }
-
-            let name_link_element =
-                extract_element_from_element(product, "h4.store_product_name > a")?;
-            let image_element = extract_element_from_element(product, "img.product_image")?;
-            let price_element = extract_element_from_element(product, "div.product_price")?;
-            let url = format!(
-                "{BASE_URL}{}",
-                element_extract_attr(name_link_element, "href")?
-            );
-
-            let name = element_to_text(name_link_element);
-
-            let image_src = element_extract_attr(image_element, "src")?;
-            let image_url = match image_src.starts_with("/") {
-                true => format!("{BASE_URL}{image_src}"),
-                false => image_src,
-            };
-
-            let price = price_to_cents(element_to_text(price_element))?;
-
-            let firearm_price = Price {
-                regular_price: price,
-                sale_price: None,
-            };
-
-            let new_result = CrawlResult::new(
-                name,
-                url,
-                firearm_price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image_url.to_string());
-
-            results.push(new_result);
+            results.record(self.parse_product(product, search_term));
         }
 
         Ok(results)
@@ -146,37 +170,9 @@ impl HtmlRetailer for CanadasGunStore {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
-        let html = Html::parse_document(response);
-
-        let Ok(page_count_element) = extract_element_from_element(
-            html.root_element(),
-            "div.store_results_navigation_top_wrapper > p.text-success",
-        ) else {
-            return Ok(0);
-        };
-
-        // 694 found, showing page 1 of 3
-        let results_text = element_to_text(page_count_element);
-        let results_parts: Vec<&str> = results_text.split(" ").collect();
-
-        let Some(pages) = results_parts.last() else {
-            let message = format!("Failed to split the string (empty matches): {results_text}");
-
-            error!(message);
-
-            return Err(RetailerError::GeneralError(message));
-        };
-
-        let Ok(page_as_num) = pages.parse::<u64>() else {
-            error!(
-                "{}",
-                format!("Failed to convert string into number: {pages}")
-            );
-
-            return Err(RetailerError::InvalidNumber(pages.to_string()));
-        };
-
-        Ok(page_as_num)
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::OffsetLimit {
+            limit: ITEM_PER_PAGE,
+        }
     }
 }
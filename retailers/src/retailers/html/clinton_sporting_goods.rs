@@ -1,7 +1,8 @@
 use async_trait::async_trait;
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
+use common::{
+    money::Currency,
+    result::enums::{Category, RetailerName},
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{Html, Selector};
@@ -9,7 +10,10 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
+    },
     utils::ecommerce::{WooCommerce, WooCommerceBuilder},
 };
 
@@ -31,6 +35,21 @@ impl ClintonSportingGoods {
 
 impl HtmlRetailerSuper for ClintonSportingGoods {}
 
+register_retailer!(
+    Html,
+    ClintonSportingGoods,
+    RetailerInfo {
+        display_name: "Clinton Sporting Goods",
+        homepage: "https://clintonsporting.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for ClintonSportingGoods {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::ClintonSportingGoods
@@ -57,10 +76,10 @@ impl HtmlRetailer for ClintonSportingGoods {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
@@ -75,11 +94,11 @@ impl HtmlRetailer for ClintonSportingGoods {
             .build();
 
         for product in html.select(&product_selector) {
-            results.push(woocommerce_helper.parse_product(
+            results.record(woocommerce_helper.parse_product(
                 product,
                 self.get_retailer_name(),
                 search_term.category,
-            )?);
+            ));
         }
 
         Ok(results)
@@ -123,7 +142,11 @@ impl HtmlRetailer for ClintonSportingGoods {
         query
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         WooCommerce::parse_max_pages(response)
     }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageCount
+    }
 }
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
+use common::{
+    money::Currency,
+    result::enums::{Category, RetailerName},
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{Html, Selector};
@@ -9,7 +10,10 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
+    },
     utils::ecommerce::{WooCommerce, WooCommerceBuilder},
 };
 
@@ -32,6 +36,21 @@ impl DanteSports {
 
 impl HtmlRetailerSuper for DanteSports {}
 
+register_retailer!(
+    Html,
+    DanteSports,
+    RetailerInfo {
+        display_name: "Dante Sports",
+        homepage: "https://www.dantesports.com",
+        province: Some(Province::Quebec),
+        city: Some("Montreal"),
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for DanteSports {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::DanteSports
@@ -59,10 +78,10 @@ impl HtmlRetailer for DanteSports {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
@@ -77,11 +96,11 @@ impl HtmlRetailer for DanteSports {
             .build();
 
         for product in html.select(&product_selector) {
-            results.push(woocommerce_helper.parse_product(
+            results.record(woocommerce_helper.parse_product(
                 product,
                 self.get_retailer_name(),
                 search_term.category,
-            )?);
+            ));
         }
 
         Ok(results)
@@ -111,7 +130,11 @@ impl HtmlRetailer for DanteSports {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         WooCommerce::parse_max_pages(response)
     }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageCount
+    }
 }
@@ -1,9 +1,13 @@
 use std::time::Duration;
 
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::{
     request::{Request, RequestBuilder},
@@ -15,9 +19,10 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -79,17 +84,12 @@ impl DominionOutdoors {
         let price_element =
             extract_element_from_element(page_element, "div.product-price > div > span.price")?;
 
-        let mut price = element_to_text(price_element);
-        if price.starts_with("C") {
-            price = price.split_off(1);
-        }
-
         // I don't know what the sale price looks like
         // YOLO
-        let price = Price {
-            regular_price: price_to_cents(price)?,
-            sale_price: None,
-        };
+        let price = Price::new(Money::parse_with_default(
+            &element_to_text(price_element),
+            self.get_currency(),
+        )?);
 
         let result = CrawlResult::new(name, url, price, retailer, category).with_image_url(image);
 
@@ -99,6 +99,21 @@ impl DominionOutdoors {
 
 impl HtmlRetailerSuper for DominionOutdoors {}
 
+register_retailer!(
+    Html,
+    DominionOutdoors,
+    RetailerInfo {
+        display_name: "Dominion Outdoors",
+        homepage: "https://www.dominionoutdoors.ca",
+        province: None,
+        city: None,
+        platforms: &[Platform::Lightspeed],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for DominionOutdoors {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::DominionOutdoors
@@ -125,10 +140,10 @@ impl HtmlRetailer for DominionOutdoors {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let mut links: Vec<String> = Vec::new();
 
@@ -148,19 +163,20 @@ impl HtmlRetailer for DominionOutdoors {
             let product_inner = Html::parse_fragment(&html_doc);
             let product = product_inner.root_element();
 
-            let title_element = extract_element_from_element(product, "a")?;
-            let url = element_extract_attr(title_element, "href")?;
+            let link = extract_element_from_element(product, "a")
+                .and_then(|title_element| element_extract_attr(title_element, "href"));
 
-            links.push(url);
+            match link {
+                Ok(url) => links.push(url),
+                Err(err) => results.push_error(err),
+            }
         }
 
         for link in links {
-            if let Some(result) = self
-                .parse_page(link, self.get_retailer_name(), search_term.category)
-                .await?
-            {
-                results.push(result);
-            }
+            results.record(
+                self.parse_page(link, self.get_retailer_name(), search_term.category)
+                    .await,
+            );
 
             sleep(Duration::from_secs(CRAWL_COOLDOWN_SECS)).await;
         }
@@ -192,7 +208,7 @@ impl HtmlRetailer for DominionOutdoors {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let fragment = Html::parse_document(response);
         let page_number_selector = Selector::parse("div.pager > ul.right > li.number").unwrap();
 
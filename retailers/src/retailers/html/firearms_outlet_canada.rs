@@ -1,22 +1,27 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
-use tracing::{debug, error};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
-    utils::{
-        conversions::price_to_cents,
-        html::{element_extract_attr, element_to_text, extract_element_from_element},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
     },
+    utils::html::{element_extract_attr, element_to_text, extract_element_from_element},
 };
 
 const URL: &str = "https://firearmsoutletcanada.com/{category}?in_stock=1&page={page}";
+// for some reason, each page returns a max of exactly 52 items
+const PAGE_SIZE: u64 = 52;
 
 pub struct FirearmsOutletCanada;
 
@@ -31,32 +36,71 @@ impl FirearmsOutletCanada {
         Self {}
     }
 
-    fn create_price(element: ElementRef) -> Result<Price, RetailerError> {
+    fn create_price(&self, element: ElementRef) -> Result<Price, RetailerError> {
         // <span data-product-non-sale-price-without-tax="" class="price price--non-sale"> $2,399.95 </span>
         // <span data-product-price-without-tax="" class="price price--withoutTax">$1,899.95</span>
 
         let main_price_el = extract_element_from_element(element, "span.price--withoutTax")?;
-        let main_price = price_to_cents(element_to_text(main_price_el))?;
-
-        let mut price = Price {
-            regular_price: main_price,
-            sale_price: None,
-        };
+        let main_price =
+            Money::parse_with_default(&element_to_text(main_price_el), self.get_currency())?;
 
         if let Ok(non_sale_price_el) = extract_element_from_element(element, "span.price--non-sale")
         {
-            let non_sale_price = price_to_cents(element_to_text(non_sale_price_el))?;
+            let non_sale_price = Money::parse_with_default(
+                &element_to_text(non_sale_price_el),
+                self.get_currency(),
+            )?;
 
-            price.sale_price = Some(price.regular_price);
-            price.regular_price = non_sale_price;
+            return Ok(Price::new(non_sale_price).with_sale_price(main_price));
         };
 
-        Ok(price)
+        Ok(Price::new(main_price))
+    }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let link_el = extract_element_from_element(product, "a.image-link.desktop")?;
+        let img_name_el = extract_element_from_element(link_el, "img.primary")?;
+
+        let link = element_extract_attr(link_el, "href")?;
+        let name = element_extract_attr(img_name_el, "title")?;
+        let image_link = element_extract_attr(img_name_el, "data-src")?;
+
+        let price = self.create_price(product)?;
+
+        let new_result = CrawlResult::new(
+            name,
+            link,
+            price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image_link);
+
+        Ok(Some(new_result))
     }
 }
 
 impl HtmlRetailerSuper for FirearmsOutletCanada {}
 
+register_retailer!(
+    Html,
+    FirearmsOutletCanada,
+    RetailerInfo {
+        display_name: "Firearms Outlet Canada",
+        homepage: "https://firearmsoutletcanada.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::BigCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for FirearmsOutletCanada {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::FirearmsOutletCanada
@@ -81,35 +125,17 @@ impl HtmlRetailer for FirearmsOutletCanada {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
         let product_selector = Selector::parse("ul.productGrid > li.product").unwrap();
 
         for product in html.select(&product_selector) {
-            let link_el = extract_element_from_element(product, "a.image-link.desktop")?;
-            let img_name_el = extract_element_from_element(link_el, "img.primary")?;
-
-            let link = element_extract_attr(link_el, "href")?;
-            let name = element_extract_attr(img_name_el, "title")?;
-            let image_link = element_extract_attr(img_name_el, "data-src")?;
-
-            let price = Self::create_price(product)?;
-
-            let new_result = CrawlResult::new(
-                name,
-                link,
-                price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image_link);
-
-            results.push(new_result);
+            results.record(self.parse_product(product, search_term));
         }
 
         Ok(results)
@@ -152,43 +178,7 @@ impl HtmlRetailer for FirearmsOutletCanada {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
-        let html = Html::parse_document(response);
-
-        let dropdown_option_selector =
-            Selector::parse("ul#facetedSearch-navList--bool > li > a").unwrap();
-
-        let mut in_stock_element: Option<ElementRef> = None;
-
-        for dropdown_option in html.select(&dropdown_option_selector) {
-            let text = element_to_text(dropdown_option);
-
-            debug!("text: {}", text);
-
-            if text.contains("In Stock") {
-                in_stock_element = Some(extract_element_from_element(dropdown_option, "span")?);
-                break;
-            }
-        }
-
-        let Some(unwrapped_in_stock_el) = in_stock_element else {
-            return Ok(0);
-        };
-
-        let mut in_stock_text = element_to_text(unwrapped_in_stock_el);
-        // the in stock text has () around it, like "(62)", use .replace()
-        // because I don't want to deal with array slicing or regex
-        in_stock_text = in_stock_text.replace("(", "").replace(")", "");
-
-        let Ok(in_stock_count) = in_stock_text.parse::<u64>() else {
-            let message = format!("Failed to parse {in_stock_text} into a number");
-
-            error!(message);
-
-            return Err(RetailerError::GeneralError(message));
-        };
-
-        // for some reason, each page returns a max of exactly 52 items
-        Ok(in_stock_count / 52)
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::OffsetLimit { limit: PAGE_SIZE }
     }
 }
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
+use common::{
+    money::Currency,
+    result::enums::{Category, RetailerName},
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
@@ -9,7 +10,8 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
         ecommerce::{WooCommerce, WooCommerceBuilder},
         html::extract_element_from_element,
@@ -49,6 +51,21 @@ impl G4CGunStore {
 
 impl HtmlRetailerSuper for G4CGunStore {}
 
+register_retailer!(
+    Html,
+    G4CGunStore,
+    RetailerInfo {
+        display_name: "G4C Gun Store",
+        homepage: "https://g4cgunstore.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for G4CGunStore {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::G4CGunStore
@@ -76,10 +93,10 @@ impl HtmlRetailer for G4CGunStore {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
@@ -95,13 +112,11 @@ impl HtmlRetailer for G4CGunStore {
                 break;
             }
 
-            let result = woocommerce_helper.parse_product(
+            results.record(woocommerce_helper.parse_product(
                 product,
                 self.get_retailer_name(),
                 search_term.category,
-            )?;
-
-            results.push(result);
+            ));
         }
 
         Ok(results)
@@ -131,7 +146,7 @@ impl HtmlRetailer for G4CGunStore {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let html = Html::parse_document(response);
         let root_element = html.root_element();
 
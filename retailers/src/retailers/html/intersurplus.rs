@@ -1,7 +1,11 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use serde::Deserialize;
@@ -9,8 +13,11 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
-    utils::conversions::price_to_cents,
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
+        UrlRules,
+    },
 };
 
 #[derive(Deserialize)]
@@ -29,6 +36,7 @@ struct Product {
 
 #[derive(Deserialize)]
 struct Variant {
+    id: u64,
     title: String,
     available: bool,
     price: String,
@@ -61,14 +69,98 @@ impl InterSurplus {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// Parses all available variants of a product
+    fn parse_product(
+        &self,
+        product: Product,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Vec<CrawlResult>, RetailerError> {
+        let mut results: Vec<CrawlResult> = Vec::new();
+
+        let image = match product.images.first() {
+            Some(image_obj) => image_obj.src.clone(),
+            None => DEFAULT_IMAGE.to_string(),
+        };
+
+        for variant in product.variants {
+            if !variant.available {
+                continue;
+            }
+
+            let mut title = product.title.clone();
+
+            // here's hoping that this NEVER changes
+            // otherwise I'm going to end up with a ton of random text
+            if variant.title.to_lowercase() != "default title" {
+                title = format!("{title} - {}", variant.title);
+            }
+
+            let current_price = Money::parse_with_default(&variant.price, self.get_currency())?;
+
+            let price = match variant.compare_at_price {
+                Some(regular_price) => Price::new(Money::parse_with_default(
+                    &regular_price,
+                    self.get_currency(),
+                )?)
+                .with_sale_price(current_price),
+                None => Price::new(current_price),
+            };
+
+            let url = format!(
+                "{}/{}",
+                PRODUCT_URL.replace("{category}", &search_term.term.to_string()),
+                product.handle.clone()
+            );
+
+            let new_result = CrawlResult::new(
+                title,
+                url,
+                price,
+                self.get_retailer_name(),
+                search_term.category,
+            )
+            .with_image_url(image.clone())
+            // variants share the product's URL, so they're told apart by their own ID
+            .with_sku(variant.id.to_string());
+
+            results.push(new_result);
+        }
+
+        Ok(results)
+    }
 }
 
 impl HtmlRetailerSuper for InterSurplus {}
 
+register_retailer!(
+    Html,
+    InterSurplus,
+    RetailerInfo {
+        display_name: "InterSurplus",
+        homepage: "https://intersurplus.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::Shopify],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for InterSurplus {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::InterSurplus
     }
+
+    // product URLs are built from the collection being crawled, so a product
+    // in more than one collection would otherwise end up with more than one URL
+    fn get_url_rules(&self) -> UrlRules {
+        UrlRules {
+            strip_collection_path: true,
+            ..UrlRules::default()
+        }
+    }
 }
 
 #[async_trait]
@@ -92,59 +184,15 @@ impl HtmlRetailer for InterSurplus {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let api_response = serde_json::from_str::<ApiResponse>(response)?;
 
         for product in api_response.products {
-            let image = match product.images.first() {
-                Some(image_obj) => image_obj.src.clone(),
-                None => DEFAULT_IMAGE.to_string(),
-            };
-
-            for variant in product.variants {
-                if !variant.available {
-                    continue;
-                }
-
-                let mut title = product.title.clone();
-
-                // here's hoping that this NEVER changes
-                // otherwise I'm going to end up with a ton of random text
-                if variant.title.to_lowercase() != "default title" {
-                    title = format!("{title} - {}", variant.title);
-                }
-
-                let mut price = Price {
-                    regular_price: price_to_cents(variant.price)?,
-                    sale_price: None,
-                };
-
-                if let Some(regular_price) = variant.compare_at_price {
-                    price.sale_price = Some(price.regular_price);
-                    price.regular_price = price_to_cents(regular_price)?;
-                };
-
-                let url = format!(
-                    "{}/{}",
-                    PRODUCT_URL.replace("{category}", &search_term.term.to_string()),
-                    product.handle.clone()
-                );
-
-                let new_result = CrawlResult::new(
-                    title,
-                    url,
-                    price,
-                    self.get_retailer_name(),
-                    search_term.category,
-                )
-                .with_image_url(image.clone());
-
-                results.push(new_result);
-            }
+            results.record_all(self.parse_product(product, search_term));
         }
 
         Ok(results)
@@ -191,13 +239,14 @@ impl HtmlRetailer for InterSurplus {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    // variants turn into their own results, so the page size has to come from the response
+    fn is_last_page(&self, response: &str) -> Result<bool, RetailerError> {
         let products = serde_json::from_str::<ApiResponse>(response)?;
 
-        if products.products.len() < 250 {
-            return Ok(0);
-        }
+        Ok(products.products.len() < 250)
+    }
 
-        Ok(u64::MAX)
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::UntilEmpty
     }
 }
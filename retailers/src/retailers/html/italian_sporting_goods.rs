@@ -1,7 +1,11 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
@@ -9,11 +13,11 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
-    utils::{
-        conversions::price_to_cents,
-        html::{element_extract_attr, element_to_text, extract_element_from_element},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
     },
+    utils::html::{element_extract_attr, element_to_text, extract_element_from_element},
 };
 
 const ITEMS_PER_PAGE: u64 = 25;
@@ -32,32 +36,81 @@ impl ItalianSportingGoods {
         Self {}
     }
 
-    fn parse_prices(element: ElementRef) -> Result<Price, RetailerError> {
+    fn parse_prices(&self, element: ElementRef) -> Result<Price, RetailerError> {
         let final_price_el = extract_element_from_element(
             element,
             "span.price-wrapper[data-price-type=finalPrice] > span",
         )?;
-        let final_price = price_to_cents(element_to_text(final_price_el))?;
-
-        let mut price = Price {
-            regular_price: final_price,
-            sale_price: None,
-        };
+        let final_price =
+            Money::parse_with_default(&element_to_text(final_price_el), self.get_currency())?;
 
         if let Ok(old_price_element) = extract_element_from_element(
             element,
             "span.price-wrapper[data-price-type=oldPrice] > span",
         ) {
-            price.regular_price = price_to_cents(element_to_text(old_price_element))?;
-            price.sale_price = Some(final_price);
+            let old_price = Money::parse_with_default(
+                &element_to_text(old_price_element),
+                self.get_currency(),
+            )?;
+
+            return Ok(Price::new(old_price).with_sale_price(final_price));
         };
 
-        Ok(price)
+        Ok(Price::new(final_price))
+    }
+
+    fn parse_product(
+        &self,
+        element: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let details_element = extract_element_from_element(element, "div.product-item-details")?;
+        let link_element = extract_element_from_element(details_element, "a.product-item-link")?;
+
+        if let Ok(data_bind_attr) = element_extract_attr(link_element, "data-bind")
+            && !data_bind_attr.is_empty()
+        {
+            return Ok(None);
+        }
+
+        let url = element_extract_attr(link_element, "href")?;
+        let name = element_to_text(link_element);
+        let price = self.parse_prices(details_element)?;
+
+        let image_element =
+            extract_element_from_element(element, "a.product-item-photo img.product-image-photo")?;
+        let image_url = element_extract_attr(image_element, "src")?;
+
+        let new_result = CrawlResult::new(
+            name,
+            url,
+            price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image_url);
+
+        Ok(Some(new_result))
     }
 }
 
 impl HtmlRetailerSuper for ItalianSportingGoods {}
 
+register_retailer!(
+    Html,
+    ItalianSportingGoods,
+    RetailerInfo {
+        display_name: "Italian Sporting Goods",
+        homepage: "https://www.italiansportinggoods.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::Magento],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for ItalianSportingGoods {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::ItalianSportingGoods
@@ -83,47 +136,17 @@ impl HtmlRetailer for ItalianSportingGoods {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
         let product_selector = Selector::parse("div.product-item-info").unwrap();
 
         for element in html.select(&product_selector) {
-            let details_element =
-                extract_element_from_element(element, "div.product-item-details")?;
-            let link_element =
-                extract_element_from_element(details_element, "a.product-item-link")?;
-
-            if let Ok(data_bind_attr) = element_extract_attr(link_element, "data-bind")
-                && !data_bind_attr.is_empty()
-            {
-                continue;
-            }
-
-            let url = element_extract_attr(link_element, "href")?;
-            let name = element_to_text(link_element);
-            let price = Self::parse_prices(details_element)?;
-
-            let image_element = extract_element_from_element(
-                element,
-                "a.product-item-photo img.product-image-photo",
-            )?;
-            let image_url = element_extract_attr(image_element, "src")?;
-
-            let new_result = CrawlResult::new(
-                name,
-                url,
-                price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image_url);
-
-            results.push(new_result);
+            results.record(self.parse_product(element, search_term));
         }
 
         Ok(results)
@@ -160,7 +183,7 @@ impl HtmlRetailer for ItalianSportingGoods {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let html = Html::parse_document(response);
 
         let item_counts = Selector::parse("p#toolbar-amount > span.toolbar-number").unwrap();
@@ -174,4 +197,8 @@ impl HtmlRetailer for ItalianSportingGoods {
             Ok(0)
         }
     }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageCount
+    }
 }
@@ -1,17 +1,22 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use tracing::{debug, error};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -30,10 +35,72 @@ impl LeverArms {
     pub fn new() -> Self {
         Self {}
     }
+
+    fn parse_product(
+        &self,
+        element: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let title_element =
+            extract_element_from_element(element, "h2.woocommerce-loop-product__title")?;
+        let image_element =
+            extract_element_from_element(element, "img.attachment-woocommerce_thumbnail");
+
+        // what the hell lever arms
+        // you have a product that is listed that has no listed
+        // price that you let people add to their carts
+        let Ok(price_element) =
+            extract_element_from_element(element, "span.woocommerce-Price-amount")
+        else {
+            return Ok(None);
+        };
+
+        let link = element_extract_attr(element, "href")?;
+        let title = element_to_text(title_element);
+        let price =
+            Money::parse_with_default(&element_to_text(price_element), self.get_currency())?;
+
+        // lever arms uses a place holder element for missing images
+        let image_link = match image_element {
+            Ok(unwrapped_img_el) => element_extract_attr(unwrapped_img_el, "src")?,
+            Err(_) => "https://leverarms.com/wp-content/uploads/2021/07/placehold.jpg".to_string(),
+        };
+
+        // parsing out the gunsmithing services located under /kit
+        if link.contains("/gunsmithing/") {
+            return Ok(None);
+        }
+
+        let result = CrawlResult::new(
+            title,
+            link,
+            Price::new(price),
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image_link.to_string());
+
+        Ok(Some(result))
+    }
 }
 
 impl HtmlRetailerSuper for LeverArms {}
 
+register_retailer!(
+    Html,
+    LeverArms,
+    RetailerInfo {
+        display_name: "Lever Arms",
+        homepage: "https://leverarms.com",
+        province: Some(Province::BritishColumbia),
+        city: Some("Vancouver"),
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for LeverArms {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::LeverArms
@@ -60,60 +127,17 @@ impl HtmlRetailer for LeverArms {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let fragment = Html::parse_document(response);
 
         let product_selector = Selector::parse("a.woocommerce-LoopProduct-link").unwrap();
 
         for element in fragment.select(&product_selector) {
-            let title_element =
-                extract_element_from_element(element, "h2.woocommerce-loop-product__title")?;
-            let image_element =
-                extract_element_from_element(element, "img.attachment-woocommerce_thumbnail");
-
-            // what the hell lever arms
-            // you have a product that is listed that has no listed
-            // price that you let people add to their carts
-            let Ok(price_element) =
-                extract_element_from_element(element, "span.woocommerce-Price-amount")
-            else {
-                continue;
-            };
-
-            let link = element_extract_attr(element, "href")?;
-            let title = element_to_text(title_element);
-            let price = price_to_cents(element_to_text(price_element))?;
-
-            // lever arms uses a place holder element for missing images
-            let image_link = match image_element {
-                Ok(unwrapped_img_el) => element_extract_attr(unwrapped_img_el, "src")?,
-                Err(_) => {
-                    "https://leverarms.com/wp-content/uploads/2021/07/placehold.jpg".to_string()
-                }
-            };
-
-            // parsing out the gunsmithing services located under /kit
-            if link.contains("/gunsmithing/") {
-                continue;
-            }
-
-            let result = CrawlResult::new(
-                title,
-                link,
-                Price {
-                    regular_price: price,
-                    sale_price: None,
-                },
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image_link.to_string());
-
-            results.push(result);
+            results.record(self.parse_product(element, search_term));
         }
 
         Ok(results)
@@ -153,7 +177,7 @@ impl HtmlRetailer for LeverArms {
         ])
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let fragment = Html::parse_document(response);
         let page_number_selector =
             Selector::parse("a:not(.next):not(.prev).page-numbers, span.page-numbers").unwrap();
@@ -1,7 +1,11 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::{
     request::{Request, RequestBuilder},
@@ -13,11 +17,11 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
-    utils::{
-        conversions::price_to_cents,
-        html::{element_extract_attr, element_to_text},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
     },
+    utils::html::{element_extract_attr, element_to_text},
 };
 
 const SITEMAP: &str = "https://magdump.ca/sitemap";
@@ -63,14 +67,14 @@ impl ResponseProduct {
         self.url.clone()
     }
 
-    fn get_price(&self) -> Result<Price, RetailerError> {
-        let regular = price_to_cents(self.regular_price_amount.to_string())?;
-        let sale = price_to_cents(self.price_amount.to_string())?;
+    fn get_price(&self, currency: Currency) -> Result<Price, RetailerError> {
+        let regular = Money::from_f32(self.regular_price_amount, currency)?;
+        let sale = Money::from_f32(self.price_amount, currency)?;
 
-        Ok(Price {
-            regular_price: regular,
-            sale_price: if regular != sale { Some(sale) } else { None },
-        })
+        match regular != sale {
+            true => Ok(Price::new(regular).with_sale_price(sale)),
+            false => Ok(Price::new(regular)),
+        }
     }
 
     fn get_image(&self) -> String {
@@ -112,6 +116,21 @@ impl MagDump {
 
 impl HtmlRetailerSuper for MagDump {}
 
+register_retailer!(
+    Html,
+    MagDump,
+    RetailerInfo {
+        display_name: "MagDump",
+        homepage: "https://magdump.ca",
+        province: None,
+        city: None,
+        platforms: &[Platform::PrestaShop],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 #[async_trait]
 impl Retailer for MagDump {
     // Marstar mixes and matches items in several categories
@@ -216,10 +235,10 @@ impl HtmlRetailer for MagDump {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let products = serde_json::from_str::<Response>(response)?;
         for product in products.products {
@@ -227,16 +246,18 @@ impl HtmlRetailer for MagDump {
                 continue;
             }
 
-            let result = CrawlResult::new(
-                product.get_name(),
-                product.get_url(),
-                product.get_price()?,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(product.get_image());
-
-            results.push(result);
+            let result = product.get_price(self.get_currency()).map(|price| {
+                CrawlResult::new(
+                    product.get_name(),
+                    product.get_url(),
+                    price,
+                    self.get_retailer_name(),
+                    search_term.category,
+                )
+                .with_image_url(product.get_image())
+            });
+
+            results.record(result);
         }
 
         Ok(results)
@@ -246,9 +267,13 @@ impl HtmlRetailer for MagDump {
         self.query.to_owned()
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let products = serde_json::from_str::<Response>(response)?;
 
         Ok(products.get_max_pages())
     }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageCount
+    }
 }
@@ -1,7 +1,8 @@
 use async_trait::async_trait;
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
+use common::{
+    money::Currency,
+    result::enums::{Category, RetailerName},
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{Html, Selector};
@@ -9,7 +10,10 @@ use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
+    },
     utils::{ecommerce::WooCommerceBuilder, generic_sitemap::get_search_queries},
 };
 
@@ -66,6 +70,21 @@ impl Marstar {
 
 impl HtmlRetailerSuper for Marstar {}
 
+register_retailer!(
+    Html,
+    Marstar,
+    RetailerInfo {
+        display_name: "Marstar",
+        homepage: "https://marstar.ca",
+        province: Some(Province::Ontario),
+        city: Some("Vankleek Hill"),
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 #[async_trait]
 impl Retailer for Marstar {
     async fn init(&mut self) -> Result<(), RetailerError> {
@@ -96,10 +115,10 @@ impl HtmlRetailer for Marstar {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
         let product_selector =
@@ -113,11 +132,11 @@ impl HtmlRetailer for Marstar {
             .build();
 
         for product in html.select(&product_selector) {
-            results.push(woocommerce_helper.parse_product(
+            results.record(woocommerce_helper.parse_product(
                 product,
                 self.get_retailer_name(),
                 search_term.category,
-            )?);
+            ));
         }
 
         Ok(results)
@@ -127,7 +146,7 @@ impl HtmlRetailer for Marstar {
         self.search_terms.clone()
     }
 
-    fn get_num_pages(&self, _response: &String) -> Result<u64, RetailerError> {
-        Ok(0)
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::SinglePage
     }
 }
@@ -1,15 +1,18 @@
 use async_trait::async_trait;
+use common::money::Currency;
 use common::result::{
     base::CrawlResult,
     enums::{Category, RetailerName},
 };
+use common::retailer_info::{Platform, RetailerInfo};
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
 use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
         ecommerce::{WooCommerce, WooCommerceBuilder, WooCommerceNested},
         html::{element_extract_attr, element_to_text, extract_element_from_element},
@@ -37,10 +40,65 @@ impl RangeviewSports {
     fn is_out_of_stock(element: ElementRef) -> bool {
         extract_element_from_element(element, "span.out-of-stock.product-label").is_ok()
     }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+        woocommerce_helper: &mut WooCommerce,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        // leave this outside and not inside if-let-Ok statement to fail on purpose
+        let price_element = extract_element_from_element(product, "span.price")?;
+        let link_element = extract_element_from_element(product, "h3.wd-entities-title > a")?;
+
+        let name = element_to_text(link_element).to_lowercase();
+
+        if BLOCKED_TITLE_TERMS
+            .iter()
+            .any(|term| name.contains(&term.to_lowercase()))
+        {
+            return Ok(None);
+        };
+
+        // rangeview does something dumb and uses a unicode dash
+        // to show case price range, instead of regular ascii
+        //
+        // so what I have in the contains below IS A UNICODE DASH
+        if element_to_text(price_element).contains("–") {
+            let link = element_extract_attr(link_element, "href")?;
+
+            woocommerce_helper.enqueue_nested_product(link, search_term.category);
+
+            return Ok(None);
+        };
+
+        let result = woocommerce_helper.parse_product(
+            product,
+            self.get_retailer_name(),
+            search_term.category,
+        )?;
+
+        Ok(Some(result))
+    }
 }
 
 impl HtmlRetailerSuper for RangeviewSports {}
 
+register_retailer!(
+    Html,
+    RangeviewSports,
+    RetailerInfo {
+        display_name: "Rangeview Sports",
+        homepage: "https://www.rangeviewsports.ca",
+        province: None,
+        city: None,
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for RangeviewSports {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::RangeviewSports
@@ -68,10 +126,10 @@ impl HtmlRetailer for RangeviewSports {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let mut woocommerce_helper = WooCommerceBuilder::default().build();
 
@@ -92,38 +150,7 @@ impl HtmlRetailer for RangeviewSports {
                 break;
             }
 
-            // leave this outside and not inside if-let-Ok statement to fail on purpose
-            let price_element = extract_element_from_element(product, "span.price")?;
-            let link_element = extract_element_from_element(product, "h3.wd-entities-title > a")?;
-
-            let name = element_to_text(link_element).to_lowercase();
-
-            if BLOCKED_TITLE_TERMS
-                .iter()
-                .any(|term| name.contains(&term.to_lowercase()))
-            {
-                continue;
-            };
-
-            // rangeview does something dumb and uses a unicode dash
-            // to show case price range, instead of regular ascii
-            //
-            // so what I have in the contains below IS A UNICODE DASH
-            if element_to_text(price_element).contains("–") {
-                let link = element_extract_attr(link_element, "href")?;
-
-                woocommerce_helper.enqueue_nested_product(link, search_term.category);
-
-                continue;
-            };
-
-            let result = woocommerce_helper.parse_product(
-                product,
-                self.get_retailer_name(),
-                search_term.category,
-            )?;
-
-            results.push(result);
+            results.record(self.parse_product(product, search_term, &mut woocommerce_helper));
         }
 
         results.extend(
@@ -179,7 +206,7 @@ impl HtmlRetailer for RangeviewSports {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let html = Html::parse_document(response);
 
         if Self::is_out_of_stock(html.root_element()) {
@@ -1,7 +1,11 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
@@ -9,9 +13,10 @@ use tracing::{debug, warn};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -46,10 +51,56 @@ impl Rdsc {
             Err(_) => false,
         }
     }
+
+    fn parse_product(
+        &self,
+        element: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        if !Self::is_in_stock(element) {
+            return Ok(None);
+        }
+
+        let title_element = extract_element_from_element(element, "a.product-item-link")?;
+        let link = element_extract_attr(title_element, "href")?;
+        let title = element_to_text(title_element);
+
+        let image_element = extract_element_from_element(element, "img.product-image-photo")?;
+        let image_url = element_extract_attr(image_element, "src")?;
+
+        let price_element = extract_element_from_element(element, "span.price-wrapper")?;
+        let price = element_extract_attr(price_element, "data-price-amount")?;
+
+        let result = CrawlResult::new(
+            title,
+            link,
+            Price::new(Money::parse_with_default(&price, self.get_currency())?),
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image_url.to_string());
+
+        Ok(Some(result))
+    }
 }
 
 impl HtmlRetailerSuper for Rdsc {}
 
+register_retailer!(
+    Html,
+    Rdsc,
+    RetailerInfo {
+        display_name: "RDSC",
+        homepage: "https://rdsc.ca",
+        province: None,
+        city: None,
+        platforms: &[Platform::Magento],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for Rdsc {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::Rdsc
@@ -76,10 +127,10 @@ impl HtmlRetailer for Rdsc {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let fragment = Html::parse_document(response);
 
@@ -87,35 +138,7 @@ impl HtmlRetailer for Rdsc {
             Selector::parse("ol.product-items > li.product-item > div.product-item-info").unwrap();
 
         for element in fragment.select(&product_selector) {
-            if !Self::is_in_stock(element) {
-                continue;
-            }
-
-            let title_element = extract_element_from_element(element, "a.product-item-link")?;
-            let link = element_extract_attr(title_element, "href")?;
-            let title = element_to_text(title_element);
-
-            let image_element = extract_element_from_element(element, "img.product-image-photo")?;
-            let image_url = element_extract_attr(image_element, "src")?;
-
-            let price_element = extract_element_from_element(element, "span.price-wrapper")?;
-            let price = element_extract_attr(price_element, "data-price-amount")?;
-
-            let price = price_to_cents(price)?;
-
-            let result = CrawlResult::new(
-                title,
-                link,
-                Price {
-                    regular_price: price,
-                    sale_price: None,
-                },
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image_url.to_string());
-
-            results.push(result);
+            results.record(self.parse_product(element, search_term));
         }
 
         Ok(results)
@@ -166,7 +189,7 @@ impl HtmlRetailer for Rdsc {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let fragment = Html::parse_document(response);
 
         // there's no way to only filter for in stock items via the website
@@ -1,7 +1,11 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::{request::Request, traits::HttpMethod};
 use scraper::{ElementRef, Html, Selector};
@@ -9,9 +13,10 @@ use serde::Serialize;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -83,28 +88,75 @@ impl ReliableGun {
         Self {}
     }
 
-    fn find_prices(element: ElementRef) -> Result<Price, RetailerError> {
+    fn find_prices(&self, element: ElementRef) -> Result<Price, RetailerError> {
         let actual_element = extract_element_from_element(element, "span.actual-price")?;
-        let actual_price = price_to_cents(element_to_text(actual_element))?;
-
-        let mut price = Price {
-            regular_price: actual_price,
-            sale_price: None,
-        };
+        let actual_price =
+            Money::parse_with_default(&element_to_text(actual_element), self.get_currency())?;
 
         if let Ok(old_price_element) = extract_element_from_element(element, "span.old-price") {
-            let old_price = price_to_cents(element_to_text(old_price_element))?;
+            let old_price = Money::parse_with_default(
+                &element_to_text(old_price_element),
+                self.get_currency(),
+            )?;
 
-            price.sale_price = Some(price.regular_price);
-            price.regular_price = old_price;
+            return Ok(Price::new(old_price).with_sale_price(actual_price));
         }
 
-        Ok(price)
+        Ok(Price::new(actual_price))
+    }
+
+    fn parse_product(
+        &self,
+        element: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let description_element = extract_element_from_element(element, "div.description")?;
+        let url_element = extract_element_from_element(element, "h2.product-title > a")?;
+        let image_element = extract_element_from_element(element, "img.product-overview-img")?;
+
+        let description = element_to_text(description_element);
+        let url_href = element_extract_attr(url_element, "href")?;
+        let name = element_to_text(url_element);
+        let image_url = element_extract_attr(image_element, "src")?;
+
+        let price = self.find_prices(element)?;
+
+        let formatted_name = match search_term.category {
+            Category::Ammunition => format!("{} {}", name, description.clone()),
+            _ => name,
+        };
+
+        let new_result = CrawlResult::new(
+            formatted_name,
+            format!("{BASE_URL}{url_href}"),
+            price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(image_url.to_string())
+        .with_description(description);
+
+        Ok(Some(new_result))
     }
 }
 
 impl HtmlRetailerSuper for ReliableGun {}
 
+register_retailer!(
+    Html,
+    ReliableGun,
+    RetailerInfo {
+        display_name: "Reliable Gun",
+        homepage: "https://www.reliablegun.com",
+        province: Some(Province::BritishColumbia),
+        city: Some("Vancouver"),
+        platforms: &[Platform::NopCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for ReliableGun {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::ReliableGun
@@ -131,41 +183,15 @@ impl HtmlRetailer for ReliableGun {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let fragment = Html::parse_document(response);
 
         for element in fragment.select(&Selector::parse("div.product-item").unwrap()) {
-            let description_element = extract_element_from_element(element, "div.description")?;
-            let url_element = extract_element_from_element(element, "h2.product-title > a")?;
-            let image_element = extract_element_from_element(element, "img.product-overview-img")?;
-
-            let description = element_to_text(description_element);
-            let url_href = element_extract_attr(url_element, "href")?;
-            let name = element_to_text(url_element);
-            let image_url = element_extract_attr(image_element, "src")?;
-
-            let price = Self::find_prices(element)?;
-
-            let formatted_name = match search_term.category {
-                Category::Ammunition => format!("{} {}", name, description.clone()),
-                _ => name,
-            };
-
-            let new_result = CrawlResult::new(
-                formatted_name,
-                format!("{BASE_URL}{url_href}"),
-                price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(image_url.to_string())
-            .with_description(description);
-
-            results.push(new_result);
+            results.record(self.parse_product(element, search_term));
         }
 
         Ok(results)
@@ -211,7 +237,7 @@ impl HtmlRetailer for ReliableGun {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let html = Html::parse_fragment(response);
         let page_selector = Selector::parse("div.pager > div > ul > li.individual-page").unwrap();
         let mut page_links = html.select(&page_selector);
@@ -1,14 +1,17 @@
 use async_trait::async_trait;
+use common::money::Currency;
 use common::result::{
     base::CrawlResult,
     enums::{Category, RetailerName},
 };
+use common::retailer_info::{Platform, RetailerInfo};
 use crawler::request::{Request, RequestBuilder};
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
         ecommerce::{BigCommerce, BigCommerceNested},
         html::{element_extract_attr, element_to_text, extract_element_from_element},
@@ -30,10 +33,64 @@ impl SJHardware {
     pub fn new() -> Self {
         Self {}
     }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+        bigcommerce_helper: &mut BigCommerce,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let cart_button =
+            extract_element_from_element(product, "div.card-text.add-to-cart-button")?;
+        let button_text = element_to_text(cart_button).to_lowercase();
+
+        let price_element = extract_element_from_element(
+            product,
+            "div.price-section > span.price.price--withoutTax",
+        )?;
+        let price_text = element_to_text(price_element);
+
+        if button_text.contains("choose options") || price_text.contains("-") {
+            let title_element = extract_element_from_element(product, "h4.card-title > a")?;
+            let url = element_extract_attr(title_element, "href")?;
+
+            // TODO: fix this, sj hardware has a product that is in stock, but
+            // does not actually go anywhere when visited (it 404s)
+            if !url.contains("https://sjhardware.com/6-israeli-bandages") {
+                let _ = bigcommerce_helper
+                    .enqueue_nested_product_element(product, search_term.category);
+            }
+        } else if button_text.contains("add to cart") {
+            let result = bigcommerce_helper.parse_product(
+                product,
+                self.get_retailer_name(),
+                search_term.category,
+            )?;
+
+            return Ok(Some(result));
+        }
+
+        Ok(None)
+    }
 }
 
 impl HtmlRetailerSuper for SJHardware {}
 
+register_retailer!(
+    Html,
+    SJHardware,
+    RetailerInfo {
+        display_name: "S&J Hardware",
+        homepage: "https://sjhardware.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::BigCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for SJHardware {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::SJHardware
@@ -58,12 +115,12 @@ impl HtmlRetailer for SJHardware {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
+    ) -> Result<ParsedPage, RetailerError> {
         let mut bigcommerce_helper = BigCommerce::new();
 
-        let mut results: Vec<CrawlResult> = Vec::new();
+        let mut results = ParsedPage::new();
 
         let products = {
             let html = Html::parse_document(response);
@@ -77,35 +134,7 @@ impl HtmlRetailer for SJHardware {
             let product_inner = Html::parse_document(&html_doc);
             let product = product_inner.root_element();
 
-            let cart_button =
-                extract_element_from_element(product, "div.card-text.add-to-cart-button")?;
-            let button_text = element_to_text(cart_button).to_lowercase();
-
-            let price_element = extract_element_from_element(
-                product,
-                "div.price-section > span.price.price--withoutTax",
-            )?;
-            let price_text = element_to_text(price_element);
-
-            if button_text.contains("choose options") || price_text.contains("-") {
-                let title_element = extract_element_from_element(product, "h4.card-title > a")?;
-                let url = element_extract_attr(title_element, "href")?;
-
-                // TODO: fix this, sj hardware has a product that is in stock, but
-                // does not actually go anywhere when visited (it 404s)
-                if !url.contains("https://sjhardware.com/6-israeli-bandages") {
-                    let _ = bigcommerce_helper
-                        .enqueue_nested_product_element(product, search_term.category);
-                }
-            } else if button_text.contains("add to cart") {
-                let result = bigcommerce_helper.parse_product(
-                    product,
-                    self.get_retailer_name(),
-                    search_term.category,
-                )?;
-
-                results.push(result);
-            }
+            results.record(self.parse_product(product, search_term, &mut bigcommerce_helper));
         }
 
         results.extend(
@@ -152,7 +181,7 @@ impl HtmlRetailer for SJHardware {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         BigCommerce::parse_max_pages(response)
     }
 }
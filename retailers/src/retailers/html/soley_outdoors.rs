@@ -1,9 +1,13 @@
 use std::{collections::HashMap, time::Duration};
 
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::{
     request::{Request, RequestBuilder},
@@ -16,9 +20,10 @@ use tracing::{debug, warn};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         generic_sitemap::get_search_queries,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
@@ -103,18 +108,16 @@ impl SoleyOutdoors {
         }
     }
 
-    fn get_price(api_price: ApiResponsePrice) -> Result<Price, RetailerError> {
-        let mut price = Price {
-            regular_price: price_to_cents(api_price.price.to_string())?,
-            sale_price: None,
-        };
+    fn get_price(&self, api_price: ApiResponsePrice) -> Result<Price, RetailerError> {
+        let current_price = Money::from_f32(api_price.price, self.get_currency())?;
 
         if api_price.price_old != 0.0 {
-            price.sale_price = Some(price.regular_price);
-            price.regular_price = price_to_cents(api_price.price_old.to_string())?;
+            let old_price = Money::from_f32(api_price.price_old, self.get_currency())?;
+
+            return Ok(Price::new(old_price).with_sale_price(current_price));
         }
 
-        Ok(price)
+        Ok(Price::new(current_price))
     }
 
     // logic copied from woocommerce parser
@@ -142,61 +145,96 @@ impl SoleyOutdoors {
         ))
     }
 
+    fn get_product_pair(&self, product: ElementRef) -> Result<Option<ProductPair>, RetailerError> {
+        let wrapper = extract_element_from_element(product, "div")?;
+
+        let Ok(data_link) = element_extract_attr(wrapper, "data-json") else {
+            warn!("Found link with no product URL: {wrapper:?}");
+            return Ok(None);
+        };
+
+        if !data_link.starts_with(PRODUCT_BASE_URL) {
+            warn!("Link is not same as retailer: {data_link}");
+            return Ok(None);
+        }
+
+        let image_link = self.get_image(wrapper)?;
+
+        Ok(Some(ProductPair {
+            url: data_link.clone(),
+            image_url: image_link,
+        }))
+    }
+
     async fn parse_links(
         &self,
         product_links: Vec<ProductPair>,
         search_term: &HtmlSearchQuery,
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
+
+        for product in product_links {
+            results.record_all(self.parse_link(product, search_term).await);
+        }
+
+        Ok(results)
+    }
+
+    /// Parses a single product, which may expand into multiple variants
+    async fn parse_link(
+        &self,
+        product: ProductPair,
+        search_term: &HtmlSearchQuery,
     ) -> Result<Vec<CrawlResult>, RetailerError> {
         let mut results: Vec<CrawlResult> = Vec::new();
 
-        for product in product_links {
-            let request = RequestBuilder::new().set_url(product.url.clone()).build();
-            let crawler = UnprotectedCrawler::make_web_request(request).await?;
+        let request = RequestBuilder::new().set_url(product.url.clone()).build();
+        let crawler = UnprotectedCrawler::make_web_request(request).await?;
 
-            let parsed_product = serde_json::from_str::<ApiResponse>(&crawler.body)?.product;
+        let parsed_product = serde_json::from_str::<ApiResponse>(&crawler.body)?.product;
 
-            // wait 2 seconds instead of default 10 since
-            // their robots.txt seems to be fine with 2
-            // (not that I would have listened anyways)
-            sleep(Duration::from_secs(2)).await;
+        // wait 2 seconds instead of default 10 since
+        // their robots.txt seems to be fine with 2
+        // (not that I would have listened anyways)
+        sleep(Duration::from_secs(2)).await;
 
-            if !parsed_product.stock.available {
-                continue;
-            }
+        if !parsed_product.stock.available {
+            return Ok(results);
+        }
+
+        let product_url = product.url.replace("?format=json", "");
 
-            let product_url = product.url.replace("?format=json", "");
+        if parsed_product.variants.is_empty() {
+            let new_result = CrawlResult::new(
+                parsed_product.title,
+                product_url,
+                self.get_price(parsed_product.price)?,
+                self.get_retailer_name(),
+                search_term.category,
+            )
+            .with_image_url(product.image_url);
 
-            if parsed_product.variants.len() == 0 {
-                let new_result = CrawlResult::new(
-                    parsed_product.title,
-                    product_url,
-                    Self::get_price(parsed_product.price)?,
-                    self.get_retailer_name(),
-                    search_term.category,
-                )
-                .with_image_url(product.image_url);
+            results.push(new_result);
 
-                results.push(new_result);
+            return Ok(results);
+        }
 
+        for nested_product in parsed_product.variants {
+            if !nested_product.stock.available {
                 continue;
             }
 
-            for nested_product in parsed_product.variants {
-                if !nested_product.stock.available {
-                    continue;
-                }
-
-                let new_result = CrawlResult::new(
-                    format!("{} - {}", parsed_product.title, nested_product.title),
-                    product_url.clone(),
-                    Self::get_price(nested_product.price)?,
-                    self.get_retailer_name(),
-                    search_term.category,
-                )
-                .with_image_url(product.image_url.clone());
-
-                results.push(new_result);
-            }
+            let new_result = CrawlResult::new(
+                format!("{} - {}", parsed_product.title, nested_product.title),
+                product_url.clone(),
+                self.get_price(nested_product.price)?,
+                self.get_retailer_name(),
+                search_term.category,
+            )
+            .with_image_url(product.image_url.clone())
+            .as_variant();
+
+            results.push(new_result);
         }
 
         Ok(results)
@@ -205,6 +243,21 @@ impl SoleyOutdoors {
 
 impl HtmlRetailerSuper for SoleyOutdoors {}
 
+register_retailer!(
+    Html,
+    SoleyOutdoors,
+    RetailerInfo {
+        display_name: "Solely Outdoors",
+        homepage: "https://www.solelyoutdoors.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::Lightspeed],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 #[async_trait]
 impl Retailer for SoleyOutdoors {
     fn get_retailer_name(&self) -> RetailerName {
@@ -268,9 +321,9 @@ impl HtmlRetailer for SoleyOutdoors {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
+    ) -> Result<ParsedPage, RetailerError> {
         let products = {
             let html = Html::parse_document(response);
             let product_selector =
@@ -280,40 +333,30 @@ impl HtmlRetailer for SoleyOutdoors {
                 .collect::<Vec<_>>()
         };
 
+        let mut results = ParsedPage::new();
         let mut product_links: Vec<ProductPair> = Vec::new();
 
         for html_doc in products {
             let product_inner = Html::parse_fragment(&html_doc);
             let product = product_inner.root_element();
 
-            let wrapper = extract_element_from_element(product, "div")?;
-
-            let Ok(data_link) = element_extract_attr(wrapper, "data-json") else {
-                warn!("Found link with no product URL: {wrapper:?}");
-                continue;
-            };
-
-            if !data_link.starts_with(PRODUCT_BASE_URL) {
-                warn!("Link is not same as retailer: {data_link}");
-                continue;
+            match self.get_product_pair(product) {
+                Ok(Some(product_pair)) => product_links.push(product_pair),
+                Ok(None) => {}
+                Err(err) => results.push_error(err),
             }
-
-            let image_link = self.get_image(wrapper)?;
-
-            product_links.push(ProductPair {
-                url: data_link.clone(),
-                image_url: image_link,
-            });
         }
 
-        Ok(self.parse_links(product_links, search_term).await?)
+        results.extend(self.parse_links(product_links, search_term).await?);
+
+        Ok(results)
     }
 
     fn get_search_terms(&self) -> Vec<HtmlSearchQuery> {
         self.search_queries.clone()
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let fragment = Html::parse_document(response);
         let page_number_selector =
             Selector::parse("div.paginate > ul > li:not(.active).number > a").unwrap();
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
 use base64::{Engine, prelude::BASE64_STANDARD};
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
+use common::{
+    money::Currency,
+    result::enums::{Category, RetailerName},
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::{
     request::{Request, RequestBuilder},
@@ -14,7 +15,10 @@ use tracing::{debug, trace};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
+    },
     utils::{
         ecommerce::{WooCommerce, WooCommerceBuilder},
         generic_sitemap::get_search_queries,
@@ -176,6 +180,21 @@ impl Tenda {
 
 impl HtmlRetailerSuper for Tenda {}
 
+register_retailer!(
+    Html,
+    Tenda,
+    RetailerInfo {
+        display_name: "Tenda Canada",
+        homepage: "https://www.gotenda.com",
+        province: Some(Province::Ontario),
+        city: Some("Richmond Hill"),
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 #[async_trait]
 impl Retailer for Tenda {
     async fn init(&mut self) -> Result<(), RetailerError> {
@@ -217,10 +236,10 @@ impl HtmlRetailer for Tenda {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let fragment = Html::parse_document(response);
 
@@ -233,11 +252,11 @@ impl HtmlRetailer for Tenda {
             .build();
 
         for element in fragment.select(&product_selector) {
-            results.push(woocommerce_helper.parse_product(
+            results.record(woocommerce_helper.parse_product(
                 element,
                 self.get_retailer_name(),
                 search_term.category,
-            )?);
+            ));
         }
 
         Ok(results)
@@ -248,7 +267,11 @@ impl HtmlRetailer for Tenda {
         self.search_terms.clone()
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         WooCommerce::parse_max_pages(response)
     }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageCount
+    }
 }
@@ -1,14 +1,16 @@
 use async_trait::async_trait;
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
+use common::{
+    money::Currency,
+    result::enums::{Category, RetailerName},
+    retailer_info::{Platform, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use scraper::{ElementRef, Html, Selector};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
         ecommerce::BigCommerce,
         html::{element_to_text, extract_element_from_element},
@@ -44,6 +46,21 @@ impl TheAmmoSource {
 
 impl HtmlRetailerSuper for TheAmmoSource {}
 
+register_retailer!(
+    Html,
+    TheAmmoSource,
+    RetailerInfo {
+        display_name: "The Ammo Source",
+        homepage: "https://theammosource.com",
+        province: None,
+        city: None,
+        platforms: &[Platform::BigCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for TheAmmoSource {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::TheAmmoSource
@@ -68,27 +85,25 @@ impl HtmlRetailer for TheAmmoSource {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
+    ) -> Result<ParsedPage, RetailerError> {
         let bigcommerce_helper = BigCommerce::new();
-        let mut results: Vec<CrawlResult> = Vec::new();
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
         let product_selector = Selector::parse("ul.productGrid > li.product").unwrap();
 
         for product in html.select(&product_selector) {
-            if Self::is_sticker_draw(product)? {
-                continue;
+            match Self::is_sticker_draw(product) {
+                Ok(true) => continue,
+                Ok(false) => results.record(bigcommerce_helper.parse_product(
+                    product,
+                    self.get_retailer_name(),
+                    search_term.category,
+                )),
+                Err(err) => results.push_error(err),
             }
-
-            let result = bigcommerce_helper.parse_product(
-                product,
-                self.get_retailer_name(),
-                search_term.category,
-            )?;
-
-            results.push(result);
         }
 
         Ok(results)
@@ -141,7 +156,7 @@ impl HtmlRetailer for TheAmmoSource {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         BigCommerce::parse_max_pages(response)
     }
 }
@@ -1,7 +1,11 @@
 use async_trait::async_trait;
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
+    retailer_info::{Platform, Province, RetailerInfo},
 };
 use crawler::request::{Request, RequestBuilder};
 use regex::Regex;
@@ -10,9 +14,10 @@ use tracing::{debug, warn};
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, ParsedPage, Retailer},
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -48,12 +53,7 @@ impl Tillsonburg {
         Ok(element_to_text(cart_button) == "Out Of Stock")
     }
 
-    fn get_price(element: ElementRef) -> Result<Price, RetailerError> {
-        let mut price = Price {
-            regular_price: 0,
-            sale_price: None,
-        };
-
+    fn get_price(&self, element: ElementRef) -> Result<Price, RetailerError> {
         let price_element = extract_element_from_element(element, "p.price")?;
 
         match extract_element_from_element(price_element, "span.price-new") {
@@ -61,15 +61,20 @@ impl Tillsonburg {
                 let regular_element =
                     extract_element_from_element(price_element, "span.price-old")?;
 
-                price.regular_price = price_to_cents(element_to_text(regular_element))?;
-                price.sale_price = Some(price_to_cents(element_to_text(sale_element))?);
-            }
-            Err(_) => {
-                price.regular_price = price_to_cents(element_to_text(price_element))?;
+                Ok(Price::new(Money::parse_with_default(
+                    &element_to_text(regular_element),
+                    self.get_currency(),
+                )?)
+                .with_sale_price(Money::parse_with_default(
+                    &element_to_text(sale_element),
+                    self.get_currency(),
+                )?))
             }
+            Err(_) => Ok(Price::new(Money::parse_with_default(
+                &element_to_text(price_element),
+                self.get_currency(),
+            )?)),
         }
-
-        Ok(price)
     }
 
     fn clean_url(url: impl Into<String>) -> String {
@@ -81,10 +86,50 @@ impl Tillsonburg {
 
         clean_url.to_string()
     }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let image_element = extract_element_from_element(product, "div.image > a > img")?;
+        let image_url = element_extract_attr(image_element, "src")?;
+
+        let title_element = extract_element_from_element(product, "div > div.caption > h4 > a")?;
+        let name = element_to_text(title_element);
+        let url = element_extract_attr(title_element, "href")?;
+        let price = self.get_price(product)?;
+
+        let new_result = CrawlResult::new(
+            name,
+            Self::clean_url(url),
+            price,
+            self.get_retailer_name(),
+            search_term.category,
+        )
+        .with_image_url(Self::clean_url(image_url));
+
+        Ok(Some(new_result))
+    }
 }
 
 impl HtmlRetailerSuper for Tillsonburg {}
 
+register_retailer!(
+    Html,
+    Tillsonburg,
+    RetailerInfo {
+        display_name: "Tillsonburg Gun Shop",
+        homepage: "https://tillsonburggunshop.com",
+        province: Some(Province::Ontario),
+        city: Some("Tillsonburg"),
+        platforms: &[Platform::OpenCart],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for Tillsonburg {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::Tillsonburg
@@ -112,10 +157,10 @@ impl HtmlRetailer for Tillsonburg {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let html = Html::parse_document(response);
 
@@ -123,29 +168,11 @@ impl HtmlRetailer for Tillsonburg {
             Selector::parse("div.row > div.product-layout > div.product-thumb").unwrap();
 
         for product in html.select(&product_selector) {
-            if Self::is_out_of_stock(product)? {
-                break;
+            match Self::is_out_of_stock(product) {
+                Ok(true) => break,
+                Ok(false) => results.record(self.parse_product(product, search_term)),
+                Err(err) => results.push_error(err),
             }
-
-            let image_element = extract_element_from_element(product, "div.image > a > img")?;
-            let image_url = element_extract_attr(image_element, "src")?;
-
-            let title_element =
-                extract_element_from_element(product, "div > div.caption > h4 > a")?;
-            let name = element_to_text(title_element);
-            let url = element_extract_attr(title_element, "href")?;
-            let price = Self::get_price(product)?;
-
-            let new_result = CrawlResult::new(
-                name,
-                Self::clean_url(url),
-                price,
-                self.get_retailer_name(),
-                search_term.category,
-            )
-            .with_image_url(Self::clean_url(image_url));
-
-            results.push(new_result);
         }
 
         Ok(results)
@@ -181,7 +208,7 @@ impl HtmlRetailer for Tillsonburg {
         terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn get_num_pages(&self, response: &str) -> Result<u64, RetailerError> {
         let html = Html::parse_document(response);
         let root = html.root_element();
 
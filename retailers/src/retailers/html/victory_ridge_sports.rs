@@ -1,18 +1,23 @@
 use async_trait::async_trait;
+use common::money::Currency;
 use common::result::{
     base::CrawlResult,
     enums::{Category, RetailerName},
 };
+use common::retailer_info::{Platform, RetailerInfo};
 use crawler::request::{Request, RequestBuilder};
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde::Deserialize;
 use tracing::debug;
 
 use crate::{
     errors::RetailerError,
-    structures::{HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, Retailer},
+    registry::register_retailer,
+    structures::{
+        HtmlRetailer, HtmlRetailerSuper, HtmlSearchQuery, PaginationStrategy, ParsedPage, Retailer,
+    },
     utils::{
-        ecommerce::{WooCommerceBuilder, WooCommerceNested},
+        ecommerce::{WooCommerce, WooCommerceBuilder, WooCommerceNested},
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
 };
@@ -25,6 +30,7 @@ struct ApiResponse {
 }
 
 const MAX_PER_PAGE: &str = "45";
+const CSS_SELECTOR: &str = "div.wd-product-header > h3 > a";
 const URL: &str = "https://victoryridgesports.ca/product-category/{category}/page/{page}/?woo_ajax=1&per_page={max_per_page}";
 
 pub struct VictoryRidgeSports;
@@ -54,10 +60,52 @@ impl VictoryRidgeSports {
 
         Ok(html.select(&selector).next().is_some())
     }
+
+    fn parse_product(
+        &self,
+        product: ElementRef,
+        search_term: &HtmlSearchQuery,
+        woocommerce_helper: &mut WooCommerce,
+    ) -> Result<Option<CrawlResult>, RetailerError> {
+        let add_cart_button =
+            extract_element_from_element(product, "div.wd-product-footer > div > a")?;
+
+        match element_to_text(add_cart_button).to_lowercase().as_str() {
+            "select options" => {
+                let product_url_element = extract_element_from_element(product, CSS_SELECTOR)?;
+                let product_url = element_extract_attr(product_url_element, "href")?;
+
+                woocommerce_helper.enqueue_nested_product(product_url, search_term.category);
+            }
+            "add to cart" => {
+                return woocommerce_helper
+                    .parse_product(product, self.get_retailer_name(), search_term.category)
+                    .map(Some);
+            }
+            _ => {}
+        };
+
+        Ok(None)
+    }
 }
 
 impl HtmlRetailerSuper for VictoryRidgeSports {}
 
+register_retailer!(
+    Html,
+    VictoryRidgeSports,
+    RetailerInfo {
+        display_name: "Victory Ridge Sports",
+        homepage: "https://victoryridgesports.ca",
+        province: None,
+        city: None,
+        platforms: &[Platform::WooCommerce],
+        currency: Currency::CAD,
+        shipping_notes: None,
+        logo_url: None,
+    }
+);
+
 impl Retailer for VictoryRidgeSports {
     fn get_retailer_name(&self) -> RetailerName {
         RetailerName::VictoryRidgeSports
@@ -85,10 +133,10 @@ impl HtmlRetailer for VictoryRidgeSports {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         let parsed_response = serde_json::from_str::<ApiResponse>(response)?;
 
@@ -100,36 +148,16 @@ impl HtmlRetailer for VictoryRidgeSports {
                 .collect::<Vec<_>>()
         };
 
-        let css_selector = "div.wd-product-header > h3 > a";
-
         let mut woocommerce_helper = WooCommerceBuilder::default()
-            .with_product_name_selector(css_selector)
-            .with_product_url_selector(css_selector)
+            .with_product_name_selector(CSS_SELECTOR)
+            .with_product_url_selector(CSS_SELECTOR)
             .build();
 
         for raw_html in products {
             let parsed_html = Html::parse_fragment(&raw_html);
             let product = parsed_html.root_element();
 
-            let add_cart_button =
-                extract_element_from_element(product, "div.wd-product-footer > div > a")?;
-
-            match element_to_text(add_cart_button).to_lowercase().as_str() {
-                "select options" => {
-                    let product_url_element = extract_element_from_element(product, css_selector)?;
-                    let product_url = element_extract_attr(product_url_element, "href")?;
-
-                    woocommerce_helper.enqueue_nested_product(product_url, search_term.category);
-                }
-                "add to cart" => {
-                    results.push(woocommerce_helper.parse_product(
-                        product,
-                        self.get_retailer_name(),
-                        search_term.category,
-                    )?);
-                }
-                _ => {}
-            };
+            results.record(self.parse_product(product, search_term, &mut woocommerce_helper));
         }
 
         results.extend(
@@ -186,13 +214,13 @@ impl HtmlRetailer for VictoryRidgeSports {
         search_terms
     }
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError> {
+    fn is_last_page(&self, response: &str) -> Result<bool, RetailerError> {
         let parsed_response = serde_json::from_str::<ApiResponse>(response)?;
 
-        if parsed_response.next_page.is_empty() || self.is_ending_page(parsed_response)? {
-            return Ok(0);
-        }
+        self.is_ending_page(parsed_response)
+    }
 
-        Ok(u64::MAX)
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::UntilEmpty
     }
 }
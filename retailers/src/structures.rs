@@ -1,14 +1,24 @@
 use async_trait::async_trait;
-use common::result::{
-    base::CrawlResult,
-    enums::{Category, RetailerName},
+use common::{
+    money::Currency,
+    result::{
+        base::CrawlResult,
+        enums::{Category, RetailerName},
+        taxonomy::TaxonomyNode,
+    },
 };
 use crawler::request::Request;
+use tracing::warn;
 
-use crate::errors::RetailerError;
+pub use crate::utils::url::UrlRules;
+use crate::{
+    errors::RetailerError,
+    registry::{RegisteredRetailer, get_retailer_currency},
+    utils::url::canonicalize_url,
+};
 
-pub trait HtmlRetailerSuper: HtmlRetailer + Retailer + Send + Sync {}
-pub trait GqlRetailerSuper: GqlRetailer + Retailer + Send + Sync {}
+pub trait HtmlRetailerSuper: HtmlRetailer + Retailer + RegisteredRetailer + Send + Sync {}
+pub trait GqlRetailerSuper: GqlRetailer + Retailer + RegisteredRetailer + Send + Sync {}
 
 #[async_trait]
 pub trait Retailer {
@@ -17,6 +27,37 @@ pub trait Retailer {
     async fn init(&mut self) -> Result<(), RetailerError> {
         Ok(())
     }
+
+    /// How the retailer's product URLs are canonicalized, on top of the usual cleanup
+    fn get_url_rules(&self) -> UrlRules {
+        UrlRules::default()
+    }
+
+    /// The currency the retailer registered with, for prices that don't say which
+    fn get_currency(&self) -> Currency {
+        get_retailer_currency(self.get_retailer_name())
+    }
+}
+
+/// How a client decides whether there's another page to fetch
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PaginationStrategy {
+    /// `get_num_pages` is re-read from every page, for pagers that only link the
+    /// pages around the current one, so pages are fetched one at a time
+    #[default]
+    PageNumber,
+    /// `get_num_pages` on the first page is the total page count, letting
+    /// the rest of the pages be fetched concurrently
+    PageCount,
+    /// Every page holds `limit` products, anything shorter is the last page
+    OffsetLimit { limit: u64 },
+    /// Keeps going until a page comes back with no products, or `is_last_page` says so
+    UntilEmpty,
+    /// A single request holds every product for the search term
+    SinglePage,
+    /// Every page hands out the token for the next one through `get_pagination_token`,
+    /// only followed by `GqlRetailer`s since their requests are built from the token
+    Cursor,
 }
 
 #[async_trait]
@@ -29,13 +70,26 @@ pub trait HtmlRetailer {
 
     async fn parse_response(
         &self,
-        response: &String,
+        response: &str,
         search_term: &HtmlSearchQuery,
-    ) -> Result<Vec<CrawlResult>, RetailerError>;
+    ) -> Result<ParsedPage, RetailerError>;
 
     fn get_search_terms(&self) -> Vec<HtmlSearchQuery>;
 
-    fn get_num_pages(&self, response: &String) -> Result<u64, RetailerError>;
+    /// Only used by `PaginationStrategy::PageNumber` and `PaginationStrategy::PageCount`
+    fn get_num_pages(&self, _response: &str) -> Result<u64, RetailerError> {
+        Ok(0)
+    }
+
+    /// Only used by `PaginationStrategy::UntilEmpty`, saves requesting
+    /// a page when the response already says there's nothing after it
+    fn is_last_page(&self, _response: &str) -> Result<bool, RetailerError> {
+        Ok(false)
+    }
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::PageNumber
+    }
 }
 
 #[async_trait]
@@ -45,9 +99,91 @@ pub trait GqlRetailer {
         pagination_token: Option<String>,
     ) -> Result<Request, RetailerError>;
 
-    async fn parse_response(&self, response: &str) -> Result<Vec<CrawlResult>, RetailerError>;
+    async fn parse_response(&self, response: &str) -> Result<ParsedPage, RetailerError>;
 
     fn get_pagination_token(&self, response: &str) -> Result<Option<String>, RetailerError>;
+
+    fn get_pagination_strategy(&self) -> PaginationStrategy {
+        PaginationStrategy::Cursor
+    }
+}
+
+/// Products parsed out of a single response. A product that fails to parse
+/// is recorded in `errors` instead of failing the whole page, failures that
+/// affect the entire page should still be returned as an `Err`
+#[derive(Debug, Default)]
+pub struct ParsedPage {
+    pub results: Vec<CrawlResult>,
+    pub errors: Vec<RetailerError>,
+    // products that were intentionally skipped, ie. out of stock
+    pub skipped: u64,
+}
+
+impl ParsedPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, result: CrawlResult) {
+        self.results.push(result);
+    }
+
+    /// Records the outcome of parsing a single product,
+    /// `Ok(None)` is for products that were intentionally skipped
+    pub fn record(
+        &mut self,
+        parsed_product: Result<impl Into<Option<CrawlResult>>, RetailerError>,
+    ) {
+        match parsed_product.map(Into::into) {
+            Ok(Some(result)) => self.results.push(result),
+            Ok(None) => self.skipped += 1,
+            Err(err) => self.push_error(err),
+        }
+    }
+
+    /// Same as `record`, but for a single listing that expands into many products
+    pub fn record_all(&mut self, parsed_products: Result<Vec<CrawlResult>, RetailerError>) {
+        match parsed_products {
+            Ok(results) => self.results.extend(results),
+            Err(err) => self.push_error(err),
+        }
+    }
+
+    pub fn push_error(&mut self, err: RetailerError) {
+        warn!("Skipping product that failed to parse: {err}");
+        self.errors.push(err);
+    }
+
+    /// Swaps every result's URL for its canonical version, resolved against `base_url`,
+    /// and gives it a product ID, results whose URL can't be resolved are moved to `errors`.
+    /// Only the parser knows which results are variants sharing a URL, so the product ID
+    /// stays the same no matter what else ended up on the page
+    pub fn canonicalize(&mut self, base_url: &str, rules: &UrlRules) {
+        for mut result in std::mem::take(&mut self.results) {
+            match canonicalize_url(base_url, &result.url, rules) {
+                Ok(url) => {
+                    result.url = url;
+                    self.results.push(result);
+                }
+                Err(err) => self.push_error(err),
+            }
+        }
+
+        for result in &mut self.results {
+            result.product_id = Some(result.get_product_id(result.is_variant));
+        }
+    }
+
+    pub fn extend(&mut self, other: ParsedPage) {
+        self.results.extend(other.results);
+        self.errors.extend(other.errors);
+        self.skipped += other.skipped;
+    }
+
+    /// Every product listing seen on the page, regardless of whether it made it into `results`
+    pub fn get_listing_count(&self) -> u64 {
+        (self.results.len() + self.errors.len()) as u64 + self.skipped
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -55,3 +191,9 @@ pub struct HtmlSearchQuery {
     pub term: String,
     pub category: Category,
 }
+
+impl HtmlSearchQuery {
+    pub fn get_taxonomy_node(&self) -> Option<TaxonomyNode> {
+        TaxonomyNode::from_search_term(self.category, &self.term)
+    }
+}
@@ -1,7 +1,8 @@
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
 use common::{
     constants::CRAWL_COOLDOWN_SECS,
+    money::{Currency, Money},
     result::{
         base::{CrawlResult, Price},
         enums::{Category, RetailerName},
@@ -14,8 +15,8 @@ use tracing::{debug, error, info};
 
 use crate::{
     errors::RetailerError,
+    structures::ParsedPage,
     utils::{
-        conversions::price_to_cents,
         ecommerce::{
             BigCommerce,
             bigcommerce::structs::{
@@ -38,7 +39,7 @@ pub(crate) trait BigCommerceNested {
         &self,
         site_url: impl Into<String>,
         retailer_name: RetailerName,
-    ) -> Result<Vec<CrawlResult>, RetailerError>;
+    ) -> Result<ParsedPage, RetailerError>;
 
     // TODO: refactor this
     // this is here because alflahertys behaves differently
@@ -54,26 +55,24 @@ pub(crate) trait BigCommerceNested {
 impl BigCommerce {
     /// For nested pricing using the JSON API response
     fn get_price_from_object(api_response: NestedApiResponsePrice) -> Result<Price, RetailerError> {
-        if api_response.without_tax.currency != "CAD" {
+        let Ok(currency) = Currency::from_str(&api_response.without_tax.currency) else {
             let message = format!(
-                "Invalid pricing, API returned non CAD pricing: {}",
+                "Invalid pricing, API returned unknown currency: {}",
                 api_response.without_tax.currency
             );
             error!(message);
             return Err(RetailerError::ApiResponseInvalidShape(message));
         };
 
-        let mut price = Price {
-            regular_price: price_to_cents(api_response.without_tax.value.to_string())?,
-            sale_price: None,
-        };
+        let price = Money::from_f32(api_response.without_tax.value, currency)?;
 
         if let Some(non_sale_price) = api_response.non_sale_price_without_tax {
-            price.sale_price = Some(price.regular_price);
-            price.regular_price = price_to_cents(non_sale_price.value.to_string())?;
+            let non_sale = Money::from_f32(non_sale_price.value, currency)?;
+
+            return Ok(Price::new(non_sale).with_sale_price(price));
         };
 
-        Ok(price)
+        Ok(Price::new(price))
     }
 
     // cursed method that parses JSON "manually" with serde
@@ -246,7 +245,7 @@ impl BigCommerce {
     }
 
     fn get_nested_name(
-        item_name: &String,
+        item_name: &str,
         variants: &Vec<FormValuePair>,
         category: Category,
     ) -> String {
@@ -274,6 +273,86 @@ impl BigCommerce {
 
         name
     }
+
+    /// Parses every in stock variant of a single nested product
+    async fn parse_nested_product(
+        nested_product: &NestedProduct,
+        site_url: &str,
+        cart_url: &str,
+        retailer_name: RetailerName,
+    ) -> Result<Vec<CrawlResult>, RetailerError> {
+        let mut results: Vec<CrawlResult> = Vec::new();
+
+        let request = RequestBuilder::new()
+            .set_url(nested_product.product_url.clone())
+            .build();
+        let result = UnprotectedCrawler::make_web_request(request).await?;
+
+        let product_id = Self::get_product_id(&result.body)?;
+        let api_url = format!("{site_url}/remote/v1/product-attributes/{product_id}");
+
+        let nested_variants = Self::get_models(&result.body, cart_url.to_string())?;
+
+        for variants in nested_variants.form_pairs {
+            let combined_attrs: String = variants
+                .iter()
+                .flat_map(|pair| {
+                    let attr = format!("&{}={}", pair.form_id, pair.form_attr_id);
+                    attr.chars().collect::<Vec<_>>()
+                })
+                .collect();
+            let body = format!("action=add&product_id={product_id}{combined_attrs}");
+
+            debug!("Sending subrequest with {}", body);
+
+            let request = RequestBuilder::new()
+                .set_url(api_url.clone())
+                .set_method(HttpMethod::POST)
+                .set_headers(
+                    [(
+                        "Content-Type".into(),
+                        "application/x-www-form-urlencoded".into(),
+                    )]
+                    .as_ref(),
+                )
+                .set_body(body)
+                .build();
+
+            let result = UnprotectedCrawler::make_web_request(request).await?;
+            let response = serde_json::from_str::<NestedApiResponse>(&result.body)?;
+
+            if !response.data.instock {
+                info!("Skipping out of stock {combined_attrs}");
+                continue;
+            }
+
+            let price = Self::get_price_from_object(response.data.price)?;
+
+            let name =
+                Self::get_nested_name(&nested_product.name, &variants, nested_product.category);
+
+            let image = match response.data.image {
+                Some(image_object) => image_object.get_image(),
+                _ => nested_product.fallback_image_url.clone(),
+            };
+
+            let new_result = CrawlResult::new(
+                name,
+                nested_product.product_url.clone(),
+                price,
+                retailer_name,
+                nested_product.category,
+            )
+            .with_image_url(image)
+            .as_variant();
+
+            results.push(new_result);
+
+            sleep(Duration::from_secs(CRAWL_COOLDOWN_SECS)).await;
+        }
+
+        Ok(results)
+    }
 }
 
 impl BigCommerceNested for BigCommerce {
@@ -315,7 +394,7 @@ impl BigCommerceNested for BigCommerce {
         &self,
         site_url: impl Into<String>,
         retailer_name: RetailerName,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
+    ) -> Result<ParsedPage, RetailerError> {
         let mut site_url = site_url.into();
 
         if site_url.ends_with("/") {
@@ -324,75 +403,13 @@ impl BigCommerceNested for BigCommerce {
 
         let cart_url = format!("{site_url}/cart.php");
 
-        let mut nested_results: Vec<CrawlResult> = Vec::new();
+        let mut nested_results = ParsedPage::new();
 
         for nested_product in &self.parse_queue {
-            let request = RequestBuilder::new()
-                .set_url(nested_product.product_url.clone())
-                .build();
-            let result = UnprotectedCrawler::make_web_request(request).await?;
-
-            let product_id = Self::get_product_id(&result.body)?;
-            let api_url = format!("{site_url}/remote/v1/product-attributes/{product_id}");
-
-            let nested_variants = Self::get_models(&result.body, cart_url.clone())?;
-
-            for variants in nested_variants.form_pairs {
-                let combined_attrs: String = variants
-                    .iter()
-                    .flat_map(|pair| {
-                        let attr = format!("&{}={}", pair.form_id, pair.form_attr_id);
-                        attr.chars().collect::<Vec<_>>()
-                    })
-                    .collect();
-                let body = format!("action=add&product_id={product_id}{combined_attrs}");
-
-                debug!("Sending subrequest with {}", body);
-
-                let request = RequestBuilder::new()
-                    .set_url(api_url.clone())
-                    .set_method(HttpMethod::POST)
-                    .set_headers(
-                        [(
-                            "Content-Type".into(),
-                            "application/x-www-form-urlencoded".into(),
-                        )]
-                        .as_ref(),
-                    )
-                    .set_body(body)
-                    .build();
-
-                let result = UnprotectedCrawler::make_web_request(request).await?;
-                let response = serde_json::from_str::<NestedApiResponse>(&result.body)?;
-
-                if !response.data.instock {
-                    info!("Skipping out of stock {combined_attrs}");
-                    continue;
-                }
-
-                let price = Self::get_price_from_object(response.data.price)?;
-
-                let name =
-                    Self::get_nested_name(&nested_product.name, &variants, nested_product.category);
-
-                let image = match response.data.image {
-                    Some(image_object) => image_object.get_image(),
-                    _ => nested_product.fallback_image_url.clone(),
-                };
-
-                let new_result = CrawlResult::new(
-                    name,
-                    nested_product.product_url.clone(),
-                    price,
-                    retailer_name,
-                    nested_product.category,
-                )
-                .with_image_url(image);
-
-                nested_results.push(new_result);
-
-                sleep(Duration::from_secs(CRAWL_COOLDOWN_SECS)).await;
-            }
+            nested_results.record_all(
+                Self::parse_nested_product(nested_product, &site_url, &cart_url, retailer_name)
+                    .await,
+            );
         }
 
         Ok(nested_results)
@@ -1,13 +1,17 @@
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
 };
 use scraper::{ElementRef, Html, Selector};
 
 use crate::{
     errors::RetailerError,
+    registry::get_retailer_currency,
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         ecommerce::bigcommerce::structs::NestedProduct,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
@@ -24,32 +28,34 @@ impl BigCommerce {
         }
     }
 
-    pub(super) fn parse_price(element: ElementRef) -> Result<Price, RetailerError> {
+    pub(super) fn parse_price(
+        element: ElementRef,
+        currency: Currency,
+    ) -> Result<Price, RetailerError> {
         let main_price_element = extract_element_from_element(
             element,
             "div.price-section.price-section--withoutTax.current-price > span.price",
         )?;
         let main_price_text = element_to_text(main_price_element);
 
-        let mut price = Price {
-            regular_price: price_to_cents(main_price_text)?,
-            sale_price: None,
-        };
+        let main_price = Money::parse_with_default(&main_price_text, currency)?;
 
         if let Ok(non_sale_element) = extract_element_from_element(
             element,
             "div.price-section.price-section--withoutTax.non-sale-price > span.price",
         ) {
-            price.sale_price = Some(price.regular_price);
-
             let non_sale_text = element_to_text(non_sale_element);
-            price.regular_price = price_to_cents(non_sale_text)?;
+
+            return Ok(
+                Price::new(Money::parse_with_default(&non_sale_text, currency)?)
+                    .with_sale_price(main_price),
+            );
         }
 
-        Ok(price)
+        Ok(Price::new(main_price))
     }
 
-    pub(crate) fn parse_max_pages(response: &String) -> Result<u64, RetailerError> {
+    pub(crate) fn parse_max_pages(response: &str) -> Result<u64, RetailerError> {
         let html = Html::parse_document(response);
 
         let selector =
@@ -123,7 +129,7 @@ impl BigCommerce {
         let product_link = Self::get_item_link(element)?;
         let product_name = Self::get_item_name(element)?;
 
-        let price = Self::parse_price(details_body_element)?;
+        let price = Self::parse_price(details_body_element, get_retailer_currency(retailer))?;
 
         let new_result = CrawlResult::new(product_name, product_link, price, retailer, category)
             .with_image_url(image_url);
@@ -10,6 +10,7 @@ use crate::{
     },
 };
 
+#[allow(dead_code)]
 pub(crate) trait BigCommerceSitemap {
     async fn get_search_terms<T: Fn(SitemapEntry) -> Option<HtmlSearchQuery>>(
         base_url: impl Into<String>,
@@ -92,6 +92,7 @@ pub(super) struct JavascriptJsonProducts {
     pub(super) in_stock_attributes: Vec<u64>,
 }
 
+#[allow(dead_code)]
 pub(crate) struct SitemapEntry {
     pub(crate) name: String,
     pub(crate) part: String,
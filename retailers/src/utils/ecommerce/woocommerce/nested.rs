@@ -2,6 +2,7 @@ use std::{collections::HashMap, time::Duration};
 
 use common::{
     constants::CRAWL_COOLDOWN_SECS,
+    money::Money,
     result::{
         base::{CrawlResult, Price},
         enums::{Category, RetailerName},
@@ -13,8 +14,9 @@ use tokio::time::sleep;
 
 use crate::{
     errors::RetailerError,
+    registry::get_retailer_currency,
+    structures::ParsedPage,
     utils::{
-        conversions::price_to_cents,
         ecommerce::{
             WooCommerce,
             woocommerce::structs::{NestedProduct, ProductVariation},
@@ -29,13 +31,13 @@ pub(crate) trait WooCommerceNested {
     async fn parse_nested_products(
         &self,
         retailer_name: RetailerName,
-    ) -> Result<Vec<CrawlResult>, RetailerError>;
+    ) -> Result<ParsedPage, RetailerError>;
 }
 
 impl WooCommerce {
     fn get_nested_product_variations(
-        result: &String,
-        product_url: &String,
+        result: &str,
+        product_url: &str,
     ) -> Result<Vec<ProductVariation>, RetailerError> {
         let html = Html::parse_document(result);
 
@@ -50,7 +52,7 @@ impl WooCommerce {
         )?)
     }
 
-    fn get_nested_product_title(result: &String) -> Result<String, RetailerError> {
+    fn get_nested_product_title(result: &str) -> Result<String, RetailerError> {
         let html = Html::parse_document(result);
         let title = extract_element_from_element(html.root_element(), "h1.product_title")?;
 
@@ -58,7 +60,7 @@ impl WooCommerce {
     }
 
     fn get_nested_product_attribute_name_mapping(
-        result: &String,
+        result: &str,
         variations: &Vec<ProductVariation>,
     ) -> Result<HashMap<String, HashMap<String, String>>, RetailerError> {
         let html = Html::parse_document(result);
@@ -93,7 +95,7 @@ impl WooCommerce {
     // I don't like how this returns a Result<Option<String>>
     // this is "temporary" to fix extra product issue
     fn format_nested_name(
-        product_title: &String,
+        product_title: &str,
         variation: &ProductVariation,
         attribute_mapping: &HashMap<String, HashMap<String, String>>,
     ) -> Result<Option<String>, RetailerError> {
@@ -129,6 +131,64 @@ impl WooCommerce {
 
         Ok(Some(format!("{product_title} - {flat_attr_names}")))
     }
+
+    /// Parses every in stock variation of a single nested product
+    async fn parse_nested_product(
+        nested_product: &NestedProduct,
+        retailer_name: RetailerName,
+    ) -> Result<Vec<CrawlResult>, RetailerError> {
+        let mut results: Vec<CrawlResult> = Vec::new();
+
+        let request = RequestBuilder::new().set_url(&nested_product.url).build();
+        let result = UnprotectedCrawler::make_web_request(request).await?;
+
+        let product_title = Self::get_nested_product_title(&result.body)?;
+
+        let product_variations =
+            Self::get_nested_product_variations(&result.body, &nested_product.url)?;
+
+        let attribute_mapping =
+            Self::get_nested_product_attribute_name_mapping(&result.body, &product_variations)?;
+
+        // variations only come with bare numbers
+        let currency = get_retailer_currency(retailer_name);
+
+        for variation in product_variations {
+            if !variation.is_in_stock {
+                continue;
+            }
+
+            let regular_price = Money::from_f32(variation.display_regular_price, currency)?;
+            let sale_price = Money::from_f32(variation.display_price, currency)?;
+
+            let price = match regular_price == sale_price {
+                true => Price::new(regular_price),
+                false => Price::new(regular_price).with_sale_price(sale_price),
+            };
+
+            let Some(name) =
+                Self::format_nested_name(&product_title, &variation, &attribute_mapping)?
+            else {
+                // none indicating extra product that is not
+                // shown to public
+                continue;
+            };
+
+            let new_result = CrawlResult::new(
+                name,
+                nested_product.url.clone(),
+                price,
+                retailer_name,
+                nested_product.category,
+            )
+            .with_image_url(variation.image.url)
+            .with_sku(variation.variation_id.to_string());
+
+            results.push(new_result);
+        }
+
+        Ok(results)
+    }
 }
 
 impl WooCommerceNested for WooCommerce {
@@ -139,57 +199,11 @@ impl WooCommerceNested for WooCommerce {
     async fn parse_nested_products(
         &self,
         retailer_name: RetailerName,
-    ) -> Result<Vec<CrawlResult>, RetailerError> {
-        let mut results: Vec<CrawlResult> = Vec::new();
+    ) -> Result<ParsedPage, RetailerError> {
+        let mut results = ParsedPage::new();
 
         for nested_product in &self.nested_queue {
-            let request = RequestBuilder::new().set_url(&nested_product.url).build();
-            let result = UnprotectedCrawler::make_web_request(request).await?;
-
-            let product_title = Self::get_nested_product_title(&result.body)?;
-
-            let product_variations =
-                Self::get_nested_product_variations(&result.body, &nested_product.url)?;
-
-            let attribute_mapping =
-                Self::get_nested_product_attribute_name_mapping(&result.body, &product_variations)?;
-
-            for variation in product_variations {
-                if !variation.is_in_stock {
-                    continue;
-                }
-
-                let regular_price = price_to_cents(variation.display_regular_price.to_string())?;
-                let sale_price = price_to_cents(variation.display_price.to_string())?;
-
-                let price = Price {
-                    regular_price,
-                    sale_price: if regular_price == sale_price {
-                        None
-                    } else {
-                        Some(sale_price)
-                    },
-                };
-
-                let Some(name) =
-                    Self::format_nested_name(&product_title, &variation, &attribute_mapping)?
-                else {
-                    // none indicating extra product that is not
-                    // shown to public
-                    continue;
-                };
-
-                let new_result = CrawlResult::new(
-                    name,
-                    nested_product.url.clone(),
-                    price,
-                    retailer_name,
-                    nested_product.category,
-                )
-                .with_image_url(variation.image.url);
-
-                results.push(new_result);
-            }
+            results.record_all(Self::parse_nested_product(nested_product, retailer_name).await);
 
             sleep(Duration::from_secs(CRAWL_COOLDOWN_SECS)).await;
         }
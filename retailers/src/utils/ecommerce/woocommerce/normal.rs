@@ -1,13 +1,17 @@
-use common::result::{
-    base::{CrawlResult, Price},
-    enums::{Category, RetailerName},
+use common::{
+    money::{Currency, Money},
+    result::{
+        base::{CrawlResult, Price},
+        enums::{Category, RetailerName},
+    },
 };
 use scraper::{ElementRef, Html, Selector};
 
 use crate::{
     errors::RetailerError,
+    registry::get_retailer_currency,
     utils::{
-        conversions::{price_to_cents, string_to_u64},
+        conversions::string_to_u64,
         ecommerce::woocommerce::structs::NestedProduct,
         html::{element_extract_attr, element_to_text, extract_element_from_element},
     },
@@ -60,31 +64,31 @@ pub(crate) struct WooCommerce {
 }
 
 impl WooCommerce {
-    fn parse_price(element: ElementRef) -> Result<Price, RetailerError> {
-        let mut price = Price {
-            regular_price: 0,
-            sale_price: None,
-        };
-
+    fn parse_price(element: ElementRef, currency: Currency) -> Result<Price, RetailerError> {
         let regular_non_sale_price =
             extract_element_from_element(element, "span.price > span.amount > bdi");
 
         match regular_non_sale_price {
-            Ok(regular_price_element) => {
-                price.regular_price = price_to_cents(element_to_text(regular_price_element))?;
-            }
+            Ok(regular_price_element) => Ok(Price::new(Money::parse_with_default(
+                &element_to_text(regular_price_element),
+                currency,
+            )?)),
             Err(_) => {
                 let sale_price =
                     extract_element_from_element(element, "span.price > ins > span.amount > bdi")?;
                 let previous_price =
                     extract_element_from_element(element, "span.price > del > span.amount > bdi")?;
 
-                price.regular_price = price_to_cents(element_to_text(previous_price))?;
-                price.sale_price = Some(price_to_cents(element_to_text(sale_price))?);
+                Ok(Price::new(Money::parse_with_default(
+                    &element_to_text(previous_price),
+                    currency,
+                )?)
+                .with_sale_price(Money::parse_with_default(
+                    &element_to_text(sale_price),
+                    currency,
+                )?))
             }
         }
-
-        Ok(price)
     }
 
     pub(crate) fn parse_max_pages(response: &str) -> Result<u64, RetailerError> {
@@ -125,6 +129,15 @@ impl WooCommerce {
         ))
     }
 
+    /// The ID on the add to cart button, not every theme shows the button in listings
+    fn get_product_id(element: ElementRef) -> Option<String> {
+        let button = extract_element_from_element(element, "[data-product_id]").ok()?;
+
+        element_extract_attr(button, "data-product_id")
+            .ok()
+            .filter(|product_id| !product_id.is_empty())
+    }
+
     pub(crate) fn parse_product(
         &self,
         element: ElementRef,
@@ -141,9 +154,14 @@ impl WooCommerce {
 
         let image_url = self.get_image_url(element)?;
 
-        let new_product =
-            CrawlResult::new(name, url, Self::parse_price(element)?, retailer, category)
-                .with_image_url(image_url);
+        let price = Self::parse_price(element, get_retailer_currency(retailer))?;
+
+        let mut new_product =
+            CrawlResult::new(name, url, price, retailer, category).with_image_url(image_url);
+
+        if let Some(product_id) = Self::get_product_id(element) {
+            new_product = new_product.with_sku(product_id);
+        }
 
         Ok(new_product)
     }
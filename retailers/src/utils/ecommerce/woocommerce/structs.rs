@@ -15,6 +15,7 @@ pub(super) struct ProductImage {
 
 #[derive(Deserialize, Debug)]
 pub(super) struct ProductVariation {
+    pub(super) variation_id: u64,
     pub(super) attributes: HashMap<String, String>,
     pub(super) image: ProductImage,
     pub(super) is_in_stock: bool,
@@ -3,3 +3,4 @@ pub(crate) mod ecommerce;
 pub(crate) mod generic_sitemap;
 pub(crate) mod html;
 pub(crate) mod regex;
+pub(crate) mod url;
@@ -0,0 +1,124 @@
+use url::Url;
+
+use crate::errors::RetailerError;
+
+// query params that only say where a visitor came from, or which session they're in
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "gbraid",
+    "wbraid",
+    "dclid",
+    "msclkid",
+    "yclid",
+    "igshid",
+    "srsltid",
+    "mc_cid",
+    "mc_eid",
+    "_ga",
+    "_gl",
+    "ref",
+    "sid",
+    "sessionid",
+    "session_id",
+    "phpsessid",
+    "jsessionid",
+    "oscsid",
+    "zenid",
+    // Shopify's search result tracking
+    "_pos",
+    "_sid",
+    "_ss",
+    "_psq",
+    "_v",
+];
+
+/// Retailer specific tweaks on top of what `canonicalize_url` always does
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UrlRules {
+    /// Query params dropped on top of the usual tracking and session params
+    pub strip_params: &'static [&'static str],
+    /// Turns Shopify's "/collections/ammo/products/x" into "/products/x",
+    /// which is the same product no matter which collection it was found in
+    pub strip_collection_path: bool,
+    /// Only for retailers whose paths are case insensitive
+    pub lowercase_path: bool,
+}
+
+/// Resolves `href` against the retailer's base URL, then strips everything that doesn't
+/// change which product it points to, so the same product always ends up with the same
+/// URL, no matter which page, category or campaign it was found through
+pub(crate) fn canonicalize_url(
+    base_url: &str,
+    href: &str,
+    rules: &UrlRules,
+) -> Result<String, RetailerError> {
+    let invalid =
+        |err: url::ParseError| RetailerError::InvalidUrl(href.to_string(), err.to_string());
+
+    let base = Url::parse(base_url).map_err(invalid)?;
+    let mut url = base.join(href.trim()).map_err(invalid)?;
+
+    if url.scheme() == "http" {
+        let _ = url.set_scheme("https");
+    }
+
+    // the same site with or without www, the base URL's version wins
+    if let Some(base_host) = base.host_str()
+        && url.host_str().is_some_and(|host| {
+            host != base_host
+                && host.trim_start_matches("www.") == base_host.trim_start_matches("www.")
+        })
+    {
+        let _ = url.set_host(Some(base_host));
+    }
+
+    url.set_fragment(None);
+
+    // session IDs tacked onto the path, ie. "/product;jsessionid=abc"
+    let mut path = url.path().split(';').next().unwrap_or_default().to_string();
+
+    if rules.strip_collection_path
+        && path.starts_with("/collections/")
+        && let Some(index) = path.find("/products/")
+    {
+        path = path[index..].to_string();
+    }
+
+    if rules.lowercase_path {
+        path = path.to_lowercase();
+    }
+
+    if path.len() > 1 {
+        path = path.trim_end_matches('/').to_string();
+    }
+
+    url.set_path(&path);
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| {
+            let name = name.to_lowercase();
+
+            !name.starts_with("utm_")
+                && !TRACKING_PARAMS.contains(&name.as_str())
+                && !rules
+                    .strip_params
+                    .iter()
+                    .any(|param| param.eq_ignore_ascii_case(&name))
+        })
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+
+    // the same params in a different order are still the same page
+    params.sort();
+
+    match params.is_empty() {
+        true => url.set_query(None),
+        false => {
+            url.query_pairs_mut().clear().extend_pairs(params);
+        }
+    }
+
+    Ok(url.to_string())
+}